@@ -0,0 +1,110 @@
+use super::super::user_entity::user_model::Entity as UserEntity;
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "auth_events")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i64,
+    pub kind: AuthEventKind,
+    pub outcome: AuthEventOutcome,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Unset when the request could not be tied to an account, e.g. an invalid magic link.
+    pub user_id: Option<Uuid>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    EnumIter,
+    DeriveActiveEnum,
+    Deserialize,
+    Serialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "auth_event_kind")]
+#[serde(rename_all = "snake_case")]
+pub enum AuthEventKind {
+    #[default]
+    #[sea_orm(string_value = "signup")]
+    Signup,
+    #[sea_orm(string_value = "magic_link_sent")]
+    MagicLinkSent,
+    #[sea_orm(string_value = "magic_link_clicked")]
+    MagicLinkClicked,
+    #[sea_orm(string_value = "code_sent")]
+    CodeSent,
+    #[sea_orm(string_value = "code_failed")]
+    CodeFailed,
+    #[sea_orm(string_value = "lockout")]
+    Lockout,
+    #[sea_orm(string_value = "login")]
+    Login,
+    #[sea_orm(string_value = "logout")]
+    Logout,
+    #[sea_orm(string_value = "account_deleted")]
+    AccountDeleted,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    EnumIter,
+    DeriveActiveEnum,
+    Deserialize,
+    Serialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "auth_event_outcome")]
+#[serde(rename_all = "lowercase")]
+pub enum AuthEventOutcome {
+    #[default]
+    #[sea_orm(string_value = "success")]
+    Success,
+    #[sea_orm(string_value = "failure")]
+    Failure,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::super::user_entity::user_model::Entity",
+        from = "Column::UserId",
+        to = "super::super::user_entity::user_model::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<UserEntity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {
+    fn new() -> Self {
+        use sea_orm::Set;
+
+        Self {
+            created_at: Set(Utc::now()),
+            ip: Set(None),
+            user_agent: Set(None),
+            ..ActiveModelTrait::default()
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod auth_event_model;
\ No newline at end of file
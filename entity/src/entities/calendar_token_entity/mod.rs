@@ -0,0 +1 @@
+pub mod calendar_token_model;
\ No newline at end of file
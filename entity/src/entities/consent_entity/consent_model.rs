@@ -0,0 +1,48 @@
+use super::super::legal_document_entity::legal_document_model::DocumentKind;
+use super::super::user_entity::user_model::Entity as UserEntity;
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "consents")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+    pub kind: DocumentKind,
+    pub version: String,
+    pub accepted_at: DateTime<Utc>,
+    pub ip: Option<String>,
+    pub user_id: Uuid,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::super::user_entity::user_model::Entity",
+        from = "Column::UserId",
+        to = "super::super::user_entity::user_model::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<UserEntity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {
+    fn new() -> Self {
+        use sea_orm::Set;
+
+        Self {
+            accepted_at: Set(Utc::now()),
+            ip: Set(None),
+            ..ActiveModelTrait::default()
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod consent_model;
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod data_export_model;
\ No newline at end of file
@@ -0,0 +1,56 @@
+use super::super::user_entity::user_model::Entity as UserEntity;
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "known_devices")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+    /// Hash of the user agent and the IP prefix, see `device_utils`.
+    pub fingerprint: String,
+    pub user_agent: Option<String>,
+    pub ip_prefix: Option<String>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    /// Carried by the "this wasn't me" link of the new device email, which
+    /// can only be followed once since the device goes with it.
+    pub report_nonce: Option<String>,
+    pub user_id: Uuid,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::super::user_entity::user_model::Entity",
+        from = "Column::UserId",
+        to = "super::super::user_entity::user_model::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<UserEntity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {
+    fn new() -> Self {
+        use sea_orm::Set;
+
+        let now = Utc::now();
+        Self {
+            user_agent: Set(None),
+            ip_prefix: Set(None),
+            first_seen_at: Set(now),
+            last_seen_at: Set(now),
+            report_nonce: Set(None),
+            ..ActiveModelTrait::default()
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod known_device_model;
\ No newline at end of file
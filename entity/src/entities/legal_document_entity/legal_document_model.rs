@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "legal_documents")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+    pub kind: DocumentKind,
+    pub version: String,
+    pub url: String,
+    pub published_at: DateTime<Utc>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    EnumIter,
+    DeriveActiveEnum,
+    Deserialize,
+    Serialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "document_kind")]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentKind {
+    #[default]
+    #[sea_orm(string_value = "terms")]
+    Terms,
+    #[sea_orm(string_value = "privacy")]
+    Privacy,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {
+    fn new() -> Self {
+        use sea_orm::Set;
+
+        Self {
+            published_at: Set(Utc::now()),
+            ..ActiveModelTrait::default()
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod legal_document_model;
\ No newline at end of file
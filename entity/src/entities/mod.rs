@@ -1,2 +1,11 @@
 pub mod two_fa_entity;
-pub mod user_entity;
\ No newline at end of file
+pub mod user_entity;
+pub mod rdv_entity;
+pub mod calendar_token_entity;
+
+pub mod session_entity;
+pub mod legal_document_entity;
+pub mod consent_entity;
+pub mod auth_event_entity;
+pub mod known_device_entity;
+pub mod data_export_entity;
\ No newline at end of file
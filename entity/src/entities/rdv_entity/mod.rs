@@ -0,0 +1 @@
+pub mod rdv_model;
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod session_model;
\ No newline at end of file
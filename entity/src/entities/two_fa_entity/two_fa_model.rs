@@ -8,13 +8,23 @@ pub struct Model {
     #[sea_orm(primary_key)]
     #[serde(skip_deserializing)]
     pub id: i32,
-    pub v_e: bool,
-    pub t: i32,
-    pub s: i32,
-    pub c: Option<String>,
-    pub up: Option<i64>,
-    pub ex: i32,
-    pub v_ph: bool,
+    // The serde names are the column names before they were spelled out.
+    #[serde(rename = "v_e", alias = "email_verified")]
+    pub email_verified: bool,
+    #[serde(rename = "t", alias = "tries_left")]
+    pub tries_left: i32,
+    #[serde(rename = "s", alias = "codes_sent")]
+    pub codes_sent: i32,
+    #[serde(rename = "c", alias = "code")]
+    pub code: Option<String>,
+    /// Timestamp in milliseconds, set when too many codes failed.
+    #[serde(rename = "up", alias = "locked_until")]
+    pub locked_until: Option<i64>,
+    /// Grows with each lock, the next one lasts longer.
+    #[serde(rename = "ex", alias = "lock_exponent")]
+    pub lock_exponent: i32,
+    #[serde(rename = "v_ph", alias = "phone_verified")]
+    pub phone_verified: bool,
     #[sea_orm(unique)]
     pub user_id: Uuid,
 }
@@ -42,13 +52,13 @@ impl ActiveModelBehavior for ActiveModel {
         use sea_orm::Set;
 
         Self {
-            v_e: Set(false),
-            t: Set(3),
-            s: Set(0),
-            c: Set(None),
-            up: Set(None),
-            ex: Set(0),
-            v_ph: Set(false),
+            email_verified: Set(false),
+            tries_left: Set(3),
+            codes_sent: Set(0),
+            code: Set(None),
+            locked_until: Set(None),
+            lock_exponent: Set(0),
+            phone_verified: Set(false),
             ..ActiveModelTrait::default()
         }
     }
@@ -20,10 +20,14 @@ pub struct UsersToRdv {
 pub struct PartialUsersToRdv {
     #[serde(skip_deserializing)]
     pub id: Uuid,
-    pub f: String,
-    pub l: String,
-    pub e: String,
-    pub ph: String,
+    #[serde(rename = "f", alias = "first_name")]
+    pub first_name: String,
+    #[serde(rename = "l", alias = "last_name")]
+    pub last_name: String,
+    #[serde(rename = "e", alias = "email")]
+    pub email: String,
+    #[serde(rename = "ph", alias = "phone")]
+    pub phone: String,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Default, DeriveEntityModel, Deserialize, Serialize)]
@@ -32,18 +36,29 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     #[serde(skip_deserializing)]
     pub id: Uuid,
-    pub av: Option<String>,
-    pub f: String,
-    pub l: String,
+    // The serde names are the column names before they were spelled out.
+    #[serde(rename = "av", alias = "avatar")]
+    pub avatar: Option<String>,
+    #[serde(rename = "f", alias = "first_name")]
+    pub first_name: String,
+    #[serde(rename = "l", alias = "last_name")]
+    pub last_name: String,
     #[sea_orm(unique)]
-    pub e: String,
+    #[serde(rename = "e", alias = "email")]
+    pub email: String,
     #[sea_orm(unique)]
-    pub ph: String,
-    pub t: bool,
-    pub pv: bool,
+    #[serde(rename = "ph", alias = "phone")]
+    pub phone: String,
+    #[serde(rename = "t", alias = "terms")]
+    pub terms: bool,
+    #[serde(rename = "pv", alias = "privacy")]
+    pub privacy: bool,
     pub two_fa: bool,
-    pub lg: Language,
+    #[serde(rename = "lg", alias = "language")]
+    pub language: Language,
+    pub role: Role,
     pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(
@@ -64,6 +79,18 @@ pub enum Language {
     It,
 }
 
+#[derive(
+    Debug, Clone, PartialEq, Eq, Default, EnumIter, DeriveActiveEnum, Deserialize, Serialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "role")]
+pub enum Role {
+    #[default]
+    #[sea_orm(string_value = "pro")]
+    Pro,
+    #[sea_orm(string_value = "admin")]
+    Admin,
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_one = "super::super::two_fa_entity::two_fa_model::Entity")]
@@ -83,10 +110,12 @@ impl ActiveModelBehavior for ActiveModel {
 
         Self {
             id: Set(Uuid::new_v4()),
-            av: Set(None),
+            avatar: Set(None),
             two_fa: Set(true),
-            lg: Set(Language::Fr),
+            language: Set(Language::Fr),
+            role: Set(Role::Pro),
             created_at: Set(Utc::now()),
+            deleted_at: Set(None),
             ..ActiveModelTrait::default()
         }
     }
@@ -0,0 +1,24 @@
+use std::env;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppEnv {
+    Development,
+    Production,
+}
+
+impl AppEnv {
+    /// `APP_ENV` is `development` (or `dev`) on a developer machine, anything
+    /// else counts as production so destructive commands stay opt-in.
+    pub fn from_env() -> Self {
+        match env::var("APP_ENV") {
+            Ok(value) if matches!(value.trim().to_lowercase().as_str(), "development" | "dev") => {
+                AppEnv::Development
+            }
+            _ => AppEnv::Production,
+        }
+    }
+
+    pub fn is_development(&self) -> bool {
+        *self == AppEnv::Development
+    }
+}
@@ -1,7 +1,26 @@
 pub use sea_orm_migration::prelude::*;
 
+pub mod app_env;
+pub mod lock;
+
 mod m20240121_140152_users_table;
 mod m20240121_140719_two_fa_table;
+mod m20240302_101200_rdv_table;
+mod m20240302_101500_calendar_token_table;
+mod m20240310_090000_normalize_users_phone;
+mod m20240316_090000_users_role;
+mod m20240318_090000_users_soft_delete;
+mod m20240320_090000_consents_table;
+mod m20240322_090000_auth_events_table;
+mod m20240324_090000_known_devices_table;
+mod m20240326_090000_lookup_indexes;
+mod m20240327_090000_seed_dev_accounts;
+mod m20240328_090000_readable_column_names;
+mod m20240330_090000_users_email_lower_key;
+mod m20240401_090000_users_listing_indexes;
+mod m20240402_090000_known_devices_report_nonce;
+mod m20240403_090000_two_fa_user_id_fkey;
+mod m20240404_090000_data_exports_table;
 
 pub struct Migrator;
 
@@ -11,6 +30,22 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20240121_140152_users_table::Migration),
             Box::new(m20240121_140719_two_fa_table::Migration),
+            Box::new(m20240302_101200_rdv_table::Migration),
+            Box::new(m20240302_101500_calendar_token_table::Migration),
+            Box::new(m20240310_090000_normalize_users_phone::Migration),
+            Box::new(m20240316_090000_users_role::Migration),
+            Box::new(m20240318_090000_users_soft_delete::Migration),
+            Box::new(m20240320_090000_consents_table::Migration),
+            Box::new(m20240322_090000_auth_events_table::Migration),
+            Box::new(m20240324_090000_known_devices_table::Migration),
+            Box::new(m20240326_090000_lookup_indexes::Migration),
+            Box::new(m20240327_090000_seed_dev_accounts::Migration),
+            Box::new(m20240328_090000_readable_column_names::Migration),
+            Box::new(m20240330_090000_users_email_lower_key::Migration),
+            Box::new(m20240401_090000_users_listing_indexes::Migration),
+            Box::new(m20240402_090000_known_devices_report_nonce::Migration),
+            Box::new(m20240403_090000_two_fa_user_id_fkey::Migration),
+            Box::new(m20240404_090000_data_exports_table::Migration),
         ]
     }
 }
@@ -0,0 +1,48 @@
+use sea_orm_migration::{
+    sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, Statement},
+    DbErr, MigratorTrait,
+};
+
+use crate::Migrator;
+
+/// Key of the Postgres advisory lock held while migrating, "focusmig" in ASCII.
+pub const MIGRATION_LOCK_KEY: i64 = 0x666f_6375_736d_6967;
+
+/// Advisory locks belong to a session, so migrations get a pool of a single
+/// connection: the lock, the migrations and the unlock share it.
+pub async fn connect_for_migrations(url: &str, schema: &str) -> Result<DatabaseConnection, DbErr> {
+    let mut opt = ConnectOptions::new(url);
+    opt.max_connections(1)
+        .min_connections(1)
+        .set_schema_search_path(schema);
+
+    Database::connect(opt).await
+}
+
+/// Waits while another instance holds the lock.
+pub async fn acquire_migration_lock(db: &DatabaseConnection) -> Result<(), DbErr> {
+    lock_statement(db, "SELECT pg_advisory_lock($1)").await
+}
+
+pub async fn release_migration_lock(db: &DatabaseConnection) -> Result<(), DbErr> {
+    lock_statement(db, "SELECT pg_advisory_unlock($1)").await
+}
+
+/// Applies the pending migrations under the lock, an instance starting while
+/// another one migrates waits for it and then finds nothing to apply.
+pub async fn up_with_lock(db: &DatabaseConnection) -> Result<(), DbErr> {
+    acquire_migration_lock(db).await?;
+    let migrated = Migrator::up(db, None).await;
+    release_migration_lock(db).await?;
+    migrated
+}
+
+async fn lock_statement(db: &DatabaseConnection, sql: &str) -> Result<(), DbErr> {
+    db.query_one(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        sql,
+        [MIGRATION_LOCK_KEY.into()],
+    ))
+    .await
+    .map(|_| ())
+}
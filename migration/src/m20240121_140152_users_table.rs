@@ -1,6 +1,4 @@
-use entity::entities::user_entity::user_model::Entity as UserEntity;
-use sea_orm_migration::{prelude::*, sea_query::extension::postgres::Type, sea_orm::Schema};
-use sea_orm::{EnumIter, Iterable};
+use sea_orm_migration::{prelude::*, sea_query::extension::postgres::Type};
 
 #[derive(DeriveMigrationName)]
 pub struct Migration;
@@ -9,45 +7,59 @@ pub struct Migration;
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
         manager
-        .create_type(
-            Type::create()
-                .as_enum(Language::Table)
-                .values(Language::iter().skip(1))
-                .to_owned(),
-        )
-        .await?;
-        let schema = Schema::new(sea_orm::DatabaseBackend::Postgres);
+            .create_type(
+                Type::create()
+                    .as_enum(Language::Enum)
+                    .values(Language::VALUES)
+                    .to_owned(),
+            )
+            .await?;
 
         manager
             .create_table(
-                schema.create_table_from_entity(UserEntity),
-               /*  Table::create()
+                Table::create()
                     .table(Users::Table)
-                        .if_not_exists()
-                        .col(ColumnDef::new(Users::Id).uuid().not_null().primary_key())
-                        .col(ColumnDef::new(Users::Avatar).string())
-                        .col(ColumnDef::new(Users::FirstName).string().not_null())
-                        .col(ColumnDef::new(Users::LastName).string().not_null())
-                        .col(ColumnDef::new(Users::Email).string().not_null().unique_key())
-                        .col(ColumnDef::new(Users::Phone).string().not_null().unique_key())
-                        .col(ColumnDef::new(Users::Terms).boolean().not_null())
-                        .col(ColumnDef::new(Users::Privacy).boolean().not_null())
-                        .col(ColumnDef::new(Users::TwoFa).boolean().not_null())
-                        .col(
-                            ColumnDef::new(Users::Language)
-                                .enumeration(Language::Table, Language::iter().skip(1)),
-                        )
-                        .col(ColumnDef::new(Users::CreatedAt).timestamp().not_null())
-                        .to_owned(), */
+                    .col(ColumnDef::new(Users::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Users::Avatar).string())
+                    .col(ColumnDef::new(Users::FirstName).string().not_null())
+                    .col(ColumnDef::new(Users::LastName).string().not_null())
+                    .col(
+                        ColumnDef::new(Users::Email)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Users::Phone)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Users::Terms).boolean().not_null())
+                    .col(ColumnDef::new(Users::Privacy).boolean().not_null())
+                    .col(ColumnDef::new(Users::TwoFa).boolean().not_null())
+                    .col(
+                        ColumnDef::new(Users::Language)
+                            .enumeration(Language::Enum, Language::VALUES)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Users::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
             )
-            .await?;
-
-        Ok(())
+            .await
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
         manager
             .drop_table(Table::drop().table(Users::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(Language::Enum).to_owned())
             .await
     }
 }
@@ -79,9 +91,10 @@ pub enum Users {
     CreatedAt,
 }
 
-#[derive(Iden, EnumIter)]
+#[derive(Iden, Clone, Copy)]
 pub enum Language {
-    Table,
+    #[iden = "language"]
+    Enum,
     #[iden = "fr"]
     Fr,
     #[iden = "en"]
@@ -94,4 +107,12 @@ pub enum Language {
     It,
 }
 
-
+impl Language {
+    const VALUES: [Language; 5] = [
+        Language::Fr,
+        Language::En,
+        Language::Es,
+        Language::De,
+        Language::It,
+    ];
+}
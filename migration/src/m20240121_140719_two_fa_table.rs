@@ -1,7 +1,4 @@
-use sea_orm_migration::{prelude::*, sea_orm::Schema};
-
-use entity::entities::two_fa_entity::two_fa_model::Entity as TwoFaEntity;
-
+use sea_orm_migration::prelude::*;
 
 #[derive(DeriveMigrationName)]
 pub struct Migration;
@@ -9,15 +6,38 @@ pub struct Migration;
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        let schema = Schema::new(sea_orm::DatabaseBackend::Postgres);
-
         manager
             .create_table(
-                schema.create_table_from_entity(TwoFaEntity)
+                Table::create()
+                    .table(TwoFa::Table)
+                    .col(
+                        ColumnDef::new(TwoFa::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TwoFa::VE).boolean().not_null())
+                    .col(ColumnDef::new(TwoFa::T).integer().not_null())
+                    .col(ColumnDef::new(TwoFa::S).integer().not_null())
+                    .col(ColumnDef::new(TwoFa::C).string())
+                    .col(ColumnDef::new(TwoFa::Up).big_integer())
+                    .col(ColumnDef::new(TwoFa::Ex).integer().not_null())
+                    .col(ColumnDef::new(TwoFa::VPh).boolean().not_null())
+                    .col(ColumnDef::new(TwoFa::UserId).uuid().not_null().unique_key())
+                    // The name sea-orm gave it, renamed by a later migration.
+                    // The name sea-orm gave it, a later migration renames it.
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-two_fa-user_id")
+                            .from(TwoFa::Table, TwoFa::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
             )
-            .await?;
-
-        Ok(())
+            .await
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
@@ -30,4 +50,21 @@ impl MigrationTrait for Migration {
 #[derive(Iden)]
 enum TwoFa {
     Table,
+    Id,
+    #[iden = "v_e"]
+    VE,
+    T,
+    S,
+    C,
+    Up,
+    Ex,
+    #[iden = "v_ph"]
+    VPh,
+    UserId,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
 }
@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Rdv::Table)
+                    .col(ColumnDef::new(Rdv::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Rdv::Title).string().not_null())
+                    .col(ColumnDef::new(Rdv::StartAt).big_integer().not_null())
+                    .col(ColumnDef::new(Rdv::EndAt).big_integer().not_null())
+                    .col(ColumnDef::new(Rdv::Address).string())
+                    .col(ColumnDef::new(Rdv::Seq).integer().not_null())
+                    .col(ColumnDef::new(Rdv::Cancelled).boolean().not_null())
+                    .col(
+                        ColumnDef::new(Rdv::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Rdv::ProId).uuid().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("rdv_pro_id_fkey")
+                            .from(Rdv::Table, Rdv::ProId)
+                            .to(Users::Table, Users::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_rdv_pro_id_start_at")
+                    .table(Rdv::Table)
+                    .col(Rdv::ProId)
+                    .col(Rdv::StartAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Rdv::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Rdv {
+    Table,
+    Id,
+    Title,
+    StartAt,
+    EndAt,
+    Address,
+    Seq,
+    Cancelled,
+    UpdatedAt,
+    ProId,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
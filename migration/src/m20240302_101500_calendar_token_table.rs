@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CalendarToken::Table)
+                    .col(
+                        ColumnDef::new(CalendarToken::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(CalendarToken::Nonce).string().not_null())
+                    .col(
+                        ColumnDef::new(CalendarToken::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CalendarToken::UserId)
+                            .uuid()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("calendar_token_user_id_fkey")
+                            .from(CalendarToken::Table, CalendarToken::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CalendarToken::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum CalendarToken {
+    Table,
+    Id,
+    Nonce,
+    CreatedAt,
+    UserId,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,29 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE users SET ph = '+33' || substr(ph, 2) WHERE ph ~ '^0[1-9][0-9]{8}$'",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE users SET ph = '0' || substr(ph, 4) WHERE ph ~ '^\\+33[1-9][0-9]{8}$'",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
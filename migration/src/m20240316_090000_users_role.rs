@@ -0,0 +1,68 @@
+use sea_orm_migration::{prelude::*, sea_query::extension::postgres::Type};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(Role::Enum)
+                    .values(Role::VALUES)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(
+                        ColumnDef::new(Users::Role)
+                            .enumeration(Role::Enum, Role::VALUES)
+                            .not_null()
+                            .default("pro"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::Role)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(Role::Enum).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    #[iden = "role"]
+    Role,
+}
+
+#[derive(Iden, Clone, Copy)]
+pub enum Role {
+    #[iden = "role"]
+    Enum,
+    #[iden = "pro"]
+    Pro,
+    #[iden = "admin"]
+    Admin,
+}
+
+impl Role {
+    const VALUES: [Role; 2] = [Role::Pro, Role::Admin];
+}
@@ -0,0 +1,166 @@
+use sea_orm_migration::{prelude::*, sea_query::extension::postgres::Type};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(DocumentKind::Enum)
+                    .values(DocumentKind::VALUES)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(LegalDocuments::Table)
+                    .col(
+                        ColumnDef::new(LegalDocuments::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(LegalDocuments::Kind)
+                            .enumeration(DocumentKind::Enum, DocumentKind::VALUES)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LegalDocuments::Version).string().not_null())
+                    .col(ColumnDef::new(LegalDocuments::Url).string().not_null())
+                    .col(
+                        ColumnDef::new(LegalDocuments::PublishedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("legal_documents_kind_version_key")
+                    .table(LegalDocuments::Table)
+                    .col(LegalDocuments::Kind)
+                    .col(LegalDocuments::Version)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Consents::Table)
+                    .col(
+                        ColumnDef::new(Consents::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Consents::Kind)
+                            .enumeration(DocumentKind::Enum, DocumentKind::VALUES)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Consents::Version).string().not_null())
+                    .col(
+                        ColumnDef::new(Consents::AcceptedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Consents::Ip).string())
+                    .col(ColumnDef::new(Consents::UserId).uuid().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("consents_user_id_fkey")
+                            .from(Consents::Table, Consents::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        // The documents accepted so far through the `t` and `pv` checkboxes.
+        db.execute_unprepared(
+            "INSERT INTO legal_documents (kind, version, url, published_at) VALUES \
+             ('terms', '2024-01-21', '/legal/terms/2024-01-21', '2024-01-21T00:00:00Z'), \
+             ('privacy', '2024-01-21', '/legal/privacy/2024-01-21', '2024-01-21T00:00:00Z')",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "INSERT INTO consents (kind, version, accepted_at, ip, user_id) \
+             SELECT 'terms', '2024-01-21', created_at, NULL, id FROM users WHERE t \
+             UNION ALL \
+             SELECT 'privacy', '2024-01-21', created_at, NULL, id FROM users WHERE pv",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Consents::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(LegalDocuments::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(DocumentKind::Enum).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum LegalDocuments {
+    Table,
+    Id,
+    Kind,
+    Version,
+    Url,
+    PublishedAt,
+}
+
+#[derive(Iden)]
+enum Consents {
+    Table,
+    Id,
+    Kind,
+    Version,
+    AcceptedAt,
+    Ip,
+    UserId,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden, Clone, Copy)]
+enum DocumentKind {
+    #[iden = "document_kind"]
+    Enum,
+    Terms,
+    Privacy,
+}
+
+impl DocumentKind {
+    const VALUES: [DocumentKind; 2] = [DocumentKind::Terms, DocumentKind::Privacy];
+}
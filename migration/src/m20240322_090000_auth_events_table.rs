@@ -0,0 +1,152 @@
+use sea_orm_migration::{prelude::*, sea_query::extension::postgres::Type};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(AuthEventKind::Enum)
+                    .values(AuthEventKind::VALUES)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(AuthEventOutcome::Enum)
+                    .values(AuthEventOutcome::VALUES)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuthEvents::Table)
+                    .col(
+                        ColumnDef::new(AuthEvents::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AuthEvents::Kind)
+                            .enumeration(AuthEventKind::Enum, AuthEventKind::VALUES)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AuthEvents::Outcome)
+                            .enumeration(AuthEventOutcome::Enum, AuthEventOutcome::VALUES)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AuthEvents::Ip).string())
+                    .col(ColumnDef::new(AuthEvents::UserAgent).string())
+                    .col(
+                        ColumnDef::new(AuthEvents::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AuthEvents::UserId).uuid())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("auth_events_user_id_fkey")
+                            .from(AuthEvents::Table, AuthEvents::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("auth_events_user_id_created_at_idx")
+                    .table(AuthEvents::Table)
+                    .col(AuthEvents::UserId)
+                    .col(AuthEvents::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuthEvents::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(AuthEventOutcome::Enum).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(AuthEventKind::Enum).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum AuthEvents {
+    Table,
+    Id,
+    Kind,
+    Outcome,
+    Ip,
+    UserAgent,
+    CreatedAt,
+    UserId,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden, Clone, Copy)]
+enum AuthEventKind {
+    #[iden = "auth_event_kind"]
+    Enum,
+    Signup,
+    MagicLinkSent,
+    MagicLinkClicked,
+    CodeSent,
+    CodeFailed,
+    Lockout,
+    Login,
+    Logout,
+    AccountDeleted,
+}
+
+impl AuthEventKind {
+    const VALUES: [AuthEventKind; 9] = [
+        AuthEventKind::Signup,
+        AuthEventKind::MagicLinkSent,
+        AuthEventKind::MagicLinkClicked,
+        AuthEventKind::CodeSent,
+        AuthEventKind::CodeFailed,
+        AuthEventKind::Lockout,
+        AuthEventKind::Login,
+        AuthEventKind::Logout,
+        AuthEventKind::AccountDeleted,
+    ];
+}
+
+#[derive(Iden, Clone, Copy)]
+enum AuthEventOutcome {
+    #[iden = "auth_event_outcome"]
+    Enum,
+    Success,
+    Failure,
+}
+
+impl AuthEventOutcome {
+    const VALUES: [AuthEventOutcome; 2] = [AuthEventOutcome::Success, AuthEventOutcome::Failure];
+}
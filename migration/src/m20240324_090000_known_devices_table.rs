@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(KnownDevices::Table)
+                    .col(
+                        ColumnDef::new(KnownDevices::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(KnownDevices::Fingerprint)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(KnownDevices::UserAgent).string())
+                    .col(ColumnDef::new(KnownDevices::IpPrefix).string())
+                    .col(
+                        ColumnDef::new(KnownDevices::FirstSeenAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(KnownDevices::LastSeenAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(KnownDevices::UserId).uuid().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("known_devices_user_id_fkey")
+                            .from(KnownDevices::Table, KnownDevices::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("known_devices_user_id_fingerprint_key")
+                    .table(KnownDevices::Table)
+                    .col(KnownDevices::UserId)
+                    .col(KnownDevices::Fingerprint)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(KnownDevices::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum KnownDevices {
+    Table,
+    Id,
+    Fingerprint,
+    UserAgent,
+    IpPrefix,
+    FirstSeenAt,
+    LastSeenAt,
+    UserId,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .name("sessions_user_id_idx")
+                    .table(Sessions::Table)
+                    .col(Sessions::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("consents_user_id_idx")
+                    .table(Consents::Table)
+                    .col(Consents::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Scanned by the purge of the soft-deleted accounts.
+        manager
+            .create_index(
+                Index::create()
+                    .name("users_deleted_at_idx")
+                    .table(Users::Table)
+                    .col(Users::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for name in [
+            "users_deleted_at_idx",
+            "consents_user_id_idx",
+            "sessions_user_id_idx",
+        ] {
+            manager
+                .drop_index(Index::drop().name(name).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Sessions {
+    Table,
+    UserId,
+}
+
+#[derive(Iden)]
+enum Consents {
+    Table,
+    UserId,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    DeletedAt,
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+use crate::app_env::AppEnv;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Ids of the seeded accounts, `down` removes them with their rows in the
+/// tables cascading from `users`.
+const SEEDED_IDS: &str = "'00000000-0000-4000-8000-000000000001', \
+                          '00000000-0000-4000-8000-000000000002', \
+                          '00000000-0000-4000-8000-000000000003'";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Test accounts to log in with on a developer machine, the migration is
+    /// recorded but inserts nothing unless `APP_ENV=development`.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !AppEnv::from_env().is_development() {
+            return Ok(());
+        }
+
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            "INSERT INTO users (id, av, f, l, e, ph, t, pv, two_fa, lg, role, created_at) VALUES \
+             ('00000000-0000-4000-8000-000000000001', NULL, 'Test', 'Pro', \
+              'pro@focus.test', '+33600000001', true, true, true, 'fr', 'pro', now()), \
+             ('00000000-0000-4000-8000-000000000002', NULL, 'Test', 'Pro Bis', \
+              'pro.bis@focus.test', '+33600000002', true, true, true, 'en', 'pro', now()), \
+             ('00000000-0000-4000-8000-000000000003', NULL, 'Test', 'Admin', \
+              'admin@focus.test', '+33600000003', true, true, true, 'fr', 'admin', now())",
+        )
+        .await?;
+
+        db.execute_unprepared(&format!(
+            "INSERT INTO two_fa (v_e, t, s, c, up, ex, v_ph, user_id) \
+             SELECT true, 3, 0, NULL, NULL, 0, true, id FROM users WHERE id IN ({})",
+            SEEDED_IDS
+        ))
+        .await?;
+
+        db.execute_unprepared(&format!(
+            "INSERT INTO consents (kind, version, accepted_at, ip, user_id) \
+             SELECT d.kind, d.version, now(), NULL, u.id \
+             FROM users u CROSS JOIN legal_documents d WHERE u.id IN ({})",
+            SEEDED_IDS
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!("DELETE FROM users WHERE id IN ({})", SEEDED_IDS))
+            .await?;
+
+        Ok(())
+    }
+}
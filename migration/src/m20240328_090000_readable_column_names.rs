@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Column renames as `(table, old name, new name)`.
+const RENAMES: [(&str, &str, &str); 15] = [
+    ("users", "av", "avatar"),
+    ("users", "f", "first_name"),
+    ("users", "l", "last_name"),
+    ("users", "e", "email"),
+    ("users", "ph", "phone"),
+    ("users", "t", "terms"),
+    ("users", "pv", "privacy"),
+    ("users", "lg", "language"),
+    ("two_fa", "v_e", "email_verified"),
+    ("two_fa", "t", "tries_left"),
+    ("two_fa", "s", "codes_sent"),
+    ("two_fa", "c", "code"),
+    ("two_fa", "up", "locked_until"),
+    ("two_fa", "ex", "lock_exponent"),
+    ("two_fa", "v_ph", "phone_verified"),
+];
+
+/// Unique constraints named after their column by Postgres.
+const CONSTRAINTS: [(&str, &str, &str); 2] = [
+    ("users", "users_e_key", "users_email_key"),
+    ("users", "users_ph_key", "users_phone_key"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (table, from, to) in RENAMES {
+            rename_column(manager, table, from, to).await?;
+        }
+
+        for (table, from, to) in CONSTRAINTS {
+            rename_constraint(manager, table, from, to).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (table, from, to) in CONSTRAINTS.into_iter().rev() {
+            rename_constraint(manager, table, to, from).await?;
+        }
+
+        for (table, from, to) in RENAMES.into_iter().rev() {
+            rename_column(manager, table, to, from).await?;
+        }
+
+        Ok(())
+    }
+}
+
+async fn rename_column(
+    manager: &SchemaManager<'_>,
+    table: &str,
+    from: &str,
+    to: &str,
+) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Alias::new(table))
+                .rename_column(Alias::new(from), Alias::new(to))
+                .to_owned(),
+        )
+        .await
+}
+
+async fn rename_constraint(
+    manager: &SchemaManager<'_>,
+    table: &str,
+    from: &str,
+    to: &str,
+) -> Result<(), DbErr> {
+    manager
+        .get_connection()
+        .execute_unprepared(&format!(
+            "ALTER TABLE \"{}\" RENAME CONSTRAINT \"{}\" TO \"{}\"",
+            table, from, to
+        ))
+        .await?;
+
+    Ok(())
+}
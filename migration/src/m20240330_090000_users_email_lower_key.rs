@@ -0,0 +1,57 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, Statement},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Stops before changing anything when two users only differ by the case
+    /// of their email, they have to be merged or renamed by hand first.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let duplicates = db
+            .query_all(Statement::from_string(
+                db.get_database_backend(),
+                "SELECT lower(trim(email)) AS email, string_agg(id::text, ', ') AS ids \
+                 FROM users GROUP BY lower(trim(email)) HAVING count(*) > 1 ORDER BY 1",
+            ))
+            .await?
+            .iter()
+            .map(|row| {
+                Ok(format!(
+                    "{} (users {})",
+                    row.try_get::<String>("", "email")?,
+                    row.try_get::<String>("", "ids")?
+                ))
+            })
+            .collect::<Result<Vec<String>, DbErr>>()?;
+
+        if !duplicates.is_empty() {
+            return Err(DbErr::Migration(format!(
+                "Emails used by several users once lowercased: {}",
+                duplicates.join("; ")
+            )));
+        }
+
+        db.execute_unprepared(
+            "UPDATE users SET email = lower(trim(email)) WHERE email <> lower(trim(email))",
+        )
+        .await?;
+
+        db.execute_unprepared("CREATE UNIQUE INDEX users_email_lower_key ON users (lower(email))")
+            .await?;
+
+        Ok(())
+    }
+
+    /// The emails stay lowercased, their original case is not kept.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("users_email_lower_key").to_owned())
+            .await
+    }
+}
@@ -0,0 +1,31 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// `two_fa` was created from its entity, which names the key after the
+    /// table and the column. The other tables use the Postgres default.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"ALTER TABLE two_fa RENAME CONSTRAINT "fk-two_fa-user_id" TO two_fa_user_id_fkey"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"ALTER TABLE two_fa RENAME CONSTRAINT two_fa_user_id_fkey TO "fk-two_fa-user_id""#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,6 +1,101 @@
-use sea_orm_migration::prelude::*;
+use std::{error::Error, process::exit};
+
+use clap::{Parser, Subcommand};
+use dotenv::dotenv;
+use migration::{
+    app_env::AppEnv,
+    lock::{acquire_migration_lock, connect_for_migrations, release_migration_lock},
+    Migrator,
+};
+use sea_orm_cli::{run_migrate_generate, MigrateSubcommands};
+use sea_orm_migration::cli::run_migrate;
+
+#[derive(Parser)]
+#[command(version, about = "Database migrations of the API")]
+struct Cli {
+    #[arg(short = 'v', long, global = true, help = "Show debug messages")]
+    verbose: bool,
+
+    #[arg(
+        short = 'u',
+        long,
+        global = true,
+        env = "DATABASE_URL",
+        help = "Database URL"
+    )]
+    database_url: Option<String>,
+
+    #[arg(
+        short = 's',
+        long,
+        global = true,
+        env = "DATABASE_SCHEMA",
+        default_value = "public",
+        help = "Database schema"
+    )]
+    database_schema: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Apply the pending migrations
+    Up {
+        #[arg(short, long, help = "Number of pending migrations to apply")]
+        num: Option<u32>,
+    },
+    /// Roll back the last applied migrations
+    Down {
+        #[arg(
+            short,
+            long,
+            default_value_t = 1,
+            help = "Number of applied migrations to roll back"
+        )]
+        num: u32,
+    },
+    /// Show which migrations are applied
+    Status,
+    /// Drop every table then apply all migrations, only with APP_ENV=development
+    Fresh,
+    /// Generate a new migration file in `migration/src`
+    Generate { name: String },
+}
 
 #[async_std::main]
 async fn main() {
-    cli::run_cli(migration::Migrator).await;
+    dotenv().ok();
+    let cli = Cli::parse();
+
+    if let Err(err) = run(cli).await {
+        eprintln!("{}", err);
+        exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let command = match cli.command {
+        Command::Generate { name } => {
+            return run_migrate_generate(env!("CARGO_MANIFEST_DIR"), &name, true);
+        }
+        Command::Fresh if !AppEnv::from_env().is_development() => {
+            return Err("`fresh` drops every table, it only runs with APP_ENV=development".into());
+        }
+        Command::Fresh => MigrateSubcommands::Fresh,
+        Command::Up { num } => MigrateSubcommands::Up { num },
+        Command::Down { num } => MigrateSubcommands::Down { num },
+        Command::Status => MigrateSubcommands::Status,
+    };
+
+    let url = cli.database_url.ok_or("DATABASE_URL is not set")?;
+    let db = connect_for_migrations(&url, &cli.database_schema).await?;
+
+    // A server starting with `--migrate` meanwhile waits for the command.
+    acquire_migration_lock(&db).await?;
+    let result = run_migrate(Migrator, &db, Some(command), cli.verbose).await;
+    release_migration_lock(&db).await?;
+
+    result
 }
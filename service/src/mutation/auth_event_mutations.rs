@@ -0,0 +1,20 @@
+use ::entity::entities::auth_event_entity::{
+    auth_event_model, auth_event_model::Entity as AuthEventEntity,
+};
+use sea_orm::*;
+use tracing::instrument;
+
+pub struct AuthEventMutation;
+
+impl AuthEventMutation {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn create_auth_event(
+        db: &DbConn,
+        form_data: auth_event_model::ActiveModel,
+    ) -> Result<(), DbErr> {
+        AuthEventEntity::insert(form_data)
+            .exec_without_returning(db)
+            .await
+            .map(|_| ())
+    }
+}
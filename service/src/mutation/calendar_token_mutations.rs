@@ -0,0 +1,37 @@
+use ::entity::entities::calendar_token_entity::{
+    calendar_token_model, calendar_token_model::Entity as CalendarTokenEntity,
+};
+use sea_orm::*;
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct CalendarTokenMutation;
+
+impl CalendarTokenMutation {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn create_calendar_token(
+        db: &DbConn,
+        form_data: calendar_token_model::ActiveModel,
+    ) -> Result<calendar_token_model::Model, DbErr> {
+        form_data.insert(db).await
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn update_calendar_token(
+        db: &DbConn,
+        form_data: calendar_token_model::ActiveModel,
+    ) -> Result<calendar_token_model::Model, DbErr> {
+        form_data.update(db).await
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn delete_calendar_token_by_user_id(
+        db: &DbConn,
+        user_id: Uuid,
+    ) -> Result<DeleteResult, DbErr> {
+        CalendarTokenEntity::delete_many()
+            .filter(calendar_token_model::Column::UserId.eq(user_id))
+            .exec(db)
+            .await
+    }
+}
@@ -0,0 +1,20 @@
+use ::entity::entities::consent_entity::{consent_model, consent_model::Entity as ConsentEntity};
+use sea_orm::*;
+use tracing::instrument;
+
+pub struct ConsentMutation;
+
+impl ConsentMutation {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn create_consents(
+        db: &DbConn,
+        consents: Vec<consent_model::ActiveModel>,
+    ) -> Result<u64, DbErr> {
+        if consents.is_empty() {
+            return Ok(0);
+        }
+        ConsentEntity::insert_many(consents)
+            .exec_without_returning(db)
+            .await
+    }
+}
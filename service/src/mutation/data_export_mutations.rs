@@ -0,0 +1,22 @@
+use ::entity::entities::data_export_entity::{
+    data_export_model, data_export_model::Entity as DataExportEntity,
+};
+use sea_orm::*;
+use tracing::instrument;
+
+pub struct DataExportMutation;
+
+impl DataExportMutation {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn create_data_export(
+        db: &DbConn,
+        form_data: data_export_model::ActiveModel,
+    ) -> Result<data_export_model::Model, DbErr> {
+        form_data.insert(db).await
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn delete_data_export_by_id(db: &DbConn, id: i32) -> Result<DeleteResult, DbErr> {
+        DataExportEntity::delete_by_id(id).exec(db).await
+    }
+}
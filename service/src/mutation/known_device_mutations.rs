@@ -0,0 +1,44 @@
+use ::entity::entities::known_device_entity::{
+    known_device_model, known_device_model::Entity as KnownDeviceEntity,
+};
+use chrono::Utc;
+use sea_orm::{sea_query::Expr, *};
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct KnownDeviceMutation;
+
+impl KnownDeviceMutation {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn create_known_device(
+        db: &DbConn,
+        form_data: known_device_model::ActiveModel,
+    ) -> Result<known_device_model::Model, DbErr> {
+        form_data.insert(db).await
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn touch_known_device(db: &DbConn, id: i32) -> Result<UpdateResult, DbErr> {
+        KnownDeviceEntity::update_many()
+            .col_expr(
+                known_device_model::Column::LastSeenAt,
+                Expr::value(Utc::now()),
+            )
+            .filter(known_device_model::Column::Id.eq(id))
+            .exec(db)
+            .await
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn delete_known_device_by_report_nonce(
+        db: &DbConn,
+        user_id: Uuid,
+        report_nonce: &str,
+    ) -> Result<DeleteResult, DbErr> {
+        KnownDeviceEntity::delete_many()
+            .filter(known_device_model::Column::UserId.eq(user_id))
+            .filter(known_device_model::Column::ReportNonce.eq(report_nonce))
+            .exec(db)
+            .await
+    }
+}
@@ -1,2 +1,8 @@
 pub mod user_mutations;
-pub mod two_fa_mutations;
\ No newline at end of file
+pub mod two_fa_mutations;
+pub mod calendar_token_mutations;
+pub mod session_mutations;
+pub mod consent_mutations;
+pub mod auth_event_mutations;
+pub mod known_device_mutations;
+pub mod data_export_mutations;
\ No newline at end of file
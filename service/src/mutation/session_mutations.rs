@@ -0,0 +1,40 @@
+use ::entity::entities::session_entity::{session_model, session_model::Entity as SessionEntity};
+use chrono::Utc;
+use sea_orm::{sea_query::Expr, *};
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct SessionMutation;
+
+impl SessionMutation {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn create_session(
+        db: &DbConn,
+        form_data: session_model::ActiveModel,
+    ) -> Result<session_model::Model, DbErr> {
+        form_data.insert(db).await
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn revoke_sessions_by_user_id(
+        db: &DbConn,
+        user_id: Uuid,
+    ) -> Result<UpdateResult, DbErr> {
+        SessionEntity::update_many()
+            .col_expr(session_model::Column::RevokedAt, Expr::value(Utc::now()))
+            .filter(session_model::Column::UserId.eq(user_id))
+            .filter(session_model::Column::RevokedAt.is_null())
+            .exec(db)
+            .await
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn revoke_session(db: &DbConn, id: Uuid) -> Result<UpdateResult, DbErr> {
+        SessionEntity::update_many()
+            .col_expr(session_model::Column::RevokedAt, Expr::value(Utc::now()))
+            .filter(session_model::Column::Id.eq(id))
+            .filter(session_model::Column::RevokedAt.is_null())
+            .exec(db)
+            .await
+    }
+}
@@ -1,9 +1,11 @@
-use ::entity::entities::two_fa_entity::{two_fa_model, two_fa_model::Entity as TwoFaEntity};
+use ::entity::entities::two_fa_entity::two_fa_model;
 use sea_orm::*;
+use tracing::instrument;
 
 pub struct TwoFaMutation;
 
 impl TwoFaMutation {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn create_two_fa(
         db: &DbConn,
         form_data: two_fa_model::ActiveModel,
@@ -11,6 +13,7 @@ impl TwoFaMutation {
         form_data.insert(db).await
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn update_two_fa(
         db: &DbConn,
         form_data: two_fa_model::ActiveModel,
@@ -18,6 +21,7 @@ impl TwoFaMutation {
         form_data.update(db).await
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn update_two_fa_by_id(
         db: &DbConn,
         id: i32,
@@ -25,19 +29,20 @@ impl TwoFaMutation {
     ) -> Result<two_fa_model::Model, DbErr> {
         two_fa_model::ActiveModel {
             id: Set(id),
-            v_e: Set(form_data.v_e),
-            t: Set(form_data.t),
-            s: Set(form_data.s),
-            c: Set(form_data.c),
-            up: Set(form_data.up),
-            ex: Set(form_data.ex),
-            v_ph: Set(form_data.v_ph),
+            email_verified: Set(form_data.email_verified),
+            tries_left: Set(form_data.tries_left),
+            codes_sent: Set(form_data.codes_sent),
+            code: Set(form_data.code),
+            locked_until: Set(form_data.locked_until),
+            lock_exponent: Set(form_data.lock_exponent),
+            phone_verified: Set(form_data.phone_verified),
             user_id: Set(form_data.user_id),
         }
         .update(db)
         .await
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn delete_two_fa(
         db: &DbConn,
         two_fa: two_fa_model::Model,
@@ -1,10 +1,17 @@
-use ::entity::entities::user_entity::{user_model, user_model::Entity as UserEntity};
+use ::entity::entities::{
+    consent_entity::{consent_model, consent_model::Entity as ConsentEntity},
+    two_fa_entity::{two_fa_model, two_fa_model::Entity as TwoFaEntity},
+    user_entity::{user_model, user_model::Entity as UserEntity},
+};
 
+use chrono::{DateTime, Utc};
 use sea_orm::{prelude::Uuid, *};
+use tracing::instrument;
 
 pub struct UserMutation;
 
 impl UserMutation {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn create_user(
         db: &DbConn,
         form_data: user_model::ActiveModel,
@@ -12,25 +19,62 @@ impl UserMutation {
         form_data.insert(db).await
     }
 
+    /// Inserts the users with their 2FA rows and consents in one
+    /// transaction, none of them is created when an insert fails.
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn create_users(
+        db: &DbConn,
+        users: Vec<user_model::ActiveModel>,
+        two_fas: Vec<two_fa_model::ActiveModel>,
+        consents: Vec<consent_model::ActiveModel>,
+    ) -> Result<u64, DbErr> {
+        let count = users.len() as u64;
+        if users.is_empty() {
+            return Ok(0);
+        }
+
+        let txn = db.begin().await?;
+        UserEntity::insert_many(users)
+            .exec_without_returning(&txn)
+            .await?;
+        if !two_fas.is_empty() {
+            TwoFaEntity::insert_many(two_fas)
+                .exec_without_returning(&txn)
+                .await?;
+        }
+        if !consents.is_empty() {
+            ConsentEntity::insert_many(consents)
+                .exec_without_returning(&txn)
+                .await?;
+        }
+        txn.commit().await?;
+
+        Ok(count)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn update_user(
         db: &DbConn,
         user: user_model::Model,
     ) -> Result<user_model::Model, DbErr> {
         user_model::ActiveModel {
             id: Set(user.id),
-            av: Set(user.av),
-            f: Set(user.f),
-            l: Set(user.l),
-            e: Set(user.e),
-            ph: Set(user.ph),
-            t: Set(user.t),
-            pv: Set(user.pv),
+            avatar: Set(user.avatar),
+            first_name: Set(user.first_name),
+            last_name: Set(user.last_name),
+            email: Set(user.email),
+            phone: Set(user.phone),
+            terms: Set(user.terms),
+            privacy: Set(user.privacy),
             two_fa: Set(user.two_fa),
-            lg: Set(user.lg),
+            language: Set(user.language),
+            role: Set(user.role),
             created_at: Set(user.created_at),
+            deleted_at: Set(user.deleted_at),
         }.update(db).await
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn update_user_by_id(
         db: &DbConn,
         id: Uuid,
@@ -44,25 +88,55 @@ impl UserMutation {
 
         user_model::ActiveModel {
             id: user.id,
-            av: Set(form_data.av.to_owned()),
-            f: Set(form_data.f.to_owned()),
-            l: Set(form_data.l.to_owned()),
-            e: Set(form_data.e.to_owned()),
-            ph: Set(form_data.ph.to_owned()),
-            t: Set(form_data.t.to_owned()),
-            pv: Set(form_data.pv.to_owned()),
+            avatar: Set(form_data.avatar.to_owned()),
+            first_name: Set(form_data.first_name.to_owned()),
+            last_name: Set(form_data.last_name.to_owned()),
+            email: Set(form_data.email.to_owned()),
+            phone: Set(form_data.phone.to_owned()),
+            terms: Set(form_data.terms.to_owned()),
+            privacy: Set(form_data.privacy.to_owned()),
             two_fa: Set(form_data.two_fa.to_owned()),
-            lg: Set(form_data.lg.to_owned()),
+            language: Set(form_data.language.to_owned()),
+            role: user.role,
             created_at: Set(form_data.created_at.to_owned()),
+            deleted_at: user.deleted_at,
+        }
+        .update(db)
+        .await
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn soft_delete_user_by_id(
+        db: &DbConn,
+        id: Uuid,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<user_model::Model, DbErr> {
+        user_model::ActiveModel {
+            id: Set(id),
+            deleted_at: Set(Some(deleted_at)),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn restore_user_by_id(db: &DbConn, id: Uuid) -> Result<user_model::Model, DbErr> {
+        user_model::ActiveModel {
+            id: Set(id),
+            deleted_at: Set(None),
+            ..Default::default()
         }
         .update(db)
         .await
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn delete_user(db: &DbConn, user: user_model::Model) -> Result<DeleteResult, DbErr> {
         user.delete(db).await
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn delete_user_by_id(db: &DbConn, id: Uuid) -> Result<DeleteResult, DbErr> {
         UserEntity::delete_by_id(id).exec(db).await
     }
@@ -0,0 +1,65 @@
+use ::entity::entities::auth_event_entity::{
+    auth_event_model, auth_event_model::Entity as AuthEventEntity,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::*;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+pub struct AuthEventQuery;
+
+impl AuthEventQuery {
+    /// Most recent events first, optionally restricted to a user and a time range.
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_auth_events(
+        db: &DbConn,
+        user_id: Option<Uuid>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: u64,
+    ) -> Result<Vec<auth_event_model::Model>, DbErr> {
+        let mut query = AuthEventEntity::find();
+
+        if let Some(user_id) = user_id {
+            query = query.filter(auth_event_model::Column::UserId.eq(user_id));
+        }
+        if let Some(from) = from {
+            query = query.filter(auth_event_model::Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = to {
+            query = query.filter(auth_event_model::Column::CreatedAt.lt(to));
+        }
+
+        match query
+            .order_by_desc(auth_event_model::Column::CreatedAt)
+            .limit(limit)
+            .all(db)
+            .await
+        {
+            Ok(events) => Ok(events),
+            Err(err) => {
+                error!("Cannot find auth events: {}", err);
+                Err(err)
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_auth_events_by_user_id(
+        db: &DbConn,
+        user_id: Uuid,
+    ) -> Result<Vec<auth_event_model::Model>, DbErr> {
+        match AuthEventEntity::find()
+            .filter(auth_event_model::Column::UserId.eq(user_id))
+            .order_by_asc(auth_event_model::Column::CreatedAt)
+            .all(db)
+            .await
+        {
+            Ok(events) => Ok(events),
+            Err(err) => {
+                error!("Cannot find auth events by user id: {}", err);
+                Err(err)
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use ::entity::entities::calendar_token_entity::{
+    calendar_token_model, calendar_token_model::Entity as CalendarTokenEntity,
+};
+use sea_orm::*;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+pub struct CalendarTokenQuery;
+
+impl CalendarTokenQuery {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_calendar_token_by_user_id(
+        db: &DbConn,
+        user_id: Uuid,
+    ) -> Result<Option<calendar_token_model::Model>, DbErr> {
+        match CalendarTokenEntity::find()
+            .filter(calendar_token_model::Column::UserId.eq(user_id))
+            .one(db)
+            .await
+        {
+            Ok(calendar_token) => Ok(calendar_token),
+            Err(err) => {
+                error!("Cannot find calendar token by user id: {}", err);
+                Err(err)
+            }
+        }
+    }
+}
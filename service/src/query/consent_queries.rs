@@ -0,0 +1,27 @@
+use ::entity::entities::consent_entity::{consent_model, consent_model::Entity as ConsentEntity};
+use sea_orm::*;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+pub struct ConsentQuery;
+
+impl ConsentQuery {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_consents_by_user_id(
+        db: &DbConn,
+        user_id: Uuid,
+    ) -> Result<Vec<consent_model::Model>, DbErr> {
+        match ConsentEntity::find()
+            .filter(consent_model::Column::UserId.eq(user_id))
+            .order_by_asc(consent_model::Column::AcceptedAt)
+            .all(db)
+            .await
+        {
+            Ok(consents) => Ok(consents),
+            Err(err) => {
+                error!("Cannot find consents by user id: {}", err);
+                Err(err)
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use ::entity::entities::data_export_entity::{
+    data_export_model, data_export_model::Entity as DataExportEntity,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::*;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+pub struct DataExportQuery;
+
+impl DataExportQuery {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_data_exports_by_user_id(
+        db: &DbConn,
+        user_id: Uuid,
+    ) -> Result<Vec<data_export_model::Model>, DbErr> {
+        match DataExportEntity::find()
+            .filter(data_export_model::Column::UserId.eq(user_id))
+            .all(db)
+            .await
+        {
+            Ok(exports) => Ok(exports),
+            Err(err) => {
+                error!("Cannot find data exports by user id: {}", err);
+                Err(err)
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_data_exports_expired_before(
+        db: &DbConn,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<data_export_model::Model>, DbErr> {
+        match DataExportEntity::find()
+            .filter(data_export_model::Column::ExpiresAt.lt(before))
+            .all(db)
+            .await
+        {
+            Ok(exports) => Ok(exports),
+            Err(err) => {
+                error!(
+                    "Cannot find data exports expired before {}: {}",
+                    before, err
+                );
+                Err(err)
+            }
+        }
+    }
+}
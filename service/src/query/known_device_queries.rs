@@ -0,0 +1,49 @@
+use ::entity::entities::known_device_entity::{
+    known_device_model, known_device_model::Entity as KnownDeviceEntity,
+};
+use sea_orm::*;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+pub struct KnownDeviceQuery;
+
+impl KnownDeviceQuery {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_known_devices_by_user_id(
+        db: &DbConn,
+        user_id: Uuid,
+    ) -> Result<Vec<known_device_model::Model>, DbErr> {
+        match KnownDeviceEntity::find()
+            .filter(known_device_model::Column::UserId.eq(user_id))
+            .order_by_asc(known_device_model::Column::FirstSeenAt)
+            .all(db)
+            .await
+        {
+            Ok(devices) => Ok(devices),
+            Err(err) => {
+                error!("Cannot find known devices by user id: {}", err);
+                Err(err)
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_known_device_by_report_nonce(
+        db: &DbConn,
+        user_id: Uuid,
+        report_nonce: &str,
+    ) -> Result<Option<known_device_model::Model>, DbErr> {
+        match KnownDeviceEntity::find()
+            .filter(known_device_model::Column::UserId.eq(user_id))
+            .filter(known_device_model::Column::ReportNonce.eq(report_nonce))
+            .one(db)
+            .await
+        {
+            Ok(device) => Ok(device),
+            Err(err) => {
+                error!("Cannot find known device by report nonce: {}", err);
+                Err(err)
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use ::entity::entities::legal_document_entity::{
+    legal_document_model, legal_document_model::Entity as LegalDocumentEntity,
+};
+use chrono::Utc;
+use sea_orm::*;
+use tracing::{error, instrument};
+
+pub struct LegalDocumentQuery;
+
+impl LegalDocumentQuery {
+    /// Latest published version of each document kind.
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_current_documents(
+        db: &DbConn,
+    ) -> Result<Vec<legal_document_model::Model>, DbErr> {
+        match LegalDocumentEntity::find()
+            .filter(legal_document_model::Column::PublishedAt.lte(Utc::now()))
+            .order_by_desc(legal_document_model::Column::PublishedAt)
+            .all(db)
+            .await
+        {
+            Ok(documents) => {
+                let mut current: Vec<legal_document_model::Model> = Vec::new();
+                for document in documents {
+                    if !current.iter().any(|d| d.kind == document.kind) {
+                        current.push(document);
+                    }
+                }
+                Ok(current)
+            }
+            Err(err) => {
+                error!("Cannot find current legal documents: {}", err);
+                Err(err)
+            }
+        }
+    }
+}
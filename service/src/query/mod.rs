@@ -1,2 +1,11 @@
 pub mod user_queries;
-pub mod two_fa_queries;
\ No newline at end of file
+pub mod two_fa_queries;
+pub mod rdv_queries;
+pub mod calendar_token_queries;
+pub mod session_queries;
+pub mod legal_document_queries;
+pub mod consent_queries;
+pub mod auth_event_queries;
+pub mod known_device_queries;
+pub mod read_replica;
+pub mod data_export_queries;
\ No newline at end of file
@@ -0,0 +1,49 @@
+use ::entity::entities::rdv_entity::{rdv_model, rdv_model::Entity as RdvEntity};
+use sea_orm::*;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+pub struct RdvQuery;
+
+impl RdvQuery {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_upcoming_rdv_by_pro_id(
+        db: &DbConn,
+        pro_id: Uuid,
+        from: i64,
+    ) -> Result<Vec<rdv_model::Model>, DbErr> {
+        match RdvEntity::find()
+            .filter(rdv_model::Column::ProId.eq(pro_id))
+            .filter(rdv_model::Column::EndAt.gte(from))
+            .filter(rdv_model::Column::Cancelled.eq(false))
+            .order_by_asc(rdv_model::Column::StartAt)
+            .all(db)
+            .await
+        {
+            Ok(rdvs) => Ok(rdvs),
+            Err(err) => {
+                error!("Cannot find upcoming rdv by pro id: {}", err);
+                Err(err)
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_all_rdv_by_pro_id(
+        db: &DbConn,
+        pro_id: Uuid,
+    ) -> Result<Vec<rdv_model::Model>, DbErr> {
+        match RdvEntity::find()
+            .filter(rdv_model::Column::ProId.eq(pro_id))
+            .order_by_asc(rdv_model::Column::StartAt)
+            .all(db)
+            .await
+        {
+            Ok(rdvs) => Ok(rdvs),
+            Err(err) => {
+                error!("Cannot find all rdv by pro id: {}", err);
+                Err(err)
+            }
+        }
+    }
+}
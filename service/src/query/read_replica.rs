@@ -0,0 +1,23 @@
+use std::sync::OnceLock;
+
+use sea_orm::DbConn;
+
+static READ_REPLICA: OnceLock<DbConn> = OnceLock::new();
+
+/// Registers the connection the admin user listing and CSV export read
+/// from, once at startup. Gives `db` back when a replica is already
+/// registered.
+pub fn set_read_replica(db: DbConn) -> Result<(), DbConn> {
+    READ_REPLICA.set(db)
+}
+
+pub fn read_replica() -> Option<&'static DbConn> {
+    READ_REPLICA.get()
+}
+
+/// The replica when one is registered, `primary` otherwise. Only for reads
+/// that can lag behind a write of the same user: the sign in, auth and
+/// profile paths read their own writes and stay on the primary.
+pub(crate) fn reader(primary: &DbConn) -> &DbConn {
+    READ_REPLICA.get().unwrap_or(primary)
+}
@@ -0,0 +1,47 @@
+use ::entity::entities::session_entity::{session_model, session_model::Entity as SessionEntity};
+use chrono::Utc;
+use sea_orm::*;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+pub struct SessionQuery;
+
+impl SessionQuery {
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_active_session(
+        db: &DbConn,
+        id: Uuid,
+    ) -> Result<Option<session_model::Model>, DbErr> {
+        match SessionEntity::find_by_id(id)
+            .filter(session_model::Column::RevokedAt.is_null())
+            .filter(session_model::Column::ExpiresAt.gt(Utc::now()))
+            .one(db)
+            .await
+        {
+            Ok(session) => Ok(session),
+            Err(err) => {
+                error!("Cannot find active session: {}", err);
+                Err(err)
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_sessions_by_user_id(
+        db: &DbConn,
+        user_id: Uuid,
+    ) -> Result<Vec<session_model::Model>, DbErr> {
+        match SessionEntity::find()
+            .filter(session_model::Column::UserId.eq(user_id))
+            .order_by_asc(session_model::Column::CreatedAt)
+            .all(db)
+            .await
+        {
+            Ok(sessions) => Ok(sessions),
+            Err(err) => {
+                error!("Cannot find sessions by user id: {}", err);
+                Err(err)
+            }
+        }
+    }
+}
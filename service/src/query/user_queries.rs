@@ -1,16 +1,199 @@
 use ::entity::entities::{
-    two_fa_entity::two_fa_model::{Entity as TwoFaEntity, Model as TwoFaModel},
-    user_entity::{user_model, user_model::Entity as UserEntity},
+    two_fa_entity::{
+        two_fa_model,
+        two_fa_model::{Entity as TwoFaEntity, Model as TwoFaModel},
+    },
+    user_entity::{
+        user_model,
+        user_model::{Entity as UserEntity, Language, Role},
+    },
 };
-use sea_orm::*;
-use tracing::{error, warn};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::{Expr, Func, SimpleExpr},
+    *,
+};
+use tracing::{error, instrument, warn};
 use uuid::Uuid;
 
+use super::read_replica::reader;
+
+/// The admin listing and CSV export read from the replica when one is
+/// registered, see `read_replica`. Single user and 2FA reads stay on the
+/// primary, they come right after a write of the same user.
 pub struct UserQuery;
 
+/// Exact match on `lower(email)`, which the unique `users_email_lower_key`
+/// index covers. Emails are stored trimmed and lowercased.
+fn email_matches(email: &str) -> SimpleExpr {
+    Expr::expr(Func::lower(Expr::col(user_model::Column::Email)))
+        .eq(email.trim().to_lowercase())
+}
+
+/// Searched by `find_users`, the `users_search_idx` GIN index is built on
+/// the same expression.
+const SEARCH_DOCUMENT: &str = "to_tsvector('simple', \"users\".\"first_name\" || ' ' || \
+    \"users\".\"last_name\" || ' ' || translate(\"users\".\"email\", '@._+-', '     '))";
+
+/// Every word of `search` as a prefix, `None` when it has no word.
+fn search_query(search: &str) -> Option<String> {
+    let terms: Vec<String> = search
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("{}:*", term.to_lowercase()))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" & "))
+}
+
+/// Filters of the admin listing, unset ones match every user.
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    /// Words matched as prefixes of the names and of the email.
+    pub search: Option<String>,
+    pub language: Option<Language>,
+    pub role: Option<Role>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub two_fa: Option<bool>,
+    pub email_verified: Option<bool>,
+    pub phone_verified: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UserSort {
+    #[default]
+    CreatedAt,
+    LastName,
+    Email,
+}
+
+impl UserSort {
+    fn column(&self) -> user_model::Column {
+        match self {
+            UserSort::CreatedAt => user_model::Column::CreatedAt,
+            UserSort::LastName => user_model::Column::LastName,
+            UserSort::Email => user_model::Column::Email,
+        }
+    }
+}
+
+/// Sort value and id of the last user of a page, the next page starts after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserCursor {
+    CreatedAt(DateTime<Utc>, Uuid),
+    LastName(String, Uuid),
+    Email(String, Uuid),
+}
+
+impl UserCursor {
+    pub fn after(user: &user_model::Model, sort: UserSort) -> Self {
+        match sort {
+            UserSort::CreatedAt => UserCursor::CreatedAt(user.created_at, user.id),
+            UserSort::LastName => UserCursor::LastName(user.last_name.to_owned(), user.id),
+            UserSort::Email => UserCursor::Email(user.email.to_owned(), user.id),
+        }
+    }
+
+    pub fn sort(&self) -> UserSort {
+        match self {
+            UserCursor::CreatedAt(..) => UserSort::CreatedAt,
+            UserCursor::LastName(..) => UserSort::LastName,
+            UserCursor::Email(..) => UserSort::Email,
+        }
+    }
+
+    /// Rows past the cursor in the `order` of the listing, ties on the sort
+    /// value are broken by id.
+    fn condition(&self, order: &Order) -> Condition {
+        let (column, value, id) = match self {
+            UserCursor::CreatedAt(created_at, id) => {
+                (user_model::Column::CreatedAt, Value::from(*created_at), id)
+            }
+            UserCursor::LastName(last_name, id) => (
+                user_model::Column::LastName,
+                Value::from(last_name.to_owned()),
+                id,
+            ),
+            UserCursor::Email(email, id) => {
+                (user_model::Column::Email, Value::from(email.to_owned()), id)
+            }
+        };
+
+        let (past, past_id) = match order {
+            Order::Asc => (
+                Expr::col((UserEntity, column)).gt(value.to_owned()),
+                Expr::col((UserEntity, user_model::Column::Id)).gt(*id),
+            ),
+            _ => (
+                Expr::col((UserEntity, column)).lt(value.to_owned()),
+                Expr::col((UserEntity, user_model::Column::Id)).lt(*id),
+            ),
+        };
+
+        Condition::any().add(past).add(
+            Condition::all()
+                .add(Expr::col((UserEntity, column)).eq(value))
+                .add(past_id),
+        )
+    }
+}
+
+/// A page of the listing with the 2FA state of each user, and the number of
+/// users matching the filter across all pages.
+#[derive(Debug)]
+pub struct UserPage {
+    pub users: Vec<(user_model::Model, Option<TwoFaModel>)>,
+    pub total: u64,
+    pub next: Option<UserCursor>,
+}
+
+/// Users not deleted matching `filter`, joined to their 2FA row.
+fn filtered_users(filter: &UserFilter) -> Select<UserEntity> {
+    let mut query = UserEntity::find()
+        .join(JoinType::LeftJoin, user_model::Relation::TwoFa.def())
+        .filter(user_model::Column::DeletedAt.is_null());
+
+    if let Some(search) = filter.search.as_deref().and_then(search_query) {
+        query = query.filter(Expr::cust_with_values(
+            format!("{} @@ to_tsquery('simple', $1)", SEARCH_DOCUMENT),
+            [search],
+        ));
+    }
+    if let Some(language) = &filter.language {
+        query = query.filter(user_model::Column::Language.eq(language.to_owned()));
+    }
+    if let Some(role) = &filter.role {
+        query = query.filter(user_model::Column::Role.eq(role.to_owned()));
+    }
+    if let Some(from) = filter.created_from {
+        query = query.filter(user_model::Column::CreatedAt.gte(from));
+    }
+    if let Some(to) = filter.created_to {
+        query = query.filter(user_model::Column::CreatedAt.lt(to));
+    }
+    if let Some(two_fa) = filter.two_fa {
+        query = query.filter(user_model::Column::TwoFa.eq(two_fa));
+    }
+    if let Some(verified) = filter.email_verified {
+        query = query.filter(two_fa_model::Column::EmailVerified.eq(verified));
+    }
+    if let Some(verified) = filter.phone_verified {
+        query = query.filter(two_fa_model::Column::PhoneVerified.eq(verified));
+    }
+
+    query
+}
+
 impl UserQuery {
+    /// Soft-deleted users are excluded, see `find_user_by_id_with_deleted`.
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn find_user_by_id(db: &DbConn, id: Uuid) -> Result<user_model::Model, DbErr> {
-        match UserEntity::find_by_id(id).one(db).await {
+        match UserEntity::find_by_id(id)
+            .filter(user_model::Column::DeletedAt.is_null())
+            .one(db)
+            .await
+        {
             Ok(user) => match user {
                 Some(user) => Ok(user),
                 None => {
@@ -25,12 +208,15 @@ impl UserQuery {
         }
     }
     
+    /// Soft-deleted users are excluded, see `find_user_by_email_with_deleted`.
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn find_user_by_email(
         db: &DbConn,
         email: &String,
     ) -> Result<user_model::Model, ()> {
         match UserEntity::find()
-            .filter(user_model::Column::E.contains(email))
+            .filter(email_matches(email))
+            .filter(user_model::Column::DeletedAt.is_null())
             .one(db)
             .await
         {
@@ -48,6 +234,180 @@ impl UserQuery {
         }
     }
 
+    /// Includes users inside their deletion grace period, so they can log in
+    /// and restore their account.
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_user_by_id_with_deleted(
+        db: &DbConn,
+        id: Uuid,
+    ) -> Result<user_model::Model, DbErr> {
+        match UserEntity::find_by_id(id).one(db).await {
+            Ok(user) => match user {
+                Some(user) => Ok(user),
+                None => {
+                    warn!("Cannot find user by id: {}", id);
+                    Err(DbErr::RecordNotFound(format!("id not found: {}", id)))
+                }
+            },
+            Err(err) => {
+                error!("Cannot find user by id: {}", err);
+                Err(err)
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_user_by_email_with_deleted(
+        db: &DbConn,
+        email: &String,
+    ) -> Result<user_model::Model, ()> {
+        match UserEntity::find()
+            .filter(email_matches(email))
+            .one(db)
+            .await
+        {
+            Ok(user) => match user {
+                Some(user) => Ok(user),
+                None => {
+                    warn!("Cannot find user by email: {}", email);
+                    Err(())
+                }
+            },
+            Err(err) => {
+                error!("Cannot find user by email: {}", err);
+                Err(())
+            }
+        }
+    }
+
+    /// Admin listing of the users not deleted, `limit` at a time.
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_users(
+        db: &DbConn,
+        filter: &UserFilter,
+        sort: UserSort,
+        order: Order,
+        after: Option<&UserCursor>,
+        limit: u64,
+    ) -> Result<UserPage, DbErr> {
+        let mut query = filtered_users(filter);
+
+        let total = match query.clone().count(reader(db)).await {
+            Ok(total) => total,
+            Err(err) => {
+                error!("Cannot count users: {}", err);
+                return Err(err);
+            }
+        };
+
+        if let Some(after) = after {
+            query = query.filter(after.condition(&order));
+        }
+
+        // One more row than the page tells whether another page follows.
+        let mut users = match query
+            .order_by(sort.column(), order.to_owned())
+            .order_by(user_model::Column::Id, order)
+            .select_also(TwoFaEntity)
+            .limit(limit + 1)
+            .all(reader(db))
+            .await
+        {
+            Ok(users) => users,
+            Err(err) => {
+                error!("Cannot find users: {}", err);
+                return Err(err);
+            }
+        };
+
+        let next = match users.len() as u64 {
+            len if len > limit => {
+                users.truncate(limit as usize);
+                users.last().map(|(user, _)| UserCursor::after(user, sort))
+            }
+            _ => None,
+        };
+
+        Ok(UserPage { users, total, next })
+    }
+
+    /// Every user `find_users` would list for `filter`, in a single query.
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_all_users(
+        db: &DbConn,
+        filter: &UserFilter,
+        sort: UserSort,
+        order: Order,
+    ) -> Result<Vec<(user_model::Model, Option<TwoFaModel>)>, DbErr> {
+        match filtered_users(filter)
+            .order_by(sort.column(), order.to_owned())
+            .order_by(user_model::Column::Id, order)
+            .select_also(TwoFaEntity)
+            .all(reader(db))
+            .await
+        {
+            Ok(users) => Ok(users),
+            Err(err) => {
+                error!("Cannot find users: {}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Users holding any of `emails` or `phones`, deleted ones included since
+    /// they keep both until they are purged.
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_users_by_emails_or_phones(
+        db: &DbConn,
+        emails: &[String],
+        phones: &[String],
+    ) -> Result<Vec<user_model::Model>, DbErr> {
+        if emails.is_empty() && phones.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let emails: Vec<String> = emails
+            .iter()
+            .map(|email| email.trim().to_lowercase())
+            .collect();
+        let email = Expr::expr(Func::lower(Expr::col(user_model::Column::Email)));
+
+        match UserEntity::find()
+            .filter(
+                Condition::any()
+                    .add(email.is_in(emails))
+                    .add(user_model::Column::Phone.is_in(phones.to_owned())),
+            )
+            .all(db)
+            .await
+        {
+            Ok(users) => Ok(users),
+            Err(err) => {
+                error!("Cannot find users by emails or phones: {}", err);
+                Err(err)
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
+    pub async fn find_users_deleted_before(
+        db: &DbConn,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<user_model::Model>, DbErr> {
+        match UserEntity::find()
+            .filter(user_model::Column::DeletedAt.lt(before))
+            .all(db)
+            .await
+        {
+            Ok(users) => Ok(users),
+            Err(err) => {
+                error!("Cannot find users deleted before {}: {}", before, err);
+                Err(err)
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql"))]
     pub async fn find_related_two_fa(
         db: &DbConn,
         user: &user_model::Model,
@@ -56,18 +416,15 @@ impl UserQuery {
             Ok(two_fa) => match two_fa {
                 Some(t) => Ok(t),
                 None => {
-                    warn!("Cannot find related two_fa, req by this user: {:#?}", user);
+                    warn!("Cannot find related two_fa of user: {}", user.id);
                     Err(DbErr::RecordNotFound(format!(
-                        "two_fa not found, req by this user: {:#?}",
-                        user
+                        "two_fa not found for user: {}",
+                        user.id
                     )))
                 }
             },
             Err(err) => {
-                error!(
-                    "Cannot find related two_fa err: {} req by this user: {:#?}",
-                    err, user
-                );
+                error!("Cannot find related two_fa of user {}: {}", user.id, err);
                 Err(err)
             }
         }
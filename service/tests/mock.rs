@@ -1,7 +1,7 @@
 mod prepare;
 
 use chrono::{DateTime, FixedOffset, Utc};
-use entity::user_entity::user_model::{self, Language};
+use entity::user_entity::user_model::{self, Language, Role};
 use prepare::prepare_mock_db;
 use service::{mutation::user_mutations::UserMutation, query::user_queries::UserQuery};
 use uuid::uuid;
@@ -44,6 +44,7 @@ async fn main() {
                 pv: true,
                 two_fa: true,
                 lg: Language::Fr,
+                role: Role::Pro,
                 created_at: Utc::now(),
             },
         )
@@ -89,6 +90,7 @@ async fn main() {
                 pv: true,
                 two_fa: true,
                 lg: Language::Fr,
+                role: Role::Pro,
                 created_at: Utc::now(),
             },
         )
@@ -108,6 +110,7 @@ async fn main() {
                 pv: true,
                 two_fa: true,
                 lg: Language::Fr,
+                role: Role::Pro,
                 created_at: Utc::now(),
             }
         );
@@ -1,7 +1,6 @@
 use chrono::Utc;
 use ::entity::entities::user_entity::user_model;
 use sea_orm::*;
-use ::entity::entities::user_entity::user_model::Language;
 use uuid::uuid;
 
 #[cfg(feature = "mock")]
@@ -19,7 +18,8 @@ pub fn prepare_mock_db() -> DatabaseConnection {
                 t: true,
                 pv: true,
                 two_fa: true,
-                lg: Language::Fr,
+                lg: user_model::Language::Fr,
+                role: user_model::Role::Pro,
                 created_at: Utc::now(),
             }],
             [user_model::Model {
@@ -32,7 +32,8 @@ pub fn prepare_mock_db() -> DatabaseConnection {
                 t: true,
                 pv: true,
                 two_fa: true,
-                lg: Language::Fr,
+                lg: user_model::Language::Fr,
+                role: user_model::Role::Pro,
                 created_at: Utc::now(),
             }],
             [user_model::Model {
@@ -45,7 +46,8 @@ pub fn prepare_mock_db() -> DatabaseConnection {
                 t: true,
                 pv: true,
                 two_fa: true,
-                lg: Language::Fr,
+                lg: user_model::Language::Fr,
+                role: user_model::Role::Pro,
                 created_at: Utc::now(),
             }],
             [user_model::Model {
@@ -58,7 +60,8 @@ pub fn prepare_mock_db() -> DatabaseConnection {
                 t: true,
                 pv: true,
                 two_fa: true,
-                lg: Language::Fr,
+                lg: user_model::Language::Fr,
+                role: user_model::Role::Pro,
                 created_at: Utc::now(),
             }],
             [user_model::Model {
@@ -71,7 +74,8 @@ pub fn prepare_mock_db() -> DatabaseConnection {
                 t: true,
                 pv: true,
                 two_fa: true,
-                lg: Language::Fr,
+                lg: user_model::Language::Fr,
+                role: user_model::Role::Pro,
                 created_at: Utc::now(),
             }],
             [user_model::Model {
@@ -84,7 +88,8 @@ pub fn prepare_mock_db() -> DatabaseConnection {
                 t: true,
                 pv: true,
                 two_fa: true,
-                lg: Language::Fr,
+                lg: user_model::Language::Fr,
+                role: user_model::Role::Pro,
                 created_at: Utc::now(),
             }],
         ])
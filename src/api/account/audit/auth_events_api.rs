@@ -0,0 +1,91 @@
+use crate::{
+    error::api_error::ApiError,
+    middlewares::authenticated_user::AuthenticatedUser,
+    types::audit::auth_event_data::{AuthEventResponse, AuthEventsRequest},
+};
+use actix_web::{
+    get,
+    web::{Data, Query},
+    HttpResponse,
+};
+use sea_orm::DatabaseConnection;
+use service::query::auth_event_queries::AuthEventQuery;
+
+/// Same filters as the admin listing, except `userId` which is always the caller.
+#[get("/me/auth_events")]
+pub async fn get_my_auth_events(
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+    query: Query<AuthEventsRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    let events =
+        AuthEventQuery::find_auth_events(&db, Some(user_id), query.from, query.to, query.limit())
+            .await?;
+
+    Ok(HttpResponse::Ok().json(
+        events
+            .iter()
+            .map(AuthEventResponse::new)
+            .collect::<Vec<AuthEventResponse>>(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use entity::entities::auth_event_entity::auth_event_model::{
+        self, AuthEventKind, AuthEventOutcome,
+    };
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase};
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use crate::middlewares::authenticated_user::test_utils::AuthenticateAs;
+
+    use super::get_my_auth_events;
+
+    #[actix_web::test]
+    async fn test_get_my_auth_events() {
+        let user_id = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[auth_event_model::Model {
+                    id: 1,
+                    kind: AuthEventKind::Login,
+                    outcome: AuthEventOutcome::Success,
+                    user_agent: Some(String::from("Mozilla/5.0")),
+                    user_id: Some(user_id),
+                    ..Default::default()
+                }]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(AuthenticateAs(user_id))
+                .service(web::scope("/account").service(get_my_auth_events)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/account/me/auth_events?from=2024-03-01T00:00:00Z&to=2024-04-01T00:00:00Z")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body[0]["kind"], "login");
+        assert_eq!(resp_body[0]["userAgent"], "Mozilla/5.0");
+    }
+}
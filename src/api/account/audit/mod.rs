@@ -0,0 +1 @@
+pub mod auth_events_api;
\ No newline at end of file
@@ -1,9 +1,13 @@
 use crate::{
-    error::bad_request,
+    error::{api_error::ApiError, codes},
     types::auth::check_code::CheckCodeRequest,
     utils::{
-        cookie_utils::{create_cookie, delete_cookie, get_cookie_from_http_request, CookiePayload},
-        crypto_utils::{decrypt_payload, EncryptedPayload},
+        auth_event_utils::record_auth_event,
+        cookie_utils::{create_cookie, read_cookie_payload, CookiePayload},
+        device_utils::{notify_new_device, register_login_device, DeviceInfo, DeviceLogin},
+        login_policy_utils::LoginPolicy,
+        request_utils::client_ip,
+        task_manager_utils::TaskManager,
         time_utils::MAX_AGE_2J,
         two_factors_auth_utils::TwoFactorsAuth,
     },
@@ -13,108 +17,163 @@ use actix_web::{
     web::{Data, Json},
     HttpRequest, HttpResponse,
 };
-use chrono::Utc;
-use sea_orm::DatabaseConnection;
+use chrono::{DateTime, Utc};
+use entity::entities::{
+    auth_event_entity::auth_event_model::{AuthEventKind, AuthEventOutcome},
+    session_entity::session_model,
+};
+use sea_orm::{ActiveModelBehavior, DatabaseConnection, Set};
 use serde_json::json;
-use service::query::user_queries::UserQuery;
+use service::{
+    mutation::{session_mutations::SessionMutation, user_mutations::UserMutation},
+    query::user_queries::UserQuery,
+};
+use tracing::error;
 
 #[post("/checkcode")]
 pub async fn check_code(
     req: HttpRequest,
     db: Data<DatabaseConnection>,
+    tasks: Data<TaskManager>,
+    policy: Data<LoginPolicy>,
     body: Json<CheckCodeRequest>,
-) -> HttpResponse {
-    let cookie = match get_cookie_from_http_request(&req, "token") {
-        Some(t) => t,
-        None => {
-            return HttpResponse::InternalServerError().finish();
-        }
-    };
+) -> Result<HttpResponse, ApiError> {
+    let cookie_payload = read_cookie_payload(&req, "token")?;
 
-    let token = serde_json::from_str::<EncryptedPayload>(&cookie.as_str()).unwrap();
+    let user = UserQuery::find_user_by_id_with_deleted(&db, cookie_payload.id).await?;
+    let two_fa = UserQuery::find_related_two_fa(&db, &user).await?;
 
-    let cookie_payload: CookiePayload = match decrypt_payload(&token.order, &token.content) {
-        Ok(p) => p,
-        Err(_) => {
-            return HttpResponse::InternalServerError().finish();
-        }
-    };
+    let resp_check_deadline = TwoFactorsAuth::check_deadline(&two_fa);
 
-    if cookie_payload.exp_at < Utc::now().timestamp() {
-        return bad_request::<String>("Token", "Expired", None);
+    if resp_check_deadline.still_time {
+        return Err(ApiError::TooManyRequests(
+            codes::ACCOUNT_LOCKED,
+            resp_check_deadline.time_left,
+        ));
     }
 
-    let user = match UserQuery::find_user_by_id(&db, cookie_payload.id).await {
-        Ok(u) => u,
-        Err(_) => return HttpResponse::InternalServerError().finish(),
-    };
+    // Checked on the stored flags, the code below verifies the phone itself.
+    if let Err(unverified) = policy.check(&two_fa) {
+        record_auth_event(
+            &db,
+            &req,
+            AuthEventKind::Login,
+            AuthEventOutcome::Failure,
+            Some(user.id),
+        )
+        .await;
+        return Err(ApiError::Forbidden(
+            codes::ACCOUNT_UNVERIFIED,
+            serde_json::to_value(unverified).ok(),
+        ));
+    }
 
-    let two_fa = match UserQuery::find_related_two_fa(&db, &user).await {
-        Ok(t) => t,
-        Err(_) => return HttpResponse::InternalServerError().finish(),
-    };
+    let check_code = TwoFactorsAuth::check_code(&two_fa, &body.code);
 
-    let resp_check_deadline = TwoFactorsAuth::check_deadline(&two_fa);
+    if !check_code.valid {
+        let tries = TwoFactorsAuth::update_pro_by_remove_one_try(&two_fa, &db).await;
+        record_auth_event(
+            &db,
+            &req,
+            AuthEventKind::CodeFailed,
+            AuthEventOutcome::Failure,
+            Some(user.id),
+        )
+        .await;
 
-    if resp_check_deadline.still_time {
-        return bad_request(
-            "Auth",
-            "TimeLeft",
+        if tries == 0 {
+            let time_left = TwoFactorsAuth::block_account(&two_fa, &db).await;
+            record_auth_event(
+                &db,
+                &req,
+                AuthEventKind::Lockout,
+                AuthEventOutcome::Failure,
+                Some(user.id),
+            )
+            .await;
+            return Err(ApiError::TooManyRequests(codes::ACCOUNT_LOCKED, time_left));
+        }
+
+        return Err(ApiError::Unprocessable(
+            codes::CODE_INVALID,
             Some(json!({
-                "timeLeft": resp_check_deadline.time_left,
+                "tries": tries,
             })),
-        );
-    } else {
-        let check_code = TwoFactorsAuth::check_code(&two_fa, &body.code);
-
-        if check_code.valid {
-            let account = json!({
-                "firstName": user.f,
-                "lastName": user.l,
-            });
-
-            let token = CookiePayload {
-                id: user.id,
-                exp_at: Utc::now().timestamp() + MAX_AGE_2J,
-            };
-
-            let session_cookie = create_cookie("SESSIONID", &token);
-
-            return HttpResponse::Ok().cookie(session_cookie).json(json!({
-                "connection test": "ok",
-                "account": account,
-            }));
-        } else {
-            let tries = TwoFactorsAuth::update_pro_by_remove_one_try(&two_fa, &db).await;
-            if tries == 0 {
-                let time_left = TwoFactorsAuth::block_account(&two_fa, &db).await;
-                return bad_request(
-                    "Auth",
-                    "TimeLeft",
-                    Some(json!({
-                        "timeLeft": time_left,
-                    })),
-                );
-            } else {
-                return bad_request(
-                    "Code",
-                    "Invalid",
-                    Some(json!({
-                        "tries": tries,
-                    })),
-                );
-            }
-        }
+        ));
+    }
+
+    let two_fa = TwoFactorsAuth::verify_phone(&two_fa, &db).await?;
+
+    let restored = user.deleted_at.is_some();
+    if restored {
+        UserMutation::restore_user_by_id(&db, user.id).await?;
+    }
+
+    let exp_at = Utc::now().timestamp() + MAX_AGE_2J;
+
+    let session = session_model::ActiveModel {
+        user_id: Set(user.id),
+        expires_at: Set(DateTime::from_timestamp(exp_at, 0).unwrap_or_default()),
+        ..session_model::ActiveModel::new()
+    };
+    let session = SessionMutation::create_session(&db, session).await?;
+
+    let device = DeviceInfo::from_request(&req);
+    match register_login_device(&db, user.id, &device).await {
+        Ok(DeviceLogin::New { report_nonce }) => notify_new_device(
+            &tasks,
+            &user,
+            &device,
+            client_ip(&req),
+            session.id,
+            report_nonce,
+        ),
+        Ok(_) => (),
+        Err(err) => error!("Cannot register device of user {}: {}", user.id, err),
     }
+
+    let account = json!({
+        "firstName": user.first_name,
+        "lastName": user.last_name,
+        "emailVerified": two_fa.email_verified,
+        "phoneVerified": two_fa.phone_verified,
+        "restored": restored,
+    });
+
+    let token = CookiePayload {
+        id: user.id,
+        exp_at,
+        session_id: Some(session.id),
+    };
+
+    let session_cookie = create_cookie("SESSIONID", &token);
+
+    record_auth_event(
+        &db,
+        &req,
+        AuthEventKind::Login,
+        AuthEventOutcome::Success,
+        Some(user.id),
+    )
+    .await;
+
+    Ok(HttpResponse::Ok().cookie(session_cookie).json(json!({
+        "connection test": "ok",
+        "account": account,
+    })))
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{env, sync::Arc};
+
     use crate::{
-        error::resp_errors::RespErrors,
+        error::{api_error::ProblemDetails, codes},
         types::auth::check_code::CheckCodeRequest,
         utils::{
             cookie_utils::{create_cookie, CookiePayload},
+            login_policy_utils::LoginPolicy,
+            task_manager_utils::TaskManager,
             time_utils::MAX_AGE_3M,
         },
     };
@@ -125,12 +184,12 @@ mod tests {
     };
     use chrono::Utc;
     use entity::entities::{
-        two_fa_entity::two_fa_model,
-        user_entity::user_model,
+        known_device_entity::known_device_model, session_entity::session_model,
+        two_fa_entity::two_fa_model, user_entity::user_model,
     };
     use reqwest::StatusCode;
-    use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, MockDatabase};
-    use serde_json::{json, Value};
+    use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, MockDatabase, RuntimeErr};
+    use serde_json::Value;
     use uuid::Uuid;
 
     use super::check_code;
@@ -139,48 +198,94 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 id: 1,
-                v_e: true,
-                t: 3,
-                s: 1,
-                c: Some(String::from("123456")),
-                up: None,
-                ex: 0,
-                v_ph: false,
+                email_verified: true,
+                tries_left: 3,
+                codes_sent: 1,
+                code: Some(String::from("123456")),
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: false,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             }]])
-            .append_query_results([[pro_model::Model {
-                id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                s: String::from("883116000"),
-                cs: true,
-                sa: true,
-                den: Some(String::from("Rob Company")),
+            .append_query_results([[two_fa_model::Model {
+                id: 1,
+                email_verified: true,
+                tries_left: 3,
+                codes_sent: 1,
+                code: Some(String::from("123456")),
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: true,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                ..Default::default()
             }]])
-            .append_query_results([[two_fa_model::Model {
+            .append_query_results([[session()]])
+            .append_query_results([[known_device_model::Model {
                 id: 1,
-                v_e: false,
-                t: 3,
-                s: 0,
-                c: None,
-                up: None,
-                ex: 0,
-                v_ph: false,
+                fingerprint: String::from("another device"),
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                ..Default::default()
+            }]])
+            .append_query_results([[known_device_model::Model {
+                id: 2,
+                user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                ..Default::default()
             }]])
             .into_connection()
     }
 
+    fn mock_db_to_restoring_account() -> DatabaseConnection {
+        let user = user_model::Model {
+            id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            first_name: String::from("Rob"),
+            last_name: String::from("Doe"),
+            email: String::from("test.pro.1@gmail.com"),
+            phone: String::from("0600000001"),
+            terms: true,
+            privacy: true,
+            deleted_at: Some(Utc::now()),
+            ..Default::default()
+        };
+        let two_fa = two_fa_model::Model {
+            id: 1,
+            email_verified: true,
+            tries_left: 3,
+            codes_sent: 1,
+            code: Some(String::from("123456")),
+            locked_until: None,
+            lock_exponent: 0,
+            phone_verified: true,
+            user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+        };
+
+        MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[user.to_owned()]])
+            .append_query_results([[two_fa]])
+            .append_query_results([[user_model::Model {
+                deleted_at: None,
+                ..user
+            }]])
+            .append_query_results([[session()]])
+            .into_connection()
+    }
+
+    fn session() -> session_model::Model {
+        session_model::Model {
+            id: Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap(),
+            user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            ..Default::default()
+        }
+    }
+
     fn mock_db_user_not_found() -> DatabaseConnection {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_errors(vec![DbErr::RecordNotFound("user not found".to_string())])
@@ -191,12 +296,12 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_errors(vec![DbErr::RecordNotFound("two_fa not found".to_string())])
@@ -207,52 +312,54 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 id: 1,
-                v_e: false,
-                t: 3,
-                s: 0,
-                c: None,
-                up: Some((Utc::now().timestamp_millis() as i64) + 300_000), // 5 minutes left
-                ex: 0,
-                v_ph: false,
+                email_verified: false,
+                tries_left: 3,
+                codes_sent: 0,
+                code: None,
+                locked_until: Some(Utc::now().timestamp_millis() + 300_000), // 5 minutes left
+                lock_exponent: 0,
+                phone_verified: false,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             }]])
             .into_connection()
     }
 
-    fn mock_db_pro_not_found() -> DatabaseConnection {
+    fn mock_db_cannot_verify_phone() -> DatabaseConnection {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 id: 1,
-                v_e: true,
-                t: 3,
-                s: 1,
-                c: Some(String::from("123456")),
-                up: None,
-                ex: 0,
-                v_ph: false,
+                email_verified: true,
+                tries_left: 3,
+                codes_sent: 1,
+                code: Some(String::from("123456")),
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: false,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             }]])
-            .append_query_errors(vec![DbErr::RecordNotFound("Pro not found".to_string())])
+            .append_query_errors(vec![DbErr::Query(RuntimeErr::Internal(
+                "Failed to verify phone".to_string(),
+            ))])
             .into_connection()
     }
 
@@ -260,23 +367,23 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 id: 1,
-                v_e: true,
-                t: 2,
-                s: 1,
-                c: Some(String::from("123456")),
-                up: None,
-                ex: 0,
-                v_ph: false,
+                email_verified: true,
+                tries_left: 2,
+                codes_sent: 1,
+                code: Some(String::from("123456")),
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: false,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             }]])
             .into_connection()
@@ -286,23 +393,49 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 id: 1,
-                v_e: true,
-                t: 1,
-                s: 1,
-                c: Some(String::from("123456")),
-                up: None,
-                ex: 0,
-                v_ph: false,
+                email_verified: true,
+                tries_left: 1,
+                codes_sent: 1,
+                code: Some(String::from("123456")),
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: false,
+                user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            }]])
+            .into_connection()
+    }
+
+    fn mock_db_unverified_account() -> DatabaseConnection {
+        MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[user_model::Model {
+                id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
+                ..Default::default()
+            }]])
+            .append_query_results([[two_fa_model::Model {
+                id: 1,
+                email_verified: false,
+                tries_left: 3,
+                codes_sent: 1,
+                code: Some(String::from("123456")),
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: false,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             }]])
             .into_connection()
@@ -315,6 +448,8 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(db_data)
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(Data::new(LoginPolicy::default()))
                 .service(web::scope("/api").service(check_code)),
         )
         .await;
@@ -324,6 +459,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -351,55 +487,45 @@ mod tests {
 
         assert_eq!(resp_body["connection test"], "ok");
         assert_eq!(resp_body["account"]["firstName"], "Rob");
-        assert_eq!(resp_body["account"]["tva"], false);
-
-        assert_eq!(
-            resp_body["account"]["addresses"][0]["address"],
-            "5 rue de la justice"
-        );
-        assert_eq!(resp_body["account"]["addresses"][0]["postal"], "60000");
-        assert_eq!(resp_body["account"]["addresses"][0]["city"], "Perpignan");
-
-        assert_eq!(
-            resp_body["account"]["services"][0]["description"],
-            "Thérapie EMDR"
-        );
-        assert_eq!(resp_body["account"]["services"][0]["duration"], 1);
-        assert_eq!(resp_body["account"]["services"][0]["unity"], "Heure");
-        assert_eq!(resp_body["account"]["services"][0]["price"], 75);
-        assert_eq!(resp_body["account"]["services"][0]["status"], "unchanged");
-
-        assert_eq!(
-            resp_body["account"]["services"][1]["description"],
-            "Thérapie de couple"
-        );
-        assert_eq!(resp_body["account"]["services"][1]["duration"], 45);
-        assert_eq!(resp_body["account"]["services"][1]["unity"], "Minute");
-        assert_eq!(resp_body["account"]["services"][1]["price"], 60);
-        assert_eq!(resp_body["account"]["services"][1]["status"], "unchanged");
-
-        assert_eq!(resp_body["account"]["formTemplates"], json!([]));
-
-        assert_eq!(
-            resp_body["account"]["rdvTemplates"][0]["title"],
-            "Consultation individuel"
-        );
-        assert_eq!(
-            resp_body["account"]["rdvTemplates"][0]["avatarId"],
-            "A0D49C-3C5337-0"
-        );
-        assert_eq!(resp_body["account"]["rdvTemplates"][0]["formTemplateId"], 0);
-        assert_eq!(resp_body["account"]["rdvTemplates"][0]["addressId"], 1);
-        assert_eq!(resp_body["account"]["rdvTemplates"][0]["serviceId"], 1);
-        assert_eq!(resp_body["account"]["rdvTemplates"][0]["max"], 1);
-
-        assert_eq!(
-            resp_body["planning"]["config"]["startCurrentDay"],
-            28_800_000
-        );
-        assert_eq!(resp_body["planning"]["config"]["endCurrentDay"], 64_800_000);
-        assert_eq!(resp_body["planning"]["usersSuggestions"], json!([]));
-        assert_eq!(resp_body["planning"]["weeks"], json!([]));
+        assert_eq!(resp_body["account"]["emailVerified"], true);
+        assert_eq!(resp_body["account"]["phoneVerified"], true);
+        assert_eq!(resp_body["account"]["restored"], false);
+    }
+
+    #[actix_web::test]
+    async fn test_checkout_code_restores_deleted_account() {
+        let db_data: Data<DatabaseConnection> = Data::new(mock_db_to_restoring_account());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(Data::new(LoginPolicy::default()))
+                .service(web::scope("/api").service(check_code)),
+        )
+        .await;
+
+        let token = CookiePayload {
+            id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            exp_at: Utc::now().timestamp() + MAX_AGE_3M,
+            session_id: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/api/checkcode")
+            .set_json(&CheckCodeRequest {
+                code: String::from("123456"),
+            })
+            .cookie(create_cookie("token", &token))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body["account"]["restored"], true);
     }
 
     #[actix_web::test]
@@ -409,6 +535,8 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(db_data)
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(Data::new(LoginPolicy::default()))
                 .service(web::scope("/api").service(check_code)),
         )
         .await;
@@ -423,7 +551,7 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
     }
 
     #[actix_web::test]
@@ -433,6 +561,8 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(db_data)
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(Data::new(LoginPolicy::default()))
                 .service(web::scope("/api").service(check_code)),
         )
         .await;
@@ -442,6 +572,7 @@ mod tests {
         let expired_token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let expired_cookie = create_cookie("token", &expired_token);
@@ -457,14 +588,12 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<Value> = serde_json::from_slice(&body).unwrap();
-
-        assert_eq!(resp_errors.kind, "Token");
-        assert_eq!(resp_errors.reason, "Expired");
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::TOKEN_EXPIRED);
     }
 
     #[actix_web::test]
@@ -474,6 +603,8 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(db_data)
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(Data::new(LoginPolicy::default()))
                 .service(web::scope("/api").service(check_code)),
         )
         .await;
@@ -483,6 +614,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -498,7 +630,7 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
     #[actix_web::test]
@@ -508,6 +640,8 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(db_data)
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(Data::new(LoginPolicy::default()))
                 .service(web::scope("/api").service(check_code)),
         )
         .await;
@@ -517,6 +651,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -532,7 +667,7 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
     #[actix_web::test]
@@ -542,6 +677,8 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(db_data)
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(Data::new(LoginPolicy::default()))
                 .service(web::scope("/api").service(check_code)),
         )
         .await;
@@ -551,6 +688,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -566,23 +704,23 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<Value> = serde_json::from_slice(&body).unwrap();
-
-        assert_eq!(resp_errors.kind, "Auth");
-        assert_eq!(resp_errors.reason, "TimeLeft");
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::ACCOUNT_LOCKED);
     }
 
     #[actix_web::test]
-    async fn test_sending_code_pro_not_found() {
-        let db_data: Data<DatabaseConnection> = Data::new(mock_db_pro_not_found());
+    async fn test_cannot_verify_phone() {
+        let db_data: Data<DatabaseConnection> = Data::new(mock_db_cannot_verify_phone());
 
         let app = test::init_service(
             App::new()
-                .app_data(db_data)
+                .app_data(db_data.clone())
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(Data::new(LoginPolicy::default()))
                 .service(web::scope("/api").service(check_code)),
         )
         .await;
@@ -592,6 +730,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -608,6 +747,18 @@ mod tests {
         let resp = test::call_service(&app, req).await;
 
         assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(resp
+            .response()
+            .cookies()
+            .all(|cookie| cookie.name() != "SESSIONID"));
+
+        drop(resp);
+        drop(app);
+        let log = Arc::try_unwrap(db_data.into_inner())
+            .unwrap()
+            .into_transaction_log();
+        assert_eq!(log.len(), 3);
+        assert!(format!("{:?}", log[2]).contains(r#"UPDATE \"two_fa\""#));
     }
 
     #[actix_web::test]
@@ -617,6 +768,8 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(db_data)
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(Data::new(LoginPolicy::default()))
                 .service(web::scope("/api").service(check_code)),
         )
         .await;
@@ -626,6 +779,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -641,16 +795,14 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<Value> = serde_json::from_slice(&body).unwrap();
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::CODE_INVALID);
 
-        assert_eq!(resp_errors.kind, "Code");
-        assert_eq!(resp_errors.reason, "Invalid");
-
-        assert_eq!(resp_errors.errors.unwrap()["tries"], 1);
+        assert_eq!(problem.errors.unwrap()["tries"], 1);
     }
 
     #[actix_web::test]
@@ -660,6 +812,8 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(db_data)
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(Data::new(LoginPolicy::default()))
                 .service(web::scope("/api").service(check_code)),
         )
         .await;
@@ -669,6 +823,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -684,13 +839,65 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<Value> = serde_json::from_slice(&body).unwrap();
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::ACCOUNT_LOCKED);
+    }
+
+    #[actix_web::test]
+    async fn test_unverified_account_is_refused() {
+        env::set_var("LOGIN_REQUIRE_VERIFIED_EMAIL", "true");
+        env::set_var("LOGIN_REQUIRE_VERIFIED_PHONE", "true");
+
+        let db_data: Data<DatabaseConnection> = Data::new(mock_db_unverified_account());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data.clone())
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(Data::new(LoginPolicy::from_env()))
+                .service(web::scope("/api").service(check_code)),
+        )
+        .await;
+
+        let user_id = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let expires_at = Utc::now().timestamp() + MAX_AGE_3M;
+        let token = CookiePayload {
+            id: user_id,
+            exp_at: expires_at,
+            session_id: None,
+        };
+
+        let cookie = create_cookie("token", &token);
+
+        let req = test::TestRequest::post()
+            .uri("/api/checkcode")
+            .set_json(&CheckCodeRequest {
+                code: String::from("123456"),
+            })
+            .cookie(cookie)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let body = test::read_body(resp).await;
+
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::ACCOUNT_UNVERIFIED);
+
+        let errors = problem.errors.unwrap();
+        assert_eq!(errors["email"], true);
+        assert_eq!(errors["phone"], true);
 
-        assert_eq!(resp_errors.kind, "Auth");
-        assert_eq!(resp_errors.reason, "TimeLeft");
+        drop(app);
+        let log = Arc::try_unwrap(db_data.into_inner())
+            .unwrap()
+            .into_transaction_log();
+        assert_eq!(log.len(), 3);
+        assert!(format!("{:?}", log[2]).contains(r#"INSERT INTO \"auth_events\""#));
     }
 }
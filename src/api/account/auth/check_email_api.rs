@@ -1,7 +1,8 @@
 use crate::{
-    error::bad_request,
+    error::{api_error::ApiError, codes},
     types::auth::check_email::CheckEmailDataRequest,
     utils::{
+        auth_event_utils::record_auth_event,
         cookie_utils::{create_cookie, CookiePayload},
         jwt_utils::decode_token,
         time_utils::MAX_AGE_1H_TEST,
@@ -11,74 +12,79 @@ use crate::{
 use actix_web::{
     post,
     web::{Data, Json},
-    HttpResponse,
+    HttpRequest, HttpResponse,
 };
 use chrono::Utc;
+use entity::entities::auth_event_entity::auth_event_model::{AuthEventKind, AuthEventOutcome};
 use sea_orm::DatabaseConnection;
-use serde_json::json;
 use service::query::user_queries::UserQuery;
-use tracing::error;
 
 #[post("/checkemail")]
 pub async fn check_email(
+    req: HttpRequest,
     db: Data<DatabaseConnection>,
     body: Json<CheckEmailDataRequest>,
-) -> HttpResponse {
-    let token = match body.token.to_owned() {
-        Some(t) => t,
-        None => {
-            error!("Cannot get token on check email");
-            return HttpResponse::InternalServerError().finish();
-        }
-    };
+) -> Result<HttpResponse, ApiError> {
+    let token = body
+        .token
+        .to_owned()
+        .ok_or(ApiError::BadRequest(codes::TOKEN_MISSING))?;
 
     let decrypted_token = match decode_token(token.as_str()) {
         Ok(tk) => tk,
-        Err(resp_error) => return HttpResponse::BadRequest().json(resp_error),
-    };
-
-    let user = match UserQuery::find_user_by_id(&db, decrypted_token.payload).await {
-        Ok(u) => u,
-        Err(_) => return HttpResponse::InternalServerError().finish(),
+        Err(err) => {
+            record_auth_event(
+                &db,
+                &req,
+                AuthEventKind::MagicLinkClicked,
+                AuthEventOutcome::Failure,
+                None,
+            )
+            .await;
+            return Err(err);
+        }
     };
 
-    let two_fa = match UserQuery::find_related_two_fa(&db, &user).await {
-        Ok(t) => t,
-        Err(_) => return HttpResponse::InternalServerError().finish(),
-    };
+    let user = UserQuery::find_user_by_id_with_deleted(&db, decrypted_token.payload).await?;
+    let two_fa = UserQuery::find_related_two_fa(&db, &user).await?;
 
     let resp_check_deadline = TwoFactorsAuth::check_deadline(&two_fa);
 
     if resp_check_deadline.still_time {
-        return bad_request(
-            "Auth",
-            "TimeLeft",
-            Some(json!({
-                "timeLeft": resp_check_deadline.time_left,
-            })),
-        );
-    } else {
-        match TwoFactorsAuth::reset_tries(&two_fa, &db).await {
-            Ok(_) => (),
-            Err(_) => return HttpResponse::InternalServerError().finish(),
-        }
+        return Err(ApiError::TooManyRequests(
+            codes::ACCOUNT_LOCKED,
+            resp_check_deadline.time_left,
+        ));
+    }
 
-        let expires_at = Utc::now().timestamp() + MAX_AGE_1H_TEST;
-        let token = CookiePayload {
-            id: user.id,
-            exp_at: expires_at,
-        };
+    TwoFactorsAuth::reset_tries(&two_fa, &db).await?;
+    TwoFactorsAuth::verify_email(&two_fa, &db).await?;
 
-        let cookie = create_cookie("token", &token);
+    let expires_at = Utc::now().timestamp() + MAX_AGE_1H_TEST;
+    let token = CookiePayload {
+        id: user.id,
+        exp_at: expires_at,
+        session_id: None,
+    };
 
-        return HttpResponse::Ok().cookie(cookie).finish();
-    }
+    let cookie = create_cookie("token", &token);
+
+    record_auth_event(
+        &db,
+        &req,
+        AuthEventKind::MagicLinkClicked,
+        AuthEventOutcome::Success,
+        Some(user.id),
+    )
+    .await;
+
+    Ok(HttpResponse::Ok().cookie(cookie).finish())
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        error::resp_errors::RespErrors,
+        error::{api_error::ProblemDetails, codes},
         utils::{jwt_utils::create_token, time_utils::MAX_AGE_3M},
     };
     use actix_web::{
@@ -89,8 +95,7 @@ mod tests {
     use chrono::Utc;
     use entity::entities::{two_fa_entity::two_fa_model, user_entity::user_model};
     use reqwest::StatusCode;
-    use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, MockDatabase, MockExecResult, RuntimeErr};
-    use serde_json::Value;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, MockDatabase, RuntimeErr};
     use uuid::Uuid;
 
     use super::{check_email, CheckEmailDataRequest};
@@ -99,29 +104,65 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 id: 1,
-                v_e: false,
-                t: 3,
-                s: 0,
-                c: None,
-                up: None,
-                ex: 0,
-                v_ph: false,
+                email_verified: false,
+                tries_left: 3,
+                codes_sent: 0,
+                code: None,
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: false,
+                user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            }]])
+            .append_query_results([[two_fa_model::Model {
+                id: 1,
+                email_verified: true,
+                tries_left: 3,
+                codes_sent: 0,
+                code: None,
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: false,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             }]])
-            .append_exec_results([MockExecResult {
-                last_insert_id: 1,
-                rows_affected: 1,
-            }])
+            .into_connection()
+    }
+
+    fn mock_db_cannot_verify_email() -> DatabaseConnection {
+        MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[user_model::Model {
+                id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
+                ..Default::default()
+            }]])
+            .append_query_results([[two_fa_model::Model {
+                id: 1,
+                email_verified: false,
+                tries_left: 3,
+                codes_sent: 0,
+                code: None,
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: false,
+                user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            }]])
+            .append_query_errors(vec![DbErr::Query(RuntimeErr::Internal(
+                "Failed to verify email".to_string(),
+            ))])
             .into_connection()
     }
 
@@ -135,12 +176,12 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_errors(vec![DbErr::RecordNotFound("two_fa not found".to_string())])
@@ -151,23 +192,23 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 id: 1,
-                v_e: false,
-                t: 3,
-                s: 0,
-                c: None,
-                up: Some((Utc::now().timestamp_millis() as i64) + 300_000), // 5 minutes left
-                ex: 0,
-                v_ph: false,
+                email_verified: false,
+                tries_left: 3,
+                codes_sent: 0,
+                code: None,
+                locked_until: Some(Utc::now().timestamp_millis() + 300_000), // 5 minutes left
+                lock_exponent: 0,
+                phone_verified: false,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             }]])
             .into_connection()
@@ -177,23 +218,23 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 id: 1,
-                v_e: false,
-                t: 0,
-                s: 2,
-                c: None,
-                up: None,
-                ex: 0,
-                v_ph: false,
+                email_verified: false,
+                tries_left: 0,
+                codes_sent: 2,
+                code: None,
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: false,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             }]])
             .append_query_errors(vec![DbErr::Query(RuntimeErr::Internal(
@@ -252,7 +293,7 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
     }
 
     #[actix_web::test]
@@ -277,7 +318,7 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
     #[actix_web::test]
@@ -302,7 +343,7 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
     #[actix_web::test]
@@ -328,14 +369,13 @@ mod tests {
 
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<Value> = serde_json::from_slice(&body).unwrap();
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
 
-        assert_eq!(resp_errors.kind, "Auth");
-        assert_eq!(resp_errors.reason, "TimeLeft");
+        assert_eq!(problem.code, codes::ACCOUNT_LOCKED);
     }
 
     #[actix_web::test]
@@ -360,6 +400,30 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    #[actix_web::test]
+    async fn test_cannot_verify_email() {
+        let db_data: Data<DatabaseConnection> = Data::new(mock_db_cannot_verify_email());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .service(web::scope("/api").service(check_email)),
+        )
+        .await;
+
+        let user_id = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let req_data = CheckEmailDataRequest {
+            token: Some(create_token(&user_id, Utc::now().timestamp() + MAX_AGE_3M)),
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/api/checkemail")
+            .set_json(&req_data)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
         assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
 }
@@ -0,0 +1,125 @@
+use crate::{
+    error::{api_error::ApiError, codes},
+    middlewares::authenticated_user::AuthenticatedUser,
+    utils::{
+        auth_event_utils::record_auth_event,
+        cookie_utils::{delete_cookie, get_cookie_from_http_request, CookiePayload},
+        crypto_utils::{decrypt_payload, EncryptedPayload},
+    },
+};
+use actix_web::{post, web::Data, HttpRequest, HttpResponse};
+use entity::entities::auth_event_entity::auth_event_model::{AuthEventKind, AuthEventOutcome};
+use sea_orm::DatabaseConnection;
+use service::mutation::session_mutations::SessionMutation;
+use tracing::error;
+
+/// Revokes the session behind the `SESSIONID` cookie, other devices stay logged in.
+#[post("/logout")]
+pub async fn logout(
+    req: HttpRequest,
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    let session_id = get_cookie_from_http_request(&req, "SESSIONID")
+        .and_then(|cookie| serde_json::from_str::<EncryptedPayload>(cookie.as_str()).ok())
+        .and_then(|token| decrypt_payload::<CookiePayload>(&token.order, &token.content).ok())
+        .and_then(|payload| payload.session_id);
+
+    if let Some(session_id) = session_id {
+        if let Err(err) = SessionMutation::revoke_session(&db, session_id).await {
+            error!("Cannot revoke session {}: {}", session_id, err);
+            record_auth_event(
+                &db,
+                &req,
+                AuthEventKind::Logout,
+                AuthEventOutcome::Failure,
+                Some(user_id),
+            )
+            .await;
+            return Err(ApiError::Internal(codes::DATABASE));
+        }
+    }
+
+    record_auth_event(
+        &db,
+        &req,
+        AuthEventKind::Logout,
+        AuthEventOutcome::Success,
+        Some(user_id),
+    )
+    .await;
+
+    Ok(HttpResponse::Ok()
+        .cookie(delete_cookie("SESSIONID"))
+        .finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use chrono::Utc;
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase, MockExecResult};
+    use uuid::Uuid;
+
+    use crate::{
+        middlewares::authenticated_user::test_utils::AuthenticateAs,
+        utils::{
+            cookie_utils::{create_cookie, CookiePayload},
+            time_utils::MAX_AGE_2J,
+        },
+    };
+
+    use super::logout;
+
+    #[actix_web::test]
+    async fn test_logout_revokes_the_session() {
+        let user_id = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_exec_results([
+                    MockExecResult {
+                        last_insert_id: 0,
+                        rows_affected: 1,
+                    },
+                    MockExecResult {
+                        last_insert_id: 0,
+                        rows_affected: 1,
+                    },
+                ])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(AuthenticateAs(user_id))
+                .service(web::scope("/account").service(logout)),
+        )
+        .await;
+
+        let token = CookiePayload {
+            id: user_id,
+            exp_at: Utc::now().timestamp() + MAX_AGE_2J,
+            session_id: Some(Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap()),
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/account/logout")
+            .cookie(create_cookie("SESSIONID", &token))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let cookie = resp.response().cookies().next().unwrap();
+        assert_eq!(cookie.name(), "SESSIONID");
+        assert_eq!(cookie.value(), "");
+    }
+}
@@ -1,4 +1,6 @@
 pub mod check_email_api;
 pub mod send_code_api;
 pub mod check_code_api;
-pub mod redirect_to_auth;
\ No newline at end of file
+pub mod redirect_to_auth;
+pub mod logout_api;
+pub mod not_me_api;
\ No newline at end of file
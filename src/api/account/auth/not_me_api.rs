@@ -0,0 +1,300 @@
+use crate::{
+    error::{api_error::ApiError, codes},
+    i18n::{catalog::translate, language::language_code},
+    types::auth::new_device::NewDevicePayload,
+    utils::{jwt_utils::decode_token, two_factors_auth_utils::TwoFactorsAuth},
+};
+use actix_web::{
+    get,
+    http::header::{self, ContentType},
+    post,
+    web::{Data, Path},
+    HttpResponse,
+};
+use entity::entities::user_entity::user_model::Language;
+use sea_orm::DatabaseConnection;
+use service::{
+    mutation::{known_device_mutations::KnownDeviceMutation, session_mutations::SessionMutation},
+    query::{known_device_queries::KnownDeviceQuery, user_queries::UserQuery},
+};
+
+/// Target of the "this wasn't me" link of the new device email. Mail scanners
+/// follow links, so it only asks to confirm and `revoke_new_device` acts.
+#[get("/not_me/{token}")]
+pub async fn report_new_device(
+    db: Data<DatabaseConnection>,
+    path: Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let payload = decode_token::<NewDevicePayload>(path.as_str())?.payload;
+
+    if KnownDeviceQuery::find_known_device_by_report_nonce(&db, payload.user_id, &payload.nonce)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::NotFound(codes::NOT_FOUND));
+    }
+    let user = UserQuery::find_user_by_id_with_deleted(&db, payload.user_id).await?;
+
+    let body = format!(
+        "<p>{}</p><form method=\"post\"><button type=\"submit\">{}</button></form>",
+        translate(&user.language, "page-not-me-text", None),
+        translate(&user.language, "page-not-me-confirm", None),
+    );
+    Ok(page(&user.language, &body))
+}
+
+/// The device is forgotten and both contacts must be verified again before
+/// the next login. It is deleted last, so that the link can be followed again
+/// if one of the steps before fails.
+#[post("/not_me/{token}")]
+pub async fn revoke_new_device(
+    db: Data<DatabaseConnection>,
+    path: Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let payload = decode_token::<NewDevicePayload>(path.as_str())?.payload;
+
+    if KnownDeviceQuery::find_known_device_by_report_nonce(&db, payload.user_id, &payload.nonce)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::NotFound(codes::NOT_FOUND));
+    }
+
+    SessionMutation::revoke_session(&db, payload.session_id).await?;
+
+    let user = UserQuery::find_user_by_id_with_deleted(&db, payload.user_id).await?;
+    let two_fa = UserQuery::find_related_two_fa(&db, &user).await?;
+
+    two_fa.reset_validation_system(&db).await;
+
+    KnownDeviceMutation::delete_known_device_by_report_nonce(&db, payload.user_id, &payload.nonce)
+        .await?;
+
+    let body = format!(
+        "<p>{}</p>",
+        translate(&user.language, "page-not-me-done", None)
+    );
+    Ok(page(&user.language, &body))
+}
+
+/// The token is in the url, it must not leak through the referrer or a cache.
+fn page(lg: &Language, body: &str) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .insert_header((header::REFERRER_POLICY, "no-referrer"))
+        .body(format!(
+            "<!DOCTYPE html><html lang=\"{}\"><head><meta charset=\"utf-8\"><title>{}</title></head><body><h1>{}</h1>{}</body></html>",
+            language_code(lg),
+            translate(lg, "page-not-me-title", None),
+            translate(lg, "page-not-me-title", None),
+            body
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use actix_web::{
+        http::header,
+        test,
+        web::{self, Data},
+        App,
+    };
+    use chrono::Utc;
+    use entity::entities::{
+        known_device_entity::known_device_model,
+        two_fa_entity::two_fa_model,
+        user_entity::user_model::{self, Language},
+    };
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase, MockExecResult};
+    use uuid::Uuid;
+
+    use crate::{
+        types::auth::new_device::NewDevicePayload,
+        utils::{jwt_utils::create_token, time_utils::MAX_AGE_7J},
+    };
+
+    use super::{report_new_device, revoke_new_device};
+
+    fn user_id() -> Uuid {
+        Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap()
+    }
+
+    fn known_device() -> known_device_model::Model {
+        known_device_model::Model {
+            id: 2,
+            fingerprint: String::from("fingerprint"),
+            report_nonce: Some(String::from("nonce")),
+            user_id: user_id(),
+            ..Default::default()
+        }
+    }
+
+    fn user() -> user_model::Model {
+        user_model::Model {
+            id: user_id(),
+            language: Language::En,
+            ..Default::default()
+        }
+    }
+
+    fn not_me_token() -> String {
+        create_token(
+            &NewDevicePayload {
+                user_id: user_id(),
+                session_id: Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap(),
+                nonce: String::from("nonce"),
+            },
+            Utc::now().timestamp() + MAX_AGE_7J,
+        )
+    }
+
+    #[actix_web::test]
+    async fn test_report_new_device_asks_to_confirm() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[known_device()]])
+                .append_query_results([[user()]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data.clone())
+                .service(web::scope("/auth/pro").service(report_new_device)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/auth/pro/not_me/{}", not_me_token()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::REFERRER_POLICY).unwrap(),
+            "no-referrer"
+        );
+
+        let body = test::read_body(resp).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains("<form method=\"post\">"));
+        assert!(body.contains("Sign out this device"));
+
+        drop(app);
+        let log = Arc::try_unwrap(db_data.into_inner())
+            .unwrap()
+            .into_transaction_log();
+        assert_eq!(log.len(), 2);
+        assert!(log
+            .iter()
+            .all(|transaction| format!("{:?}", transaction).contains("SELECT")));
+    }
+
+    #[actix_web::test]
+    async fn test_revoke_new_device_success() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[known_device()]])
+                .append_exec_results([
+                    MockExecResult {
+                        last_insert_id: 0,
+                        rows_affected: 1,
+                    },
+                    MockExecResult {
+                        last_insert_id: 0,
+                        rows_affected: 1,
+                    },
+                ])
+                .append_query_results([[user()]])
+                .append_query_results([[two_fa_model::Model {
+                    id: 1,
+                    email_verified: true,
+                    phone_verified: true,
+                    user_id: user_id(),
+                    ..Default::default()
+                }]])
+                .append_query_results([[two_fa_model::Model {
+                    id: 1,
+                    tries_left: 3,
+                    user_id: user_id(),
+                    ..Default::default()
+                }]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data.clone())
+                .service(web::scope("/auth/pro").service(revoke_new_device)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/auth/pro/not_me/{}", not_me_token()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains("The device is signed out."));
+
+        drop(app);
+        let log = Arc::try_unwrap(db_data.into_inner())
+            .unwrap()
+            .into_transaction_log();
+        let delete = format!("{:?}", log.last().unwrap());
+        assert!(delete.contains(r#"DELETE FROM \"known_devices\""#));
+        assert!(delete.contains(r#"String(Some("nonce"))"#));
+    }
+
+    #[actix_web::test]
+    async fn test_revoke_new_device_link_used() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([Vec::<known_device_model::Model>::new()])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .service(web::scope("/auth/pro").service(revoke_new_device)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/auth/pro/not_me/{}", not_me_token()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_report_new_device_invalid_token() {
+        let db_data: Data<DatabaseConnection> =
+            Data::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .service(web::scope("/auth/pro").service(report_new_device)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/auth/pro/not_me/invalid")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -1,94 +1,90 @@
 use crate::{
-    error::{bad_request, internal_server_error},
+    error::{api_error::ApiError, codes},
     utils::{
-        cookie_utils::{create_cookie, CookiePayload, get_cookie_from_http_request},
-        crypto_utils::{decrypt_payload, EncryptedPayload},
+        auth_event_utils::record_auth_event,
+        cookie_utils::{create_cookie, read_cookie_payload, CookiePayload},
         time_utils::MAX_AGE_3M,
         two_factors_auth_utils::{SendingState, TwoFactorsAuth},
     },
 };
 use actix_web::{post, web::Data, HttpRequest, HttpResponse};
 use chrono::Utc;
+use entity::entities::auth_event_entity::auth_event_model::{AuthEventKind, AuthEventOutcome};
 use sea_orm::DatabaseConnection;
 use serde_json::json;
 use service::query::user_queries::UserQuery;
 
 #[post("/sendcode")]
-pub async fn send_code(req: HttpRequest, db: Data<DatabaseConnection>) -> HttpResponse {
-    let cookie = match get_cookie_from_http_request(&req, "token") {
-        Some(c) => c,
-        None => {
-            return HttpResponse::InternalServerError().finish();
-        }
-    };
-
-    let token = serde_json::from_str::<EncryptedPayload>(&cookie.as_str()).unwrap();
-
-    let cookie_payload: CookiePayload = match decrypt_payload(&token.order, &token.content) {
-        Ok(p) => p,
-        Err(_) => {
-            return HttpResponse::InternalServerError().finish();
-        }
-    };
-
-    if cookie_payload.exp_at < Utc::now().timestamp() {
-        return bad_request::<String>("Token", "Expired", None);
-    }
-
-    let user = match UserQuery::find_user_by_id(&db, cookie_payload.id).await {
-        Ok(u) => u,
-        Err(_) => return HttpResponse::InternalServerError().finish(),
-    };
+pub async fn send_code(
+    req: HttpRequest,
+    db: Data<DatabaseConnection>,
+) -> Result<HttpResponse, ApiError> {
+    let cookie_payload = read_cookie_payload(&req, "token")?;
 
-    let two_fa = match UserQuery::find_related_two_fa(&db, &user).await {
-        Ok(t) => t,
-        Err(_) => return HttpResponse::InternalServerError().finish(),
-    };
+    let user = UserQuery::find_user_by_id_with_deleted(&db, cookie_payload.id).await?;
+    let two_fa = UserQuery::find_related_two_fa(&db, &user).await?;
 
     let resp_check_deadline = TwoFactorsAuth::check_deadline(&two_fa);
 
     if resp_check_deadline.still_time {
-        return bad_request(
-            "Auth",
-            "TimeLeft",
-            Some(json!({
-                "timeLeft": resp_check_deadline.time_left,
-            })),
-        );
-    } else {
-        let code = TwoFactorsAuth::generate_code(&two_fa);
-        TwoFactorsAuth::update_two_fa_with_new_code(&two_fa, &code, &db).await;
-        let send_code = TwoFactorsAuth::send_code_to_pro(&two_fa, user.to_owned(), &code).await;
-
-        match send_code {
-            SendingState::Sent => {
-                let num_of_sending =
-                    TwoFactorsAuth::update_two_fa_with_new_num_of_sending(&two_fa, &db).await;
-
-                let token = CookiePayload {
-                    id: user.id,
-                    exp_at: Utc::now().timestamp() + MAX_AGE_3M,
-                };
-
-                let cookie = create_cookie("token", &token);
-
-                return HttpResponse::Ok().cookie(cookie).json(json!({
-                    "sent": num_of_sending,
-                }));
-            }
-            SendingState::AlreadySent => {
-                let time_left = TwoFactorsAuth::block_account(&two_fa, &db).await;
-                return bad_request(
-                    "Auth",
-                    "TimeLeft",
-                    Some(json!({
-                        "timeLeft": time_left,
-                    })),
-                );
-            }
-            SendingState::NotSent => {
-                return internal_server_error::<String>("SMS", "NotSent", None);
-            }
+        return Err(ApiError::TooManyRequests(
+            codes::ACCOUNT_LOCKED,
+            resp_check_deadline.time_left,
+        ));
+    }
+
+    let code = TwoFactorsAuth::generate_code(&two_fa);
+    TwoFactorsAuth::update_two_fa_with_new_code(&two_fa, &code, &db).await;
+    let send_code = TwoFactorsAuth::send_code_to_pro(&two_fa, user.to_owned(), &code).await;
+
+    match send_code {
+        SendingState::Sent => {
+            let num_of_sending =
+                TwoFactorsAuth::update_two_fa_with_new_num_of_sending(&two_fa, &db).await;
+
+            let token = CookiePayload {
+                id: user.id,
+                exp_at: Utc::now().timestamp() + MAX_AGE_3M,
+                session_id: None,
+            };
+
+            let cookie = create_cookie("token", &token);
+
+            record_auth_event(
+                &db,
+                &req,
+                AuthEventKind::CodeSent,
+                AuthEventOutcome::Success,
+                Some(user.id),
+            )
+            .await;
+
+            Ok(HttpResponse::Ok().cookie(cookie).json(json!({
+                "sent": num_of_sending,
+            })))
+        }
+        SendingState::AlreadySent => {
+            let time_left = TwoFactorsAuth::block_account(&two_fa, &db).await;
+            record_auth_event(
+                &db,
+                &req,
+                AuthEventKind::Lockout,
+                AuthEventOutcome::Failure,
+                Some(user.id),
+            )
+            .await;
+            Err(ApiError::TooManyRequests(codes::ACCOUNT_LOCKED, time_left))
+        }
+        SendingState::NotSent => {
+            record_auth_event(
+                &db,
+                &req,
+                AuthEventKind::CodeSent,
+                AuthEventOutcome::Failure,
+                Some(user.id),
+            )
+            .await;
+            Err(ApiError::Internal(codes::SMS_NOT_SENT))
         }
     }
 }
@@ -96,7 +92,7 @@ pub async fn send_code(req: HttpRequest, db: Data<DatabaseConnection>) -> HttpRe
 #[cfg(test)]
 mod tests {
     use crate::{
-        error::resp_errors::RespErrors,
+        error::{api_error::ProblemDetails, codes},
         utils::{
             cookie_utils::{create_cookie, CookiePayload},
             time_utils::MAX_AGE_3M,
@@ -120,23 +116,23 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 id: 1,
-                v_e: false,
-                t: 3,
-                s: 0,
-                c: None,
-                up: None,
-                ex: 0,
-                v_ph: false,
+                email_verified: false,
+                tries_left: 3,
+                codes_sent: 0,
+                code: None,
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: false,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             }]])
             .into_connection()
@@ -152,12 +148,12 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_errors(vec![DbErr::RecordNotFound("two_fa not found".to_string())])
@@ -168,23 +164,23 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 id: 1,
-                v_e: false,
-                t: 3,
-                s: 0,
-                c: None,
-                up: Some((Utc::now().timestamp_millis() as i64) + 300_000), // 5 minutes left
-                ex: 0,
-                v_ph: false,
+                email_verified: false,
+                tries_left: 3,
+                codes_sent: 0,
+                code: None,
+                locked_until: Some(Utc::now().timestamp_millis() + 300_000), // 5 minutes left
+                lock_exponent: 0,
+                phone_verified: false,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             }]])
             .into_connection()
@@ -194,23 +190,23 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 id: 1,
-                v_e: false,
-                t: 3,
-                s: 2, // 2 sent code already
-                c: None,
-                up: None,
-                ex: 0,
-                v_ph: false,
+                email_verified: false,
+                tries_left: 3,
+                codes_sent: 2, // 2 sent code already
+                code: None,
+                locked_until: None,
+                lock_exponent: 0,
+                phone_verified: false,
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             }]])
             .into_connection()
@@ -232,6 +228,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -269,7 +266,7 @@ mod tests {
         let req = test::TestRequest::post().uri("/api/sendcode").to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
     }
 
     #[actix_web::test]
@@ -288,6 +285,7 @@ mod tests {
         let expired_token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let expired_cookie = create_cookie("token", &expired_token);
@@ -298,14 +296,12 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<Value> = serde_json::from_slice(&body).unwrap();
-
-        assert_eq!(resp_errors.kind, "Token");
-        assert_eq!(resp_errors.reason, "Expired");
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::TOKEN_EXPIRED);
     }
 
     #[actix_web::test]
@@ -324,6 +320,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -334,7 +331,7 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
     #[actix_web::test]
@@ -353,6 +350,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -363,7 +361,7 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
     #[actix_web::test]
@@ -382,6 +380,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -392,14 +391,12 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<Value> = serde_json::from_slice(&body).unwrap();
-
-        assert_eq!(resp_errors.kind, "Auth");
-        assert_eq!(resp_errors.reason, "TimeLeft");
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::ACCOUNT_LOCKED);
     }
 
     #[actix_web::test]
@@ -418,6 +415,7 @@ mod tests {
         let token = CookiePayload {
             id: user_id,
             exp_at: expires_at,
+            session_id: None,
         };
 
         let cookie = create_cookie("token", &token);
@@ -428,13 +426,11 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<Value> = serde_json::from_slice(&body).unwrap();
-
-        assert_eq!(resp_errors.kind, "Auth");
-        assert_eq!(resp_errors.reason, "TimeLeft");
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::ACCOUNT_LOCKED);
     }
 }
@@ -0,0 +1,163 @@
+use crate::{
+    error::{api_error::ApiError, codes, ok_response},
+    middlewares::authenticated_user::AuthenticatedUser,
+    types::calendar::calendar_feed::CalendarFeedPayload,
+    utils::{jwt_utils::create_token, time_utils::MAX_AGE_10Y},
+};
+use actix_web::{delete, post, web::Data, HttpRequest, HttpResponse};
+use chrono::Utc;
+use entity::entities::calendar_token_entity::calendar_token_model;
+use nanoid::nanoid;
+use sea_orm::{ActiveModelBehavior, ActiveValue::Set, DatabaseConnection};
+use serde_json::json;
+use service::{
+    mutation::calendar_token_mutations::CalendarTokenMutation,
+    query::calendar_token_queries::CalendarTokenQuery,
+};
+use tracing::error;
+
+#[post("/calendar/token")]
+pub async fn create_calendar_token(
+    req: HttpRequest,
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+    let nonce = nanoid!();
+
+    let saved = match CalendarTokenQuery::find_calendar_token_by_user_id(&db, user_id).await? {
+        Some(calendar_token) => {
+            let mut calendar_token: calendar_token_model::ActiveModel = calendar_token.into();
+            calendar_token.nonce = Set(nonce.to_owned());
+            calendar_token.created_at = Set(Utc::now());
+            CalendarTokenMutation::update_calendar_token(&db, calendar_token).await
+        }
+        None => {
+            let mut calendar_token = calendar_token_model::ActiveModel::new();
+            calendar_token.nonce = Set(nonce.to_owned());
+            calendar_token.user_id = Set(user_id);
+            CalendarTokenMutation::create_calendar_token(&db, calendar_token).await
+        }
+    };
+
+    if let Err(err) = saved {
+        error!("Cannot save calendar token: {}", err);
+        return Err(ApiError::Internal(codes::CALENDAR_TOKEN_NOT_SAVED));
+    }
+
+    let token = create_token(
+        &CalendarFeedPayload { id: user_id, nonce },
+        Utc::now().timestamp() + MAX_AGE_10Y,
+    );
+    let conn = req.connection_info();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "url": format!("{}://{}/calendar/{}.ics", conn.scheme(), conn.host(), token),
+    })))
+}
+
+#[delete("/calendar/token")]
+pub async fn revoke_calendar_token(
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    match CalendarTokenMutation::delete_calendar_token_by_user_id(&db, user_id).await {
+        Ok(_) => Ok(ok_response::<String>(None)),
+        Err(err) => {
+            error!("Cannot revoke calendar token: {}", err);
+            Err(ApiError::Internal(codes::CALENDAR_TOKEN_NOT_REVOKED))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use chrono::Utc;
+    use entity::entities::calendar_token_entity::calendar_token_model;
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase, MockExecResult};
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use crate::middlewares::authenticated_user::test_utils::AuthenticateAs;
+
+    use super::{create_calendar_token, revoke_calendar_token};
+
+    fn authenticated() -> AuthenticateAs {
+        AuthenticateAs(Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap())
+    }
+
+    fn mock_db_with_existing_token() -> DatabaseConnection {
+        let calendar_token = calendar_token_model::Model {
+            id: 1,
+            nonce: String::from("old_nonce"),
+            created_at: Utc::now(),
+            user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+        };
+        MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[calendar_token.clone()]])
+            .append_query_results([[calendar_token]])
+            .into_connection()
+    }
+
+    #[actix_web::test]
+    async fn test_regenerate_calendar_token() {
+        let db_data: Data<DatabaseConnection> = Data::new(mock_db_with_existing_token());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/api").service(create_calendar_token)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/calendar/token")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+        let url = resp_body["url"].as_str().unwrap();
+
+        assert!(url.contains("/calendar/"));
+        assert!(url.ends_with(".ics"));
+    }
+
+    #[actix_web::test]
+    async fn test_revoke_calendar_token() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_exec_results([MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                }])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/api").service(revoke_calendar_token)),
+        )
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri("/api/calendar/token")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1 @@
+pub mod calendar_token_api;
\ No newline at end of file
@@ -0,0 +1,259 @@
+use crate::{
+    error::{api_error::ApiError, codes, ok_response},
+    middlewares::authenticated_user::AuthenticatedUser,
+    types::consent::consent_data::{ConsentAcceptRequest, ConsentResponse, ConsentStatusResponse},
+    utils::{
+        consent_utils::{new_consents, pending_consents},
+        request_utils::client_ip,
+    },
+};
+use actix_web::{
+    get, post,
+    web::{Data, Json},
+    HttpRequest, HttpResponse,
+};
+use entity::entities::legal_document_entity::legal_document_model;
+use sea_orm::{DatabaseConnection, DbErr};
+use serde_json::json;
+use service::{
+    mutation::consent_mutations::ConsentMutation,
+    query::{consent_queries::ConsentQuery, legal_document_queries::LegalDocumentQuery},
+};
+use tracing::error;
+use uuid::Uuid;
+
+#[get("/me/consents")]
+pub async fn get_consents(
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    Ok(ok_response(Some(consent_status(&db, user_id).await?)))
+}
+
+#[post("/me/consents")]
+pub async fn accept_consents(
+    req: HttpRequest,
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+    body: Json<ConsentAcceptRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    let documents = LegalDocumentQuery::find_current_documents(&db).await?;
+
+    let mut accepted: Vec<legal_document_model::Model> = Vec::new();
+    for consent in &body.documents {
+        match documents
+            .iter()
+            .find(|d| d.kind == consent.kind && d.version == consent.version)
+        {
+            Some(document) => accepted.push(document.to_owned()),
+            None => {
+                return Err(ApiError::Conflict(
+                    codes::CONSENT_OUTDATED,
+                    Some(json!({
+                        "kind": consent.kind,
+                        "version": consent.version,
+                    })),
+                ))
+            }
+        }
+    }
+
+    if let Err(err) =
+        ConsentMutation::create_consents(&db, new_consents(user_id, &accepted, client_ip(&req)))
+            .await
+    {
+        error!("Cannot record consents of user {}: {}", user_id, err);
+        return Err(ApiError::Internal(codes::CONSENT_NOT_RECORDED));
+    }
+
+    Ok(ok_response(Some(consent_status(&db, user_id).await?)))
+}
+
+async fn consent_status(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<ConsentStatusResponse, DbErr> {
+    let documents = LegalDocumentQuery::find_current_documents(db).await?;
+    let consents = ConsentQuery::find_consents_by_user_id(db, user_id).await?;
+    let pending = pending_consents(&documents, &consents);
+
+    Ok(ConsentStatusResponse {
+        must_reaccept: !pending.is_empty(),
+        pending,
+        consents: consents.iter().map(ConsentResponse::new).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use entity::entities::{
+        consent_entity::consent_model, legal_document_entity::legal_document_model::DocumentKind,
+    };
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase, MockExecResult};
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use crate::{
+        error::{api_error::ProblemDetails, codes},
+        middlewares::authenticated_user::test_utils::AuthenticateAs,
+        types::consent::consent_data::{ConsentAcceptRequest, ConsentDocument},
+    };
+
+    use super::*;
+
+    fn authenticated() -> AuthenticateAs {
+        AuthenticateAs(Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap())
+    }
+
+    fn documents() -> Vec<legal_document_model::Model> {
+        vec![
+            legal_document_model::Model {
+                id: 3,
+                kind: DocumentKind::Terms,
+                version: String::from("2024-03-01"),
+                url: String::from("/legal/terms/2024-03-01"),
+                ..Default::default()
+            },
+            legal_document_model::Model {
+                id: 2,
+                kind: DocumentKind::Privacy,
+                version: String::from("2024-01-21"),
+                url: String::from("/legal/privacy/2024-01-21"),
+                ..Default::default()
+            },
+        ]
+    }
+
+    fn consent(kind: DocumentKind, version: &str) -> consent_model::Model {
+        consent_model::Model {
+            kind,
+            version: String::from(version),
+            user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_get_consents_must_reaccept() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([documents()])
+                .append_query_results([vec![
+                    consent(DocumentKind::Terms, "2024-01-21"),
+                    consent(DocumentKind::Privacy, "2024-01-21"),
+                ]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(get_consents)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/account/me/consents")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body["mustReaccept"], true);
+        assert_eq!(resp_body["pending"][0]["kind"], "terms");
+        assert_eq!(resp_body["pending"][0]["version"], "2024-03-01");
+        assert_eq!(resp_body["consents"].as_array().unwrap().len(), 2);
+    }
+
+    #[actix_web::test]
+    async fn test_accept_consents_success() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([documents()])
+                .append_exec_results([MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                }])
+                .append_query_results([documents()])
+                .append_query_results([vec![
+                    consent(DocumentKind::Terms, "2024-03-01"),
+                    consent(DocumentKind::Privacy, "2024-01-21"),
+                ]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(accept_consents)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/consents")
+            .set_json(ConsentAcceptRequest {
+                documents: vec![ConsentDocument {
+                    kind: DocumentKind::Terms,
+                    version: String::from("2024-03-01"),
+                }],
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body["mustReaccept"], false);
+    }
+
+    #[actix_web::test]
+    async fn test_accept_consents_outdated_version() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([documents()])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(accept_consents)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/consents")
+            .set_json(ConsentAcceptRequest {
+                documents: vec![ConsentDocument {
+                    kind: DocumentKind::Terms,
+                    version: String::from("2024-01-21"),
+                }],
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let body = test::read_body(resp).await;
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::CONSENT_OUTDATED);
+    }
+}
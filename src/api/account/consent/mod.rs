@@ -0,0 +1 @@
+pub mod consent_api;
\ No newline at end of file
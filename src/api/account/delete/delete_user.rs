@@ -1,23 +1,152 @@
 use crate::{
-    error::{internal_server_error, ok_response},
-    middlewares::app_state::AppState,
+    error::{api_error::ApiError, codes},
+    middlewares::authenticated_user::AuthenticatedUser,
+    utils::{
+        account_deletion_utils::DeletionPolicy, auth_event_utils::record_auth_event,
+        cookie_utils::delete_cookie,
+    },
 };
 use actix_web::{delete, web::Data, HttpRequest, HttpResponse};
+use chrono::Utc;
+use entity::entities::auth_event_entity::auth_event_model::{AuthEventKind, AuthEventOutcome};
 use sea_orm::DatabaseConnection;
-use service::mutation::user_mutations::UserMutation;
+use serde_json::json;
+use service::mutation::{session_mutations::SessionMutation, user_mutations::UserMutation};
 use tracing::error;
 
+/// Only marks the account as deleted, the purge task removes it once the
+/// grace period is over. Logging in again before that restores it.
 #[delete("/delete_account")]
 pub async fn delete_user(
     req: HttpRequest,
     db: Data<DatabaseConnection>,
-    user: Data<AppState>,
-) -> HttpResponse {
-    match UserMutation::delete_user_by_id(&db, user.id.lock().unwrap().unwrap()).await {
-        Ok(_) => ok_response::<String>(None),
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    let user = match UserMutation::soft_delete_user_by_id(&db, user_id, Utc::now()).await {
+        Ok(u) => u,
         Err(err) => {
             error!("Cannot delete user: {}", err);
-            return internal_server_error::<String>("User", "DeletionFailure", None);
+            return Err(ApiError::Internal(codes::ACCOUNT_NOT_DELETED));
         }
+    };
+
+    if let Err(err) = SessionMutation::revoke_sessions_by_user_id(&db, user_id).await {
+        error!("Cannot revoke sessions of user {}: {}", user_id, err);
+        return Err(ApiError::Internal(codes::ACCOUNT_NOT_DELETED));
+    }
+
+    record_auth_event(
+        &db,
+        &req,
+        AuthEventKind::AccountDeleted,
+        AuthEventOutcome::Success,
+        Some(user_id),
+    )
+    .await;
+
+    let purge_at = DeletionPolicy::from_env().purge_at(user.deleted_at.unwrap_or_else(Utc::now));
+
+    Ok(HttpResponse::Ok()
+        .cookie(delete_cookie("SESSIONID"))
+        .json(json!({
+            "purgeAt": purge_at,
+        })))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use chrono::Utc;
+    use entity::entities::user_entity::user_model;
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, MockDatabase, MockExecResult};
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use crate::{
+        error::{api_error::ProblemDetails, codes},
+        middlewares::authenticated_user::test_utils::AuthenticateAs,
+    };
+
+    use super::delete_user;
+
+    fn authenticated() -> AuthenticateAs {
+        AuthenticateAs(Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap())
+    }
+
+    #[actix_web::test]
+    async fn test_delete_user_success() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user_model::Model {
+                    id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                    deleted_at: Some(Utc::now()),
+                    ..Default::default()
+                }]])
+                .append_exec_results([MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                }])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(delete_user)),
+        )
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri("/account/delete_account")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let cookie = resp.response().cookies().next().unwrap();
+        assert_eq!(cookie.name(), "SESSIONID");
+        assert_eq!(cookie.value(), "");
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert!(resp_body["purgeAt"].is_string());
+    }
+
+    #[actix_web::test]
+    async fn test_delete_user_failure() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_errors(vec![DbErr::RecordNotUpdated])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(delete_user)),
+        )
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri("/account/delete_account")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let body = test::read_body(resp).await;
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(problem.code, codes::ACCOUNT_NOT_DELETED);
     }
 }
@@ -0,0 +1,294 @@
+use crate::{
+    emails::data_export_email::{send_data_export_email, DataExportEmailData},
+    error::{api_error::ApiError, codes},
+    middlewares::authenticated_user::AuthenticatedUser,
+    storage::blob_store::BlobStore,
+    types::export::data_export::{DataExportPayload, UserDataExport},
+    utils::{
+        data_export_utils::{
+            build_export_archive, collect_user_data, export_file_name, export_object_key,
+            EXPORT_CONTENT_TYPE,
+        },
+        jwt_utils::create_token,
+        task_manager_utils::TaskManager,
+        time_utils::MAX_AGE_7J,
+    },
+};
+use actix_web::{get, http::header, web::Data, HttpResponse};
+use chrono::{Duration, Utc};
+use entity::entities::{data_export_entity::data_export_model, user_entity::user_model};
+use sea_orm::{ActiveModelBehavior, DatabaseConnection, DbErr, Set};
+use serde_json::json;
+use service::{
+    mutation::data_export_mutations::DataExportMutation, query::user_queries::UserQuery,
+};
+use tracing::{error, Instrument};
+use uuid::Uuid;
+
+#[get("/me/export")]
+pub async fn export_my_data(
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+    tasks: Data<TaskManager>,
+    store: Data<dyn BlobStore>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    let requester = UserQuery::find_user_by_id(&db, user_id).await?;
+
+    export_user_data(&db, &tasks, store, user_id, requester).await
+}
+
+/// Small exports are returned as a ZIP right away, large ones are built in
+/// the background and a download link is emailed to `requester`.
+pub async fn export_user_data(
+    db: &Data<DatabaseConnection>,
+    tasks: &TaskManager,
+    store: Data<dyn BlobStore>,
+    user_id: Uuid,
+    requester: user_model::Model,
+) -> Result<HttpResponse, ApiError> {
+    let export = match collect_user_data(db, user_id).await {
+        Ok(e) => e,
+        Err(DbErr::RecordNotFound(_)) => return Err(ApiError::NotFound(codes::USER_NOT_FOUND)),
+        Err(err) => return Err(err.into()),
+    };
+
+    if export.is_large() {
+        tasks.spawn_job(send_export_link(db.clone(), store, export, requester).in_current_span());
+        return Ok(HttpResponse::Accepted().json(json!({
+            "status": "Pending",
+        })));
+    }
+
+    match build_export_archive(&export, store.get_ref()).await {
+        Ok(bytes) => Ok(HttpResponse::Ok()
+            .content_type(EXPORT_CONTENT_TYPE)
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", export_file_name(user_id)),
+            ))
+            .body(bytes)),
+        Err(err) => {
+            error!("Cannot build export for user {}: {}", user_id, err);
+            Err(ApiError::Internal(codes::EXPORT_NOT_BUILT))
+        }
+    }
+}
+
+async fn send_export_link(
+    db: Data<DatabaseConnection>,
+    store: Data<dyn BlobStore>,
+    export: UserDataExport,
+    requester: user_model::Model,
+) {
+    let user_id = export.user.id;
+
+    let bytes = match build_export_archive(&export, store.get_ref()).await {
+        Ok(b) => b,
+        Err(err) => {
+            error!("Cannot build export for user {}: {}", user_id, err);
+            return;
+        }
+    };
+
+    let key = export_object_key(user_id);
+    if let Err(err) = store.put(&key, EXPORT_CONTENT_TYPE, bytes).await {
+        error!("Cannot store export {}: {}", key, err);
+        return;
+    }
+
+    // The purge worker deletes the archive once the link has expired.
+    let expires_at = Utc::now() + Duration::seconds(MAX_AGE_7J);
+    let mut data_export = data_export_model::ActiveModel::new();
+    data_export.object_key = Set(key.to_owned());
+    data_export.expires_at = Set(expires_at);
+    data_export.user_id = Set(user_id);
+    if let Err(err) = DataExportMutation::create_data_export(&db, data_export).await {
+        error!("Cannot record export {}: {}", key, err);
+        if let Err(err) = store.delete(&key).await {
+            error!("Cannot delete export {}: {}", key, err);
+        }
+        return;
+    }
+
+    let token = create_token(
+        &DataExportPayload { id: user_id, key },
+        expires_at.timestamp(),
+    );
+
+    let email_data = DataExportEmailData {
+        email_to: requester.email,
+        first_name: requester.first_name,
+        token,
+    };
+    if send_data_export_email(email_data).await.is_err() {
+        error!("Cannot send export link for user {}", user_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, sync::Arc};
+
+    use actix_web::{
+        http::header,
+        test,
+        web::{self, Data},
+        App,
+    };
+    use entity::entities::{
+        auth_event_entity::auth_event_model::{self, AuthEventKind, AuthEventOutcome},
+        consent_entity::consent_model,
+        known_device_entity::known_device_model,
+        legal_document_entity::legal_document_model::DocumentKind,
+        rdv_entity::rdv_model,
+        session_entity::session_model,
+        two_fa_entity::two_fa_model,
+    };
+    use nanoid::nanoid;
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase};
+    use uuid::Uuid;
+    use zip::ZipArchive;
+
+    use crate::{
+        middlewares::authenticated_user::test_utils::AuthenticateAs,
+        storage::{blob_store::BlobStore, local_blob_store::LocalBlobStore},
+    };
+
+    use super::*;
+
+    fn user() -> user_model::Model {
+        user_model::Model {
+            id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            first_name: String::from("Rob"),
+            last_name: String::from("Doe"),
+            email: String::from("test.pro.1@gmail.com"),
+            phone: String::from("+33600000001"),
+            terms: true,
+            privacy: true,
+            ..Default::default()
+        }
+    }
+
+    fn store() -> Data<dyn BlobStore> {
+        let root = std::env::temp_dir().join(format!("data-export-api-{}", nanoid!()));
+        let store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(root));
+        Data::from(store)
+    }
+
+    #[actix_web::test]
+    async fn test_export_my_data_success() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user()]])
+                .append_query_results([[user()]])
+                .append_query_results([[two_fa_model::Model {
+                    id: 1,
+                    email_verified: true,
+                    code: Some(String::from("secret-code")),
+                    user_id: user().id,
+                    ..Default::default()
+                }]])
+                .append_query_results([Vec::<
+                    entity::entities::calendar_token_entity::calendar_token_model::Model,
+                >::new()])
+                .append_query_results([[session_model::Model {
+                    id: Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap(),
+                    user_id: user().id,
+                    ..Default::default()
+                }]])
+                .append_query_results([[consent_model::Model {
+                    kind: DocumentKind::Terms,
+                    version: String::from("2024-01-21"),
+                    user_id: user().id,
+                    ..Default::default()
+                }]])
+                .append_query_results([[auth_event_model::Model {
+                    kind: AuthEventKind::Login,
+                    outcome: AuthEventOutcome::Success,
+                    user_id: Some(user().id),
+                    ..Default::default()
+                }]])
+                .append_query_results([[known_device_model::Model {
+                    user_agent: Some(String::from("Mozilla/5.0")),
+                    user_id: user().id,
+                    ..Default::default()
+                }]])
+                .append_query_results([[rdv_model::Model {
+                    title: String::from("Consultation"),
+                    pro_id: user().id,
+                    ..Default::default()
+                }]])
+                .into_connection(),
+        );
+        let authenticated = AuthenticateAs(user().id);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated)
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(store())
+                .service(web::scope("/account").service(export_my_data)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/account/me/export")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            EXPORT_CONTENT_TYPE
+        );
+
+        let body = test::read_body(resp).await;
+        let mut archive = ZipArchive::new(Cursor::new(body.to_vec())).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_reader(archive.by_name("export.json").unwrap()).unwrap();
+
+        assert_eq!(json["user"]["email"], "test.pro.1@gmail.com");
+        assert_eq!(json["twoFa"]["emailVerified"], true);
+        assert!(!json.to_string().contains("secret-code"));
+        assert_eq!(json["calendarToken"], serde_json::Value::Null);
+        assert_eq!(
+            json["sessions"][0]["id"],
+            "00000000-0000-0000-0000-000000000002"
+        );
+        assert_eq!(json["consents"][0]["kind"], "terms");
+        assert_eq!(json["authEvents"][0]["kind"], "login");
+        assert_eq!(json["knownDevices"][0]["userAgent"], "Mozilla/5.0");
+        assert_eq!(json["appointments"][0]["title"], "Consultation");
+    }
+
+    #[actix_web::test]
+    async fn test_export_my_data_cannot_find_user() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([Vec::<user_model::Model>::new()])
+                .into_connection(),
+        );
+        let authenticated = AuthenticateAs(user().id);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated)
+                .app_data(Data::new(TaskManager::new()))
+                .app_data(store())
+                .service(web::scope("/account").service(export_my_data)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/account/me/export")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1 @@
+pub mod data_export_api;
\ No newline at end of file
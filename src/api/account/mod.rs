@@ -1,3 +1,8 @@
 pub mod register;
 pub mod auth;
-pub mod delete;
\ No newline at end of file
+pub mod delete;
+pub mod calendar;
+pub mod profile;
+pub mod export;
+pub mod consent;
+pub mod audit;
\ No newline at end of file
@@ -0,0 +1,369 @@
+use crate::{
+    error::{api_error::ApiError, codes, ok_response},
+    middlewares::authenticated_user::AuthenticatedUser,
+    storage::blob_store::BlobStore,
+    utils::image_utils::{
+        avatar_object_key, create_avatar_thumbnails, is_avatar_of, new_avatar_key, ImageError,
+        AVATAR_CONTENT_TYPES, AVATAR_MAX_BYTES, AVATAR_OUTPUT_CONTENT_TYPE, AVATAR_SIZES,
+    },
+};
+use actix_multipart::Multipart;
+use actix_web::{
+    post,
+    web::{self, Data},
+    HttpResponse,
+};
+use futures_util::StreamExt;
+use sea_orm::DatabaseConnection;
+use serde_json::json;
+use service::{mutation::user_mutations::UserMutation, query::user_queries::UserQuery};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+const AVATAR_FIELD: &str = "avatar";
+
+#[post("/me/avatar")]
+pub async fn upload_avatar(
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+    store: Data<dyn BlobStore>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+    let mut upload: Option<Vec<u8>> = None;
+
+    while let Some(item) = payload.next().await {
+        let mut field = match item {
+            Ok(f) => f,
+            Err(_) => return Err(ApiError::Unprocessable(codes::AVATAR_UNREADABLE, None)),
+        };
+
+        if field.name() != AVATAR_FIELD || upload.is_some() {
+            while let Some(chunk) = field.next().await {
+                if chunk.is_err() {
+                    return Err(ApiError::Unprocessable(codes::AVATAR_UNREADABLE, None));
+                }
+            }
+            continue;
+        }
+
+        let allowed_type = field
+            .content_type()
+            .is_some_and(|mime| AVATAR_CONTENT_TYPES.contains(&mime.essence_str()));
+        if !allowed_type {
+            return Err(ApiError::Unprocessable(codes::AVATAR_INVALID_TYPE, None));
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = match chunk {
+                Ok(c) => c,
+                Err(_) => return Err(ApiError::Unprocessable(codes::AVATAR_UNREADABLE, None)),
+            };
+            if bytes.len() + chunk.len() > AVATAR_MAX_BYTES {
+                return Err(ApiError::PayloadTooLarge(codes::AVATAR_TOO_LARGE));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        upload = Some(bytes);
+    }
+
+    let bytes = match upload {
+        Some(b) => b,
+        None => return Err(ApiError::Unprocessable(codes::AVATAR_MISSING, None)),
+    };
+
+    let thumbnails = match web::block(move || create_avatar_thumbnails(&bytes)).await {
+        Ok(Ok(t)) => t,
+        Ok(Err(ImageError::InvalidType)) => {
+            return Err(ApiError::Unprocessable(codes::AVATAR_INVALID_TYPE, None))
+        }
+        Ok(Err(ImageError::Unreadable)) => {
+            return Err(ApiError::Unprocessable(codes::AVATAR_UNREADABLE, None))
+        }
+        Err(err) => {
+            error!("Cannot process avatar: {}", err);
+            return Err(ApiError::Internal(codes::INTERNAL));
+        }
+    };
+
+    let mut user = UserQuery::find_user_by_id(&db, user_id).await?;
+
+    let avatar_key = new_avatar_key(user.id);
+
+    for thumbnail in thumbnails {
+        let key = avatar_object_key(&avatar_key, thumbnail.size);
+        if let Err(err) = store
+            .put(&key, AVATAR_OUTPUT_CONTENT_TYPE, thumbnail.bytes)
+            .await
+        {
+            error!("Cannot store avatar {}: {}", key, err);
+            return Err(ApiError::Internal(codes::AVATAR_NOT_STORED));
+        }
+    }
+
+    let previous_avatar_key = user.avatar.replace(avatar_key.to_owned());
+
+    if let Err(err) = UserMutation::update_user(&db, user).await {
+        error!("Cannot save avatar: {}", err);
+        delete_avatar(&store, user_id, &avatar_key).await;
+        return Err(ApiError::Internal(codes::PROFILE_NOT_UPDATED));
+    }
+
+    if let Some(previous_avatar_key) = previous_avatar_key {
+        delete_avatar(&store, user_id, &previous_avatar_key).await;
+    }
+
+    Ok(ok_response(Some(json!({
+        "avatar": avatar_key,
+    }))))
+}
+
+pub async fn delete_avatar(store: &Data<dyn BlobStore>, user_id: Uuid, avatar_key: &str) {
+    if !is_avatar_of(avatar_key, user_id) {
+        warn!("Not deleting avatar {} of user {}", avatar_key, user_id);
+        return;
+    }
+    for size in AVATAR_SIZES {
+        let key = avatar_object_key(avatar_key, size);
+        if let Err(err) = store.delete(&key).await {
+            warn!("Cannot delete avatar {}: {}", key, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, path::PathBuf, sync::Arc };
+
+    use actix_web::{
+        http::header,
+        test,
+        web::{self, Data},
+        App,
+    };
+    use entity::entities::user_entity::user_model;
+    use image::{DynamicImage, ImageFormat};
+    use nanoid::nanoid;
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase};
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use crate::{
+        error::{api_error::ProblemDetails, codes},
+        middlewares::authenticated_user::test_utils::AuthenticateAs,
+        storage::{blob_store::BlobStore, local_blob_store::LocalBlobStore},
+        utils::image_utils::{avatar_object_key, AVATAR_SIZES},
+    };
+
+    use super::upload_avatar;
+
+    const BOUNDARY: &str = "----focus-avatar-boundary";
+
+    fn authenticated() -> AuthenticateAs {
+        AuthenticateAs(Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap())
+    }
+
+    fn user() -> user_model::Model {
+        user_model::Model {
+            id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            first_name: String::from("Rob"),
+            last_name: String::from("Doe"),
+            email: String::from("test.pro.1@gmail.com"),
+            phone: String::from("+33600000001"),
+            terms: true,
+            privacy: true,
+            ..Default::default()
+        }
+    }
+
+    fn png() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        DynamicImage::new_rgb8(320, 240)
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    fn multipart_body(content_type: &str, bytes: &[u8]) -> Vec<u8> {
+        let mut body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"avatar\"\r\nContent-Type: {}\r\n\r\n",
+            BOUNDARY, content_type
+        )
+        .into_bytes();
+        body.extend_from_slice(bytes);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+        body
+    }
+
+    fn temp_store() -> (PathBuf, Data<dyn BlobStore>) {
+        let root = std::env::temp_dir().join(format!("avatar-upload-{}", nanoid!()));
+        let store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(&root));
+        (root, Data::from(store))
+    }
+
+    #[actix_web::test]
+    async fn test_upload_avatar_success() {
+        let (root, store) = temp_store();
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user()]])
+                .append_query_results([[user()]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .app_data(store.clone())
+                .service(web::scope("/account").service(upload_avatar)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/avatar")
+            .insert_header((
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", BOUNDARY),
+            ))
+            .set_payload(multipart_body("image/png", &png()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+        let avatar_key = resp_body["avatar"].as_str().unwrap();
+
+        assert!(avatar_key.starts_with("avatars/00000000-0000-0000-0000-000000000001/"));
+        for size in AVATAR_SIZES {
+            let blob = store
+                .get(&avatar_object_key(avatar_key, size))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(blob.content_type, "image/jpeg");
+        }
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[actix_web::test]
+    async fn test_upload_avatar_keeps_files_of_other_users() {
+        let (root, store) = temp_store();
+        let other_key = "avatars/00000000-0000-0000-0000-000000000002/a";
+        store
+            .put(&avatar_object_key(other_key, 64), "image/jpeg", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let tampered_user = user_model::Model {
+            avatar: Some(String::from(other_key)),
+            ..user()
+        };
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[tampered_user]])
+                .append_query_results([[user()]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .app_data(store.clone())
+                .service(web::scope("/account").service(upload_avatar)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/avatar")
+            .insert_header((
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", BOUNDARY),
+            ))
+            .set_payload(multipart_body("image/png", &png()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(store
+            .get(&avatar_object_key(other_key, 64))
+            .await
+            .unwrap()
+            .is_some());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[actix_web::test]
+    async fn test_upload_avatar_invalid_type() {
+        let (_, store) = temp_store();
+        let db_data: Data<DatabaseConnection> =
+            Data::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .app_data(store)
+                .service(web::scope("/account").service(upload_avatar)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/avatar")
+            .insert_header((
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", BOUNDARY),
+            ))
+            .set_payload(multipart_body("text/plain", b"hello"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = test::read_body(resp).await;
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::AVATAR_INVALID_TYPE);
+    }
+
+    #[actix_web::test]
+    async fn test_upload_avatar_too_large() {
+        let (_, store) = temp_store();
+        let db_data: Data<DatabaseConnection> =
+            Data::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .app_data(store)
+                .service(web::scope("/account").service(upload_avatar)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/avatar")
+            .insert_header((
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", BOUNDARY),
+            ))
+            .set_payload(multipart_body(
+                "image/jpeg",
+                &vec![0; super::AVATAR_MAX_BYTES + 1],
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let body = test::read_body(resp).await;
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(problem.code, codes::AVATAR_TOO_LARGE);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod profile_api;
+pub mod avatar_upload_api;
\ No newline at end of file
@@ -0,0 +1,815 @@
+use crate::{
+    emails::two_factor_auth_email::{
+        send_two_factor_auth_email, TwoFactorAuthEmailData, EMAIL_CHANGE_CHECK_PATH,
+    },
+    error::{
+        api_error::ApiError,
+        codes,
+        errors::{
+            duplicate_key::{duplicate_key, DuplicateKey},
+            profile_data::{
+                profile_data_check::ProfileDataCheck, profile_data_errors::ProfileDataErrors,
+            },
+            signin_data::signin_data_check::SignInDataCheck,
+        },
+        ok_response,
+    },
+    i18n::language::language_from_code,
+    middlewares::authenticated_user::AuthenticatedUser,
+    sms::send_auth_code_sms::{send_auth_code_sms, AuthCodeSmsData},
+    types::profile::profile_data::{
+        EmailChangeConfirmRequest, EmailChangePayload, EmailChangeRequest,
+        PhoneChangeConfirmRequest, PhoneChangePayload, PhoneChangeRequest, ProfileResponse,
+        ProfileUpdateRequest,
+    },
+    utils::{
+        auth_event_utils::record_auth_event,
+        jwt_utils::{create_token, decode_token},
+        phone_utils::{normalize_phone, DEFAULT_COUNTRY},
+        time_utils::MAX_AGE_10M,
+        two_factors_auth_utils::TwoFactorsAuth,
+        validate_utils::has_errors,
+    },
+};
+use actix_web::{
+    get, patch, post,
+    web::{Data, Json},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use entity::entities::auth_event_entity::auth_event_model::{AuthEventKind, AuthEventOutcome};
+use sea_orm::{DatabaseConnection, DbErr};
+use serde_json::json;
+use service::{mutation::user_mutations::UserMutation, query::user_queries::UserQuery};
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+#[get("/me")]
+pub async fn get_profile(
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    let user = UserQuery::find_user_by_id(&db, user_id).await?;
+    let two_fa = UserQuery::find_related_two_fa(&db, &user).await?;
+
+    Ok(ok_response(Some(ProfileResponse::new(&user, &two_fa))))
+}
+
+#[patch("/me")]
+pub async fn update_profile(
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+    body: Json<ProfileUpdateRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    let profile_data_check = ProfileDataCheck {
+        first_name: body.first_name.to_owned(),
+        last_name: body.last_name.to_owned(),
+    };
+
+    let mut profile_data_errors = profile_data_check.validate().unwrap_or_default();
+
+    let language = match &body.language {
+        Some(code) => match language_from_code(code) {
+            Some(lg) => Some(lg),
+            None => {
+                profile_data_errors.language = String::from("invalid");
+                None
+            }
+        },
+        None => None,
+    };
+
+    if has_errors(vec![
+        &profile_data_errors.first_name,
+        &profile_data_errors.last_name,
+        &profile_data_errors.language,
+    ]) {
+        return Err(ApiError::invalid(codes::FORM_INVALID, profile_data_errors));
+    }
+
+    let mut user = UserQuery::find_user_by_id(&db, user_id).await?;
+
+    if let Some(first_name) = profile_data_check.first_name {
+        user.first_name = first_name;
+    }
+    if let Some(last_name) = profile_data_check.last_name {
+        user.last_name = last_name;
+    }
+    if let Some(lg) = language {
+        user.language = lg;
+    }
+
+    let user = match UserMutation::update_user(&db, user).await {
+        Ok(u) => u,
+        Err(err) => {
+            error!("Cannot update profile: {}", err);
+            return Err(ApiError::Internal(codes::PROFILE_NOT_UPDATED));
+        }
+    };
+
+    let two_fa = UserQuery::find_related_two_fa(&db, &user).await?;
+
+    Ok(ok_response(Some(ProfileResponse::new(&user, &two_fa))))
+}
+
+#[post("/me/email")]
+pub async fn request_email_change(
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+    body: Json<EmailChangeRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    let email = match SignInDataCheck::new(body.email.to_string()).validate() {
+        Ok(e) => e.email,
+        Err(invalid_email_error) => {
+            let mut profile_data_errors = ProfileDataErrors::new();
+            profile_data_errors.email = invalid_email_error.email;
+            return Err(ApiError::invalid(codes::FORM_INVALID, profile_data_errors));
+        }
+    };
+
+    let user = UserQuery::find_user_by_id(&db, user_id).await?;
+
+    let data_to_email = TwoFactorAuthEmailData {
+        first_name: user.first_name,
+        email_to: email.to_owned(),
+        path: String::from(EMAIL_CHANGE_CHECK_PATH),
+        token: create_token(
+            &EmailChangePayload { id: user.id, email },
+            Utc::now().timestamp() + MAX_AGE_10M,
+        ),
+    };
+
+    match send_two_factor_auth_email(data_to_email).await {
+        Ok(()) => Ok(ok_response::<String>(None)),
+        Err(err) => {
+            error!("EMAIL CHANGE: Email not sent, details: {:?}", err);
+            Err(ApiError::Internal(codes::EMAIL_NOT_SENT))
+        }
+    }
+}
+
+#[post("/me/email/confirm")]
+pub async fn confirm_email_change(
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+    body: Json<EmailChangeConfirmRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    let payload = decode_token::<EmailChangePayload>(body.token.as_str())?.payload;
+
+    if payload.id != user_id {
+        return Err(ApiError::Unauthorized(codes::TOKEN_INVALID));
+    }
+
+    let mut user = UserQuery::find_user_by_id(&db, user_id).await?;
+    user.email = payload.email;
+
+    let user = UserMutation::update_user(&db, user)
+        .await
+        .map_err(|err| contact_update_error(&err))?;
+
+    let two_fa = UserQuery::find_related_two_fa(&db, &user).await?;
+    let two_fa = TwoFactorsAuth::verify_email(&two_fa, &db).await?;
+
+    Ok(ok_response(Some(ProfileResponse::new(&user, &two_fa))))
+}
+
+#[post("/me/phone")]
+pub async fn request_phone_change(
+    req: HttpRequest,
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+    body: Json<PhoneChangeRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+    let country = body
+        .country
+        .to_owned()
+        .unwrap_or_else(|| String::from(DEFAULT_COUNTRY));
+
+    let phone = match normalize_phone(&body.phone, &country) {
+        Ok(p) => p,
+        Err(_) => {
+            let mut profile_data_errors = ProfileDataErrors::new();
+            profile_data_errors.phone = String::from("invalid");
+            return Err(ApiError::invalid(codes::FORM_INVALID, profile_data_errors));
+        }
+    };
+
+    let user = UserQuery::find_user_by_id(&db, user_id).await?;
+    let two_fa = UserQuery::find_related_two_fa(&db, &user).await?;
+
+    let resp_check_deadline = TwoFactorsAuth::check_deadline(&two_fa);
+
+    if resp_check_deadline.still_time {
+        return Err(ApiError::TooManyRequests(
+            codes::ACCOUNT_LOCKED,
+            resp_check_deadline.time_left,
+        ));
+    }
+
+    // Same cap as the login codes, whose counter is shared. The counter starts
+    // over with the lockout so that the user can try again once it ends.
+    if TwoFactorsAuth::get_number_of_sending(&two_fa) >= 2 {
+        let time_left = TwoFactorsAuth::block_account(&two_fa, &db).await;
+        TwoFactorsAuth::reset_tries(&two_fa, &db).await?;
+        record_auth_event(
+            &db,
+            &req,
+            AuthEventKind::Lockout,
+            AuthEventOutcome::Failure,
+            Some(user.id),
+        )
+        .await;
+        return Err(ApiError::TooManyRequests(codes::ACCOUNT_LOCKED, time_left));
+    }
+
+    let code = TwoFactorsAuth::generate_code(&two_fa);
+
+    let data_to_sms = AuthCodeSmsData {
+        phone: phone.to_owned(),
+        first_name: user.first_name,
+        code: code.to_owned(),
+        lg: user.language,
+    };
+
+    if send_auth_code_sms(data_to_sms).await.is_err() {
+        record_auth_event(
+            &db,
+            &req,
+            AuthEventKind::CodeSent,
+            AuthEventOutcome::Failure,
+            Some(user.id),
+        )
+        .await;
+        return Err(ApiError::Internal(codes::SMS_NOT_SENT));
+    }
+
+    TwoFactorsAuth::update_two_fa_with_new_num_of_sending(&two_fa, &db).await;
+    record_auth_event(
+        &db,
+        &req,
+        AuthEventKind::CodeSent,
+        AuthEventOutcome::Success,
+        Some(user.id),
+    )
+    .await;
+
+    let token = create_token(
+        &PhoneChangePayload {
+            id: user.id,
+            phone,
+            current_phone: user.phone,
+            code_hash: phone_change_code_hash(&code),
+        },
+        Utc::now().timestamp() + MAX_AGE_10M,
+    );
+
+    Ok(ok_response(Some(json!({
+        "token": token,
+    }))))
+}
+
+#[post("/me/phone/confirm")]
+pub async fn confirm_phone_change(
+    req: HttpRequest,
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+    body: Json<PhoneChangeConfirmRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.id;
+
+    let payload = decode_token::<PhoneChangePayload>(body.token.as_str())?.payload;
+
+    if payload.id != user_id {
+        return Err(ApiError::Unauthorized(codes::TOKEN_INVALID));
+    }
+
+    let mut user = UserQuery::find_user_by_id(&db, user_id).await?;
+    let two_fa = UserQuery::find_related_two_fa(&db, &user).await?;
+
+    let resp_check_deadline = TwoFactorsAuth::check_deadline(&two_fa);
+
+    if resp_check_deadline.still_time {
+        return Err(ApiError::TooManyRequests(
+            codes::ACCOUNT_LOCKED,
+            resp_check_deadline.time_left,
+        ));
+    }
+
+    // The phone already changed with this token.
+    if user.phone != payload.current_phone {
+        return Err(ApiError::Unauthorized(codes::TOKEN_INVALID));
+    }
+
+    if phone_change_code_hash(&body.code) != payload.code_hash {
+        let tries = TwoFactorsAuth::update_pro_by_remove_one_try(&two_fa, &db).await;
+        record_auth_event(
+            &db,
+            &req,
+            AuthEventKind::CodeFailed,
+            AuthEventOutcome::Failure,
+            Some(user.id),
+        )
+        .await;
+
+        if tries == 0 {
+            let time_left = TwoFactorsAuth::block_account(&two_fa, &db).await;
+            record_auth_event(
+                &db,
+                &req,
+                AuthEventKind::Lockout,
+                AuthEventOutcome::Failure,
+                Some(user.id),
+            )
+            .await;
+            return Err(ApiError::TooManyRequests(codes::ACCOUNT_LOCKED, time_left));
+        }
+        return Err(ApiError::Unprocessable(
+            codes::CODE_INVALID,
+            Some(json!({
+                "tries": tries,
+            })),
+        ));
+    }
+
+    user.phone = payload.phone;
+
+    let user = UserMutation::update_user(&db, user)
+        .await
+        .map_err(|err| contact_update_error(&err))?;
+
+    let two_fa = TwoFactorsAuth::verify_new_phone(&two_fa, &db).await?;
+
+    Ok(ok_response(Some(ProfileResponse::new(&user, &two_fa))))
+}
+
+fn phone_change_code_hash(code: &str) -> String {
+    hex::encode(Sha256::digest(code.trim().as_bytes()))
+}
+
+fn contact_update_error(err: &DbErr) -> ApiError {
+    let mut profile_data_errors = ProfileDataErrors::new();
+    match duplicate_key(err) {
+        Some(DuplicateKey::Email) => profile_data_errors.email = String::from("already_exists"),
+        Some(DuplicateKey::Phone) => profile_data_errors.phone = String::from("already_exists"),
+        Some(DuplicateKey::Other) => (),
+        None => {
+            error!("Cannot update contact: {}", err);
+            return ApiError::Internal(codes::DATABASE);
+        }
+    }
+    ApiError::Conflict(
+        codes::ACCOUNT_EXISTS,
+        serde_json::to_value(profile_data_errors).ok(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use chrono::Utc;
+    use entity::entities::{
+        two_fa_entity::two_fa_model,
+        user_entity::user_model::{self, Language},
+    };
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, MockDatabase, RuntimeErr};
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use crate::{
+        error::{api_error::ProblemDetails, codes},
+        middlewares::authenticated_user::test_utils::AuthenticateAs,
+        types::profile::profile_data::{
+            EmailChangeConfirmRequest, EmailChangePayload, PhoneChangeConfirmRequest,
+            PhoneChangePayload, PhoneChangeRequest, ProfileUpdateRequest,
+        },
+        utils::{jwt_utils::create_token, time_utils::MAX_AGE_10M},
+    };
+
+    use super::{
+        confirm_email_change, confirm_phone_change, get_profile, phone_change_code_hash,
+        request_phone_change, update_profile,
+    };
+
+    fn authenticated() -> AuthenticateAs {
+        AuthenticateAs(Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap())
+    }
+
+    fn user() -> user_model::Model {
+        user_model::Model {
+            id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            first_name: String::from("Rob"),
+            last_name: String::from("Doe"),
+            email: String::from("test.pro.1@gmail.com"),
+            phone: String::from("+33600000001"),
+            terms: true,
+            privacy: true,
+            ..Default::default()
+        }
+    }
+
+    fn two_fa() -> two_fa_model::Model {
+        two_fa_model::Model {
+            id: 1,
+            email_verified: true,
+            tries_left: 3,
+            codes_sent: 1,
+            code: Some(String::from("123456")),
+            locked_until: None,
+            lock_exponent: 0,
+            phone_verified: true,
+            user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+        }
+    }
+
+    fn phone_change_token(current_phone: &str, code: &str) -> String {
+        create_token(
+            &PhoneChangePayload {
+                id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                phone: String::from("+33600000002"),
+                current_phone: String::from(current_phone),
+                code_hash: phone_change_code_hash(code),
+            },
+            Utc::now().timestamp() + MAX_AGE_10M,
+        )
+    }
+
+    #[actix_web::test]
+    async fn test_get_profile() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user()]])
+                .append_query_results([[two_fa()]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(get_profile)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/account/me").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body["firstName"], "Rob");
+        assert_eq!(resp_body["phone"], "+33600000001");
+        assert_eq!(resp_body["language"], "fr");
+        assert_eq!(resp_body["emailVerified"], true);
+        assert_eq!(resp_body["phoneVerified"], true);
+    }
+
+    #[actix_web::test]
+    async fn test_update_profile() {
+        let updated_user = user_model::Model {
+            first_name: String::from("Bob"),
+            language: Language::En,
+            ..user()
+        };
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user()]])
+                .append_query_results([[updated_user]])
+                .append_query_results([[two_fa()]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(update_profile)),
+        )
+        .await;
+
+        let req = test::TestRequest::patch()
+            .uri("/account/me")
+            .set_json(&ProfileUpdateRequest {
+                first_name: Some(String::from("Bob")),
+                last_name: None,
+                language: Some(String::from("en")),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body["firstName"], "Bob");
+        assert_eq!(resp_body["language"], "en");
+    }
+
+    #[actix_web::test]
+    async fn test_update_profile_invalid_form() {
+        let db_data: Data<DatabaseConnection> =
+            Data::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(update_profile)),
+        )
+        .await;
+
+        let req = test::TestRequest::patch()
+            .uri("/account/me")
+            .set_json(&ProfileUpdateRequest {
+                first_name: Some(String::from("B")),
+                last_name: None,
+                language: Some(String::from("xx")),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = test::read_body(resp).await;
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(problem.code, codes::FORM_INVALID);
+        let errors = problem.errors.unwrap();
+
+        assert_eq!(errors["firstName"], "not_a_first_name");
+        assert_eq!(errors["language"], "invalid");
+    }
+
+    #[actix_web::test]
+    async fn test_confirm_email_change_already_taken() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user()]])
+                .append_query_errors(vec![DbErr::Query(RuntimeErr::Internal(
+                    "duplicate key value violates unique constraint \"users_email_key\"".to_string(),
+                ))])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(confirm_email_change)),
+        )
+        .await;
+
+        let token = create_token(
+            &EmailChangePayload {
+                id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                email: String::from("test.pro.2@gmail.com"),
+            },
+            Utc::now().timestamp() + MAX_AGE_10M,
+        );
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/email/confirm")
+            .set_json(&EmailChangeConfirmRequest { token })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let body = test::read_body(resp).await;
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(problem.code, codes::ACCOUNT_EXISTS);
+        assert_eq!(problem.errors.unwrap()["email"], "already_exists");
+    }
+
+    #[actix_web::test]
+    async fn test_confirm_email_change_wrong_user() {
+        let db_data: Data<DatabaseConnection> =
+            Data::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(confirm_email_change)),
+        )
+        .await;
+
+        let token = create_token(
+            &EmailChangePayload {
+                id: Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap(),
+                email: String::from("test.pro.2@gmail.com"),
+            },
+            Utc::now().timestamp() + MAX_AGE_10M,
+        );
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/email/confirm")
+            .set_json(&EmailChangeConfirmRequest { token })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_confirm_phone_change() {
+        let updated_user = user_model::Model {
+            phone: String::from("+33600000002"),
+            ..user()
+        };
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user()]])
+                .append_query_results([[two_fa()]])
+                .append_query_results([[updated_user]])
+                .append_query_results([[two_fa_model::Model {
+                    codes_sent: 0,
+                    ..two_fa()
+                }]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(confirm_phone_change)),
+        )
+        .await;
+
+        let token = phone_change_token("+33600000001", "123456");
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/phone/confirm")
+            .set_json(&PhoneChangeConfirmRequest {
+                token,
+                code: String::from("123456"),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body["phone"], "+33600000002");
+        assert_eq!(resp_body["phoneVerified"], true);
+    }
+
+    #[actix_web::test]
+    async fn test_confirm_phone_change_invalid_code() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user()]])
+                .append_query_results([[two_fa()]])
+                .append_query_results([[two_fa_model::Model {
+                    tries_left: 2,
+                    ..two_fa()
+                }]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data.clone())
+                .wrap(authenticated())
+                .service(web::scope("/account").service(confirm_phone_change)),
+        )
+        .await;
+
+        let token = phone_change_token("+33600000001", "123456");
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/phone/confirm")
+            .set_json(&PhoneChangeConfirmRequest {
+                token,
+                code: String::from("654321"),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = test::read_body(resp).await;
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(problem.code, codes::CODE_INVALID);
+        assert_eq!(problem.errors.unwrap()["tries"], 2);
+
+        drop(app);
+        let log = Arc::try_unwrap(db_data.into_inner())
+            .unwrap()
+            .into_transaction_log();
+
+        assert!(format!("{:?}", log[3]).contains(r#"INSERT INTO \"auth_events\""#));
+        assert!(format!("{:?}", log[3]).contains(r#"String(Some("code_failed"))"#));
+    }
+
+    #[actix_web::test]
+    async fn test_confirm_phone_change_twice() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user_model::Model {
+                    phone: String::from("+33600000002"),
+                    ..user()
+                }]])
+                .append_query_results([[two_fa()]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(authenticated())
+                .service(web::scope("/account").service(confirm_phone_change)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/phone/confirm")
+            .set_json(&PhoneChangeConfirmRequest {
+                token: phone_change_token("+33600000001", "123456"),
+                code: String::from("123456"),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_request_phone_change_too_many_codes() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user()]])
+                .append_query_results([[two_fa_model::Model {
+                    codes_sent: 2,
+                    ..two_fa()
+                }]])
+                .append_query_results([[two_fa_model::Model {
+                    codes_sent: 2,
+                    lock_exponent: 1,
+                    locked_until: Some(Utc::now().timestamp_millis() + 300_000),
+                    ..two_fa()
+                }]])
+                .append_query_results([[two_fa_model::Model {
+                    codes_sent: 0,
+                    lock_exponent: 1,
+                    locked_until: Some(Utc::now().timestamp_millis() + 300_000),
+                    ..two_fa()
+                }]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data.clone())
+                .wrap(authenticated())
+                .service(web::scope("/account").service(request_phone_change)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/account/me/phone")
+            .set_json(&PhoneChangeRequest {
+                phone: String::from("0612345678"),
+                country: None,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        drop(resp);
+        drop(app);
+        let log = Arc::try_unwrap(db_data.into_inner())
+            .unwrap()
+            .into_transaction_log();
+
+        assert_eq!(log.len(), 5);
+        assert!(format!("{:?}", log[2]).contains(r#"SET \"locked_until\""#));
+        assert!(format!("{:?}", log[3]).contains(r#"\"codes_sent\" = "#));
+        assert!(format!("{:?}", log[4]).contains(r#"INSERT INTO \"auth_events\""#));
+        assert!(format!("{:?}", log[4]).contains(r#"String(Some("lockout"))"#));
+    }
+}
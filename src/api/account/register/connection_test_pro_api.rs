@@ -9,10 +9,11 @@ use serde_json::json;
 
 use crate::{
     error::{
+        api_error::ApiError,
+        codes,
         errors::signin_data::{
             signin_data_check::SignInDataCheck, signin_data_errors::SignInDataErrors,
         },
-        bad_request,
     },
     types::register::signin_data_result::SigninDataResult,
     utils::{
@@ -26,35 +27,36 @@ use service::query::user_queries::UserQuery;
 pub async fn connection_test_pro(
     db: Data<DatabaseConnection>,
     pro: Json<SigninDataResult>,
-) -> HttpResponse {
+) -> Result<HttpResponse, ApiError> {
     let email = match SignInDataCheck::new(pro.email.to_string()).validate() {
         Ok(e) => e.email,
         Err(form_error) => {
-            return bad_request("Form", "Invalid", Some(form_error))
+            return Err(ApiError::invalid(codes::FORM_INVALID, form_error))
         }
     };
 
     let user = match UserQuery::find_user_by_email(&db, &email).await {
         Ok(p) => p,
         Err(_) => {
-            return bad_request("Form", "Unknown", Some(SignInDataErrors::new("not_found")))
+            return Err(ApiError::invalid(codes::ACCOUNT_UNKNOWN, SignInDataErrors::new("not_found")))
         }
     };
 
     let token = CookiePayload {
         id: user.id,
         exp_at: Utc::now().timestamp() + MAX_AGE_1H_TEST,
+        session_id: None,
     };
     let session_cookie = create_cookie("SESSIONID", &token);
 
 
     let account = json!({
-        "firstName": user.f,
-        "lastName": user.l,
+        "firstName": user.first_name,
+        "lastName": user.last_name,
     });
 
-    return HttpResponse::Ok().cookie(session_cookie).json(json!({
+    Ok(HttpResponse::Ok().cookie(session_cookie).json(json!({
         "connection test pro": "ok",
         "account": account,
-    }));
+    })))
 }
@@ -1,76 +1,102 @@
 use actix_web::{
     post,
     web::{Data, Json},
-    HttpResponse,
+    HttpRequest, HttpResponse,
 };
 use chrono::Utc;
+use entity::entities::auth_event_entity::auth_event_model::{AuthEventKind, AuthEventOutcome};
 use sea_orm::DatabaseConnection;
-use serde_json::json;
 
 use crate::{
-    emails::two_factor_auth_email::{send_two_factor_auth_email, TwoFactorAuthEmailData},
+    emails::two_factor_auth_email::{
+        send_two_factor_auth_email, TwoFactorAuthEmailData, AUTH_CHECK_PATH,
+    },
     error::{
-        bad_request,
+        api_error::ApiError,
+        codes,
         errors::signin_data::{
             signin_data_check::SignInDataCheck, signin_data_errors::SignInDataErrors,
         },
     },
     types::register::signin_data_result::SigninDataResult,
     utils::{
-        jwt_utils::create_token, time_utils::MAX_AGE_3M, two_factors_auth_utils::TwoFactorsAuth,
+        auth_event_utils::record_auth_event, jwt_utils::create_token, time_utils::MAX_AGE_3M,
+        two_factors_auth_utils::TwoFactorsAuth,
     },
 };
 use service::query::user_queries::UserQuery;
 
 #[post("/signin")]
 pub async fn sign_in_pro(
+    req: HttpRequest,
     db: Data<DatabaseConnection>,
     pro: Json<SigninDataResult>,
-) -> HttpResponse {
-    let email = match SignInDataCheck::new(pro.email.to_string()).validate() {
-        Ok(e) => e.email,
-        Err(invalid_email_error) => {
-            return bad_request("Form", "Invalid", Some(invalid_email_error));
-        }
-    };
+) -> Result<HttpResponse, ApiError> {
+    let email = SignInDataCheck::new(pro.email.to_string())
+        .validate()
+        .map_err(|invalid_email_error| ApiError::invalid(codes::FORM_INVALID, invalid_email_error))?
+        .email;
 
-    let user = match UserQuery::find_user_by_email(&db, &email).await {
+    let user = match UserQuery::find_user_by_email_with_deleted(&db, &email).await {
         Ok(p) => p,
-        Err(_) => return bad_request("Form", "Unknown", Some(SignInDataErrors::new("not_found"))),
+        Err(_) => {
+            record_auth_event(
+                &db,
+                &req,
+                AuthEventKind::MagicLinkSent,
+                AuthEventOutcome::Failure,
+                None,
+            )
+            .await;
+            return Err(ApiError::invalid(
+                codes::ACCOUNT_UNKNOWN,
+                SignInDataErrors::new("not_found"),
+            ));
+        }
     };
 
-    let two_fa = match UserQuery::find_related_two_fa(&db, &user).await {
-        Ok(t) => t,
-        Err(_) => return HttpResponse::InternalServerError().finish(),
-    };
+    let two_fa = UserQuery::find_related_two_fa(&db, &user).await?;
 
     let resp_check_deadline = TwoFactorsAuth::check_deadline(&two_fa);
 
     if resp_check_deadline.still_time {
-        return bad_request(
-            "Auth",
-            "TimeLeft",
-            Some(json!({
-                "timeLeft": resp_check_deadline.time_left,
-            })),
-        );
-    } else {
-        let data_to_email = TwoFactorAuthEmailData {
-            first_name: user.f,
-            email_to: user.e,
-            token: create_token(&user.id, Utc::now().timestamp() + MAX_AGE_3M),
-        };
+        return Err(ApiError::TooManyRequests(
+            codes::ACCOUNT_LOCKED,
+            resp_check_deadline.time_left,
+        ));
+    }
 
-        match send_two_factor_auth_email(data_to_email).await {
-            Ok(()) => HttpResponse::Ok().finish(),
-            Err(_) => HttpResponse::InternalServerError().finish(),
-        }
+    let user_id = user.id;
+    let data_to_email = TwoFactorAuthEmailData {
+        first_name: user.first_name,
+        email_to: user.email,
+        path: String::from(AUTH_CHECK_PATH),
+        token: create_token(&user.id, Utc::now().timestamp() + MAX_AGE_3M),
+    };
+
+    let sent = send_two_factor_auth_email(data_to_email).await;
+    let outcome = match sent {
+        Ok(()) => AuthEventOutcome::Success,
+        Err(_) => AuthEventOutcome::Failure,
+    };
+    record_auth_event(
+        &db,
+        &req,
+        AuthEventKind::MagicLinkSent,
+        outcome,
+        Some(user_id),
+    )
+    .await;
+
+    match sent {
+        Ok(()) => Ok(HttpResponse::Ok().finish()),
+        Err(_) => Err(ApiError::Internal(codes::EMAIL_NOT_SENT)),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::error::resp_errors::RespErrors;
+    use crate::error::{api_error::ProblemDetails, codes};
     use actix_web::{
         test,
         web::{self, Data},
@@ -80,10 +106,10 @@ mod tests {
     use entity::entities::{two_fa_entity::two_fa_model, user_entity::user_model};
     use reqwest::StatusCode;
     use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, MockDatabase};
-    use serde_json::Value;
+    use std::sync::Arc;
     use uuid::Uuid;
 
-    use super::{sign_in_pro, SignInDataErrors, SigninDataResult};
+    use super::{sign_in_pro, SigninDataResult};
 
     fn mock_db_user_not_found() -> DatabaseConnection {
         MockDatabase::new(DatabaseBackend::Postgres)
@@ -95,12 +121,12 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
@@ -114,16 +140,16 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
-                up: Some((Utc::now().timestamp_millis() as i64) + 300_000), // 5 minutes left
+                locked_until: Some(Utc::now().timestamp_millis() + 300_000), // 5 minutes left
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
                 ..Default::default()
             }]])
@@ -153,6 +179,37 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[actix_web::test]
+    async fn test_sign_in_pro_matches_the_email_exactly() {
+        let db_data: Data<DatabaseConnection> = Data::new(mock_db_with_verified_account());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data.clone())
+                .service(web::scope("/api").service(sign_in_pro)),
+        )
+        .await;
+
+        let signin_data = SigninDataResult {
+            email: String::from(" Test.Pro.1@Gmail.com "),
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/api/signin")
+            .set_json(&signin_data)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        drop((resp, app));
+        let db = Arc::try_unwrap(db_data.into_inner()).unwrap();
+        let user_query = format!("{:?}", db.into_transaction_log()[0]);
+        assert!(user_query.contains(r#"WHERE LOWER(\"email\") = $1"#));
+        assert!(user_query.contains(r#"String(Some("test.pro.1@gmail.com"))"#));
+        assert!(!user_query.contains("LIKE"));
+    }
+
     #[actix_web::test]
     async fn test_sign_in_pro_account_blocked() {
         let db_data: Data<DatabaseConnection> = Data::new(mock_db_with_blocked_account());
@@ -175,14 +232,12 @@ mod tests {
 
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<Value> = serde_json::from_slice(&body).unwrap();
-
-        assert_eq!(resp_errors.kind, "Auth");
-        assert_eq!(resp_errors.reason, "TimeLeft");
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::ACCOUNT_LOCKED);
     }
 
     #[actix_web::test]
@@ -207,15 +262,13 @@ mod tests {
 
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<SignInDataErrors> = serde_json::from_slice(&body).unwrap();
-
-        assert_eq!(resp_errors.kind, "Form");
-        assert_eq!(resp_errors.reason, "Invalid");
-        assert_eq!(resp_errors.errors.unwrap().email, String::from("invalid"));
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::FORM_INVALID);
+        assert_eq!(problem.errors.unwrap()["email"], String::from("invalid"));
     }
 
     #[actix_web::test]
@@ -240,12 +293,11 @@ mod tests {
 
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
         let body = test::read_body(resp).await;
-        let resp_errors: RespErrors<SignInDataErrors> = serde_json::from_slice(&body).unwrap();
-        assert_eq!(resp_errors.kind, "Form");
-        assert_eq!(resp_errors.reason, "Unknown");
-        assert_eq!(resp_errors.errors.unwrap().email, String::from("not_found"));
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, codes::ACCOUNT_UNKNOWN);
+        assert_eq!(problem.errors.unwrap()["email"], String::from("not_found"));
     }
 }
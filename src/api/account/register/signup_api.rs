@@ -1,47 +1,64 @@
 use crate::{
-    emails::two_factor_auth_email::{send_two_factor_auth_email, TwoFactorAuthEmailData},
+    emails::two_factor_auth_email::{
+        send_two_factor_auth_email, TwoFactorAuthEmailData, AUTH_CHECK_PATH,
+    },
     error::{
-        bad_request,
+        api_error::ApiError,
+        codes,
         errors::{
+            duplicate_key::{duplicate_key, DuplicateKey},
             signup_data::{
                 signup_data_check::SignUpDataCheck, signup_data_errors::SignUpDataErrors,
             },
         },
-        internal_server_error,
     },
+    i18n::language::language_from_request,
     types::register::signup_data_result::SignUpDataResult,
     utils::{
+        auth_event_utils::record_auth_event,
+        consent_utils::new_consents,
+        email_utils::normalize_email,
         jwt_utils::create_token,
+        phone_utils::DEFAULT_COUNTRY,
+        request_utils::client_ip,
         time_utils::MAX_AGE_3M,
     },
 };
 use actix_web::{
     post,
     web::{Data, Json},
-    HttpResponse,
+    HttpRequest, HttpResponse,
 };
 
 use chrono::Utc;
 use entity::entities::{
+    auth_event_entity::auth_event_model::{AuthEventKind, AuthEventOutcome},
     two_fa_entity::two_fa_model,
     user_entity::user_model,
 };
-use sea_orm::{prelude::*, ActiveValue::Set, DatabaseConnection};
+use sea_orm::{ActiveModelBehavior, ActiveValue::Set, DatabaseConnection};
 
+use service::mutation::consent_mutations::ConsentMutation;
 use service::mutation::two_fa_mutations::TwoFaMutation;
 use service::mutation::user_mutations::UserMutation;
+use service::query::legal_document_queries::LegalDocumentQuery;
 use tracing::error;
 
 #[post("/signup")]
 pub async fn sign_up_pro(
+    req: HttpRequest,
     db: Data<DatabaseConnection>,
     new_pro: Json<SignUpDataResult>,
-) -> HttpResponse {
+) -> Result<HttpResponse, ApiError> {
     let signup_data_check = SignUpDataCheck {
         firstName: new_pro.firstName.to_string(),
         lastName: new_pro.lastName.to_string(),
-        email: new_pro.email.to_string(),
+        email: normalize_email(&new_pro.email),
         phone: new_pro.phone.to_string(),
+        country: new_pro
+            .country
+            .to_owned()
+            .unwrap_or_else(|| String::from(DEFAULT_COUNTRY)),
         siren: new_pro.siren.to_string(),
         terms: new_pro.terms.to_owned(),
         privacy: new_pro.privacy.to_owned(),
@@ -50,38 +67,39 @@ pub async fn sign_up_pro(
     match signup_data_check.validate() {
         None => (),
         Some(form_errors) => {
-            return bad_request("Form", "Invalid", Some(form_errors));
+            return Err(ApiError::invalid(codes::FORM_INVALID, form_errors));
         }
     }
 
     let mut user = user_model::ActiveModel::new();
-    user.f = Set(signup_data_check.firstName.to_owned());
-    user.l = Set(signup_data_check.lastName.to_owned());
-    user.e = Set(signup_data_check.email.to_owned());
-    user.ph = Set(signup_data_check.phone.to_owned());
-    user.t = Set(signup_data_check.terms.to_owned());
-    user.pv = Set(signup_data_check.privacy.to_owned());
+    user.first_name = Set(signup_data_check.firstName.to_owned());
+    user.last_name = Set(signup_data_check.lastName.to_owned());
+    user.email = Set(signup_data_check.email.to_owned());
+    user.phone = Set(signup_data_check.normalized_phone().unwrap());
+    user.terms = Set(signup_data_check.terms.to_owned());
+    user.privacy = Set(signup_data_check.privacy.to_owned());
+    user.language = Set(language_from_request(&req));
 
     let created_user = match UserMutation::create_user(&db, user).await {
         Ok(u) => u,
-        Err(err) => match err {
-            DbErr::Query(err) => {
-                let error = err.to_string();
-                if error.contains("duplicate key") {
-                    let mut sign_up_data_errors = SignUpDataErrors::new();
-                    if error.contains("users_e_key") {
-                        sign_up_data_errors.email = String::from("already_exists");
-                    } else if error.contains("users_ph_key") {
-                        sign_up_data_errors.phone = String::from("already_exists");
+        Err(err) => match duplicate_key(&err) {
+            Some(key) => {
+                let mut sign_up_data_errors = SignUpDataErrors::new();
+                match key {
+                    DuplicateKey::Email => {
+                        sign_up_data_errors.email = String::from("already_exists")
+                    }
+                    DuplicateKey::Phone => {
+                        sign_up_data_errors.phone = String::from("already_exists")
                     }
-                    return bad_request("Form", "Invalid", Some(sign_up_data_errors));
-                } else {
-                    return HttpResponse::InternalServerError().finish();
+                    DuplicateKey::Other => (),
                 }
+                return Err(ApiError::Conflict(
+                    codes::ACCOUNT_EXISTS,
+                    serde_json::to_value(sign_up_data_errors).ok(),
+                ));
             }
-            _ => {
-                return HttpResponse::InternalServerError().finish();
-            }
+            None => return Err(err.into()),
         },
     };
 
@@ -89,17 +107,56 @@ pub async fn sign_up_pro(
     two_fa.user_id = Set(created_user.id);
     let new_two_fa = TwoFaMutation::create_two_fa(&db, two_fa).await.unwrap();
 
+    // `validate` rejects the form unless both terms and privacy are accepted.
+    let consents = match LegalDocumentQuery::find_current_documents(&db).await {
+        Ok(documents) => new_consents(created_user.id, &documents, client_ip(&req)),
+        Err(_) => return Err(ApiError::Internal(codes::CONSENT_NOT_RECORDED)),
+    };
+    if let Err(err) = ConsentMutation::create_consents(&db, consents).await {
+        error!("SIGNUP: Consents not recorded, details: {:?}", err);
+        return Err(ApiError::Internal(codes::CONSENT_NOT_RECORDED));
+    }
+
+    record_auth_event(
+        &db,
+        &req,
+        AuthEventKind::Signup,
+        AuthEventOutcome::Success,
+        Some(created_user.id),
+    )
+    .await;
+
+    let user_id = created_user.id;
     let data_to_email = TwoFactorAuthEmailData {
-        first_name: created_user.f,
-        email_to: created_user.e,
+        first_name: created_user.first_name,
+        email_to: created_user.email,
+        path: String::from(AUTH_CHECK_PATH),
         token: create_token(&created_user.id, Utc::now().timestamp() + MAX_AGE_3M),
     };
 
     match send_two_factor_auth_email(data_to_email).await {
-        Ok(()) => HttpResponse::Ok().finish(),
+        Ok(()) => {
+            record_auth_event(
+                &db,
+                &req,
+                AuthEventKind::MagicLinkSent,
+                AuthEventOutcome::Success,
+                Some(user_id),
+            )
+            .await;
+            Ok(HttpResponse::Ok().finish())
+        }
         Err(err) => {
             error!("SIGNUP: Email not sent, details: {:?}", err);
-            return internal_server_error::<String>("Email", "NotSent", None);
+            record_auth_event(
+                &db,
+                &req,
+                AuthEventKind::MagicLinkSent,
+                AuthEventOutcome::Failure,
+                Some(user_id),
+            )
+            .await;
+            Err(ApiError::Internal(codes::EMAIL_NOT_SENT))
         }
     }
 }
@@ -108,6 +165,7 @@ pub async fn sign_up_pro(
 mod tests {
     use actix_web::{test, web::{self, Data}, App,};
     use entity::entities::{
+        legal_document_entity::legal_document_model::{self, DocumentKind},
         two_fa_entity::two_fa_model,
         user_entity::user_model,
     };
@@ -116,7 +174,7 @@ mod tests {
         DatabaseBackend, DatabaseConnection, DbErr, MockDatabase, MockExecResult, RuntimeErr,
     };
     use uuid::Uuid;
-    use crate::error::resp_errors::RespErrors;
+    use crate::error::{api_error::ProblemDetails, codes};
 
     use super::{sign_up_pro, SignUpDataResult, SignUpDataErrors};
 
@@ -124,25 +182,34 @@ mod tests {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[user_model::Model {
                 id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                f: String::from("Rob"),
-                l: String::from("Doe"),
-                e: String::from("test.pro.1@gmail.com"),
-                ph: String::from("0600000001"),
-                t: true,
-                pv: true,
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
                 ..Default::default()
             }]])
             .append_query_results([[two_fa_model::Model {
                 user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
                 ..Default::default()
             }]])
+            .append_query_results([[legal_document_model::Model {
+                kind: DocumentKind::Terms,
+                version: String::from("2024-01-21"),
+                ..Default::default()
+            }]])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
             .into_connection()
     }
 
     fn mock_db_duplicated_user_email() -> DatabaseConnection {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_errors(vec![DbErr::Query(RuntimeErr::Internal(
-                "duplicate key: users_e_key".to_string(),
+                "duplicate key: users_email_key".to_string(),
             ))])
             .into_connection()
     }
@@ -150,7 +217,7 @@ mod tests {
     fn mock_db_duplicated_user_phone() -> DatabaseConnection {
         MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_errors(vec![DbErr::Query(RuntimeErr::Internal(
-                "duplicate key: users_ph_key".to_string(),
+                "duplicate key: users_phone_key".to_string(),
             ))])
             .into_connection()
     }
@@ -171,6 +238,7 @@ mod tests {
             lastName: "Doe".to_owned(),
             email: "test.pro.1@gmail.com".to_owned(),
             phone: "0600000001".to_owned(),
+            country: None,
             siren: "883116000".to_owned(),
             terms: true,
             privacy: true,
@@ -208,6 +276,7 @@ mod tests {
             lastName: "l".to_owned(),
             email: "invalid.email@".to_owned(),
             phone: "0102030405".to_owned(),
+            country: None,
             siren: "8831002003".to_owned(),
             terms: false,
             privacy: false,
@@ -226,15 +295,14 @@ mod tests {
 
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<SignUpDataErrors> = serde_json::from_slice(&body).unwrap();
-        let errors = resp_errors.errors.unwrap();
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+        let errors: SignUpDataErrors = serde_json::from_value(problem.errors.unwrap()).unwrap();
 
-        assert_eq!(resp_errors.kind, "Form");
-        assert_eq!(resp_errors.reason, "Invalid");
+        assert_eq!(problem.code, codes::FORM_INVALID);
 
         let required_errror = String::from("required");
         let not_a_last_name_error = String::from("not_a_last_name");
@@ -267,6 +335,7 @@ mod tests {
             lastName: "Doe".to_owned(),
             email: "test.pro.1@gmail.com".to_owned(),
             phone: "0600000001".to_owned(),
+            country: None,
             siren: "883116000".to_owned(),
             terms: true,
             privacy: true,
@@ -285,16 +354,15 @@ mod tests {
 
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<SignUpDataErrors> = serde_json::from_slice(&body).unwrap();
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
 
-        assert_eq!(resp_errors.kind, "Form");
-        assert_eq!(resp_errors.reason, "Invalid");
+        assert_eq!(problem.code, codes::ACCOUNT_EXISTS);
 
-        let errors = resp_errors.errors.unwrap();
+        let errors: SignUpDataErrors = serde_json::from_value(problem.errors.unwrap()).unwrap();
 
         assert_eq!(errors.phone, String::from(""));
         assert_eq!(errors.email, String::from("already_exists"));
@@ -316,6 +384,7 @@ mod tests {
             lastName: "Doe".to_owned(),
             email: "test.pro.1@gmail.com".to_owned(),
             phone: "0600000001".to_owned(),
+            country: None,
             siren: "883116000".to_owned(),
             terms: true,
             privacy: true,
@@ -334,16 +403,15 @@ mod tests {
 
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
 
         let body = test::read_body(resp).await;
 
-        let resp_errors: RespErrors<SignUpDataErrors> = serde_json::from_slice(&body).unwrap();
+        let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
 
-        assert_eq!(resp_errors.kind, "Form");
-        assert_eq!(resp_errors.reason, "Invalid");
+        assert_eq!(problem.code, codes::ACCOUNT_EXISTS);
 
-        let errors = resp_errors.errors.unwrap();
+        let errors: SignUpDataErrors = serde_json::from_value(problem.errors.unwrap()).unwrap();
 
         assert_eq!(errors.email, String::from(""));
         assert_eq!(errors.phone, String::from("already_exists"));
@@ -0,0 +1,108 @@
+use crate::{
+    error::api_error::ApiError,
+    types::audit::auth_event_data::{AuthEventResponse, AuthEventsRequest},
+};
+use actix_web::{
+    get,
+    web::{Data, Query},
+    HttpResponse,
+};
+use sea_orm::DatabaseConnection;
+use service::query::auth_event_queries::AuthEventQuery;
+
+/// Filters the audit log of every account by `userId` and by a `[from, to)` range.
+#[get("/auth_events")]
+pub async fn get_auth_events(
+    db: Data<DatabaseConnection>,
+    query: Query<AuthEventsRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let events =
+        AuthEventQuery::find_auth_events(&db, query.user_id, query.from, query.to, query.limit())
+            .await?;
+
+    Ok(HttpResponse::Ok().json(
+        events
+            .iter()
+            .map(AuthEventResponse::new)
+            .collect::<Vec<AuthEventResponse>>(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use entity::entities::auth_event_entity::auth_event_model::{
+        self, AuthEventKind, AuthEventOutcome,
+    };
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, MockDatabase};
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use super::get_auth_events;
+
+    #[actix_web::test]
+    async fn test_get_auth_events_filtered() {
+        let user_id = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[auth_event_model::Model {
+                    id: 1,
+                    kind: AuthEventKind::CodeFailed,
+                    outcome: AuthEventOutcome::Failure,
+                    ip: Some(String::from("203.0.113.7")),
+                    user_id: Some(user_id),
+                    ..Default::default()
+                }]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .service(web::scope("/admin").service(get_auth_events)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/admin/auth_events?userId=00000000-0000-0000-0000-000000000001&from=2024-03-01T00:00:00Z&limit=5000")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body[0]["kind"], "code_failed");
+        assert_eq!(resp_body[0]["outcome"], "failure");
+        assert_eq!(resp_body[0]["ip"], "203.0.113.7");
+    }
+
+    #[actix_web::test]
+    async fn test_get_auth_events_failure() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_errors(vec![DbErr::Custom(String::from("down"))])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .service(web::scope("/admin").service(get_auth_events)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/admin/auth_events")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{
+    api::account::export::data_export_api::export_user_data, error::api_error::ApiError,
+    middlewares::authenticated_user::AuthenticatedUser, storage::blob_store::BlobStore,
+    utils::task_manager_utils::TaskManager,
+};
+use actix_web::{
+    get,
+    web::{Data, Path},
+    HttpResponse,
+};
+use sea_orm::DatabaseConnection;
+use service::query::user_queries::UserQuery;
+use tracing::info;
+use uuid::Uuid;
+
+#[get("/users/{user_id}/export")]
+pub async fn export_user_data_as_admin(
+    db: Data<DatabaseConnection>,
+    user: AuthenticatedUser,
+    tasks: Data<TaskManager>,
+    store: Data<dyn BlobStore>,
+    path: Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let admin_id = user.id;
+    let user_id = path.into_inner();
+
+    let admin = UserQuery::find_user_by_id(&db, admin_id).await?;
+
+    info!("Admin {} exports data of user {}", admin_id, user_id);
+    export_user_data(&db, &tasks, store, user_id, admin).await
+}
@@ -0,0 +1,208 @@
+use crate::{
+    error::api_error::ApiError,
+    types::admin::user_list_data::{UsersRequest, UsersResponse},
+};
+use actix_web::{
+    get,
+    web::{Data, Query},
+    HttpResponse,
+};
+use sea_orm::DatabaseConnection;
+use service::query::user_queries::UserQuery;
+
+/// Lists the accounts not deleted with their total count. `nextCursor`
+/// fetches the following page with the same filters and sort.
+#[get("/users")]
+pub async fn get_users(
+    db: Data<DatabaseConnection>,
+    query: Query<UsersRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let filter = query.filter()?;
+    let after = query.cursor()?;
+
+    let page = UserQuery::find_users(
+        &db,
+        &filter,
+        query.sort(),
+        query.order(),
+        after.as_ref(),
+        query.limit(),
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(UsersResponse::new(&page)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use chrono::{TimeZone, Utc};
+    use entity::entities::{two_fa_entity::two_fa_model, user_entity::user_model};
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase, Value as DbValue};
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use super::get_users;
+    use crate::types::admin::user_list_data::{decode_cursor, encode_cursor};
+    use service::query::user_queries::UserCursor;
+
+    fn user(id: u128, last_name: &str) -> (user_model::Model, Option<two_fa_model::Model>) {
+        let id = Uuid::from_u128(id);
+        (
+            user_model::Model {
+                id,
+                first_name: String::from("Jean"),
+                last_name: String::from(last_name),
+                email: format!("{}@focus.test", last_name.to_lowercase()),
+                two_fa: true,
+                created_at: Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap(),
+                ..Default::default()
+            },
+            Some(two_fa_model::Model {
+                user_id: id,
+                email_verified: true,
+                ..Default::default()
+            }),
+        )
+    }
+
+    fn count(total: i64) -> BTreeMap<&'static str, DbValue> {
+        BTreeMap::from([("num_items", DbValue::BigInt(Some(total)))])
+    }
+
+    #[actix_web::test]
+    async fn test_get_users_filtered() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[count(3)]])
+                .append_query_results([[user(1, "Dupont"), user(2, "Durand")]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data.clone())
+                .service(web::scope("/admin").service(get_users)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/admin/users?search=du%20foc&language=fr&twoFa=true&emailVerified=true&sort=lastName&limit=1")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body["total"], 3);
+        assert_eq!(resp_body["users"].as_array().unwrap().len(), 1);
+        assert_eq!(resp_body["users"][0]["lastName"], "Dupont");
+        assert_eq!(resp_body["users"][0]["emailVerified"], true);
+        assert_eq!(resp_body["users"][0]["phoneVerified"], false);
+        assert_eq!(resp_body["users"][0]["role"], "pro");
+
+        let cursor = decode_cursor(resp_body["nextCursor"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            cursor,
+            UserCursor::LastName(String::from("Dupont"), Uuid::from_u128(1))
+        );
+
+        drop(app);
+        let log = Arc::try_unwrap(db_data.into_inner())
+            .unwrap()
+            .into_transaction_log();
+        let select = format!("{:?}", log[1]);
+
+        assert!(select.contains("@@ to_tsquery('simple', $1)"));
+        assert!(select.contains(r#"String(Some("du:* & foc:*"))"#));
+        assert!(select
+            .contains(r#"ORDER BY \"users\".\"last_name\" ASC, \"users\".\"id\" ASC LIMIT $5"#));
+        assert!(select.contains("BigUnsigned(Some(2))"));
+    }
+
+    #[actix_web::test]
+    async fn test_get_users_after_cursor() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[count(3)]])
+                .append_query_results([[user(2, "Durand")]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data.clone())
+                .service(web::scope("/admin").service(get_users)),
+        )
+        .await;
+
+        let cursor = encode_cursor(&UserCursor::LastName(
+            String::from("Dupont"),
+            Uuid::from_u128(1),
+        ));
+        let req = test::TestRequest::get()
+            .uri(&format!("/admin/users?sort=lastName&cursor={}", cursor))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body["nextCursor"], Value::Null);
+
+        drop(app);
+        let log = Arc::try_unwrap(db_data.into_inner())
+            .unwrap()
+            .into_transaction_log();
+        let count = format!("{:?}", log[0]);
+        let select = format!("{:?}", log[1]);
+
+        assert!(!count.contains("Dupont"));
+        assert!(select.contains(r#"\"users\".\"last_name\" > $1"#));
+        assert!(select.contains(r#"String(Some("Dupont"))"#));
+    }
+
+    #[actix_web::test]
+    async fn test_get_users_invalid_cursor() {
+        let db_data: Data<DatabaseConnection> =
+            Data::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .service(web::scope("/admin").service(get_users)),
+        )
+        .await;
+
+        let cursor = encode_cursor(&UserCursor::Email(
+            String::from("dupont@focus.test"),
+            Uuid::from_u128(1),
+        ));
+
+        for uri in [
+            String::from("/admin/users?cursor=not-a-cursor"),
+            format!("/admin/users?sort=lastName&cursor={}", cursor),
+        ] {
+            let req = test::TestRequest::get().uri(&uri).to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+            let body = test::read_body(resp).await;
+            let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+            assert_eq!(resp_body["code"], "cursor-invalid");
+        }
+    }
+}
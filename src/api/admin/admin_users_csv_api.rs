@@ -0,0 +1,427 @@
+use std::collections::HashSet;
+
+use crate::{
+    error::{
+        api_error::ApiError,
+        codes,
+        errors::{duplicate_key::duplicate_key, signup_data::signup_data_errors::SignUpDataErrors},
+    },
+    types::admin::{
+        user_export_data::{users_csv, UsersExportRequest, USERS_CSV_CONTENT_TYPE},
+        user_import_data::{import_rows, ImportRequest, ImportResponse, ImportRow, ImportRowError},
+        user_list_data::{AdminUserResponse, UsersRequest},
+    },
+    utils::consent_utils::new_consents,
+};
+use actix_web::{
+    get,
+    http::header,
+    post,
+    web::{Data, Query},
+    HttpResponse,
+};
+use entity::entities::{two_fa_entity::two_fa_model, user_entity::user_model};
+use sea_orm::{ActiveModelBehavior, ActiveValue::Set, DatabaseConnection};
+use service::{
+    mutation::user_mutations::UserMutation,
+    query::{legal_document_queries::LegalDocumentQuery, user_queries::UserQuery},
+};
+use tracing::{error, info};
+
+/// Creates pro accounts from a CSV file, see `IMPORT_COLUMNS`. Each row is
+/// checked like the signup form and against the existing accounts, then the
+/// valid rows are inserted together unless `dryRun` is set. The accounts get
+/// no 2FA email, their owners receive a code when they first sign in.
+#[post("/users/import")]
+pub async fn import_users(
+    db: Data<DatabaseConnection>,
+    query: Query<ImportRequest>,
+    body: String,
+) -> Result<HttpResponse, ApiError> {
+    let rows = import_rows(&body)?;
+    let total = rows.len();
+    let (valid, mut errors) = check_rows(&db, rows).await?;
+
+    let imported = match (query.dry_run, valid.is_empty()) {
+        (false, false) => create_users(&db, &valid).await?,
+        _ => 0,
+    };
+    errors.sort_by_key(|error| error.line);
+
+    Ok(HttpResponse::Ok().json(ImportResponse {
+        dry_run: query.dry_run,
+        rows: total,
+        valid: valid.len(),
+        imported,
+        errors,
+    }))
+}
+
+/// Splits the rows into valid ones and the errors of the others. An email or
+/// phone already taken, by an account or by a previous row of the file, is
+/// reported as `already_exists`.
+async fn check_rows(
+    db: &DatabaseConnection,
+    rows: Vec<ImportRow>,
+) -> Result<(Vec<(ImportRow, String)>, Vec<ImportRowError>), ApiError> {
+    let mut valid = Vec::new();
+    let mut errors = Vec::new();
+    let mut emails = HashSet::new();
+    let mut phones = HashSet::new();
+
+    for row in rows {
+        if let Some(row_errors) = row.check.validate() {
+            errors.push(ImportRowError {
+                line: row.line,
+                errors: row_errors,
+            });
+            continue;
+        }
+
+        // `validate` has checked the number.
+        let phone = row.check.normalized_phone().unwrap_or_default();
+        let mut row_errors = SignUpDataErrors::new();
+        if !emails.insert(row.check.email.to_owned()) {
+            row_errors.email = String::from("already_exists");
+        }
+        if !phones.insert(phone.to_owned()) {
+            row_errors.phone = String::from("already_exists");
+        }
+        match row_errors.email.is_empty() && row_errors.phone.is_empty() {
+            true => valid.push((row, phone)),
+            false => errors.push(ImportRowError {
+                line: row.line,
+                errors: row_errors,
+            }),
+        }
+    }
+
+    let emails: Vec<String> = emails.into_iter().collect();
+    let phones: Vec<String> = phones.into_iter().collect();
+    let existing = UserQuery::find_users_by_emails_or_phones(db, &emails, &phones).await?;
+    let taken_emails: HashSet<&str> = existing.iter().map(|user| user.email.as_str()).collect();
+    let taken_phones: HashSet<&str> = existing.iter().map(|user| user.phone.as_str()).collect();
+
+    let (valid, taken): (Vec<_>, Vec<_>) = valid.into_iter().partition(|(row, phone)| {
+        !taken_emails.contains(row.check.email.as_str()) && !taken_phones.contains(phone.as_str())
+    });
+    for (row, phone) in taken {
+        let mut row_errors = SignUpDataErrors::new();
+        if taken_emails.contains(row.check.email.as_str()) {
+            row_errors.email = String::from("already_exists");
+        }
+        if taken_phones.contains(phone.as_str()) {
+            row_errors.phone = String::from("already_exists");
+        }
+        errors.push(ImportRowError {
+            line: row.line,
+            errors: row_errors,
+        });
+    }
+
+    Ok((valid, errors))
+}
+
+async fn create_users(
+    db: &DatabaseConnection,
+    rows: &[(ImportRow, String)],
+) -> Result<u64, ApiError> {
+    let documents = match LegalDocumentQuery::find_current_documents(db).await {
+        Ok(documents) => documents,
+        Err(_) => return Err(ApiError::Internal(codes::CONSENT_NOT_RECORDED)),
+    };
+
+    let mut users = Vec::new();
+    let mut two_fas = Vec::new();
+    let mut consents = Vec::new();
+    for (row, phone) in rows {
+        let mut user = user_model::ActiveModel::new();
+        user.first_name = Set(row.check.firstName.to_owned());
+        user.last_name = Set(row.check.lastName.to_owned());
+        user.email = Set(row.check.email.to_owned());
+        user.phone = Set(phone.to_owned());
+        user.terms = Set(row.check.terms);
+        user.privacy = Set(row.check.privacy);
+        let user_id = user.id.to_owned().unwrap();
+
+        let mut two_fa = two_fa_model::ActiveModel::new();
+        two_fa.user_id = Set(user_id);
+
+        users.push(user);
+        two_fas.push(two_fa);
+        consents.extend(new_consents(user_id, &documents, None));
+    }
+
+    match UserMutation::create_users(db, users, two_fas, consents).await {
+        Ok(imported) => {
+            info!("IMPORT: {} users created", imported);
+            Ok(imported)
+        }
+        // An account was created with the same email or phone meanwhile.
+        Err(err) if duplicate_key(&err).is_some() => {
+            Err(ApiError::Conflict(codes::ACCOUNT_EXISTS, None))
+        }
+        Err(err) => {
+            error!("IMPORT: Users not created, details: {:?}", err);
+            Err(ApiError::Internal(codes::USERS_NOT_IMPORTED))
+        }
+    }
+}
+
+/// The users of the listing for the same filters and sort, every page at
+/// once, as a CSV file with the `columns` asked for.
+#[get("/users/export")]
+pub async fn export_users(
+    db: Data<DatabaseConnection>,
+    query: Query<UsersRequest>,
+    export: Query<UsersExportRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let columns = export.columns()?;
+    let filter = query.filter()?;
+
+    let users: Vec<AdminUserResponse> =
+        UserQuery::find_all_users(&db, &filter, query.sort(), query.order())
+            .await?
+            .iter()
+            .map(|(user, two_fa)| AdminUserResponse::new(user, two_fa.as_ref()))
+            .collect();
+
+    Ok(HttpResponse::Ok()
+        .content_type(USERS_CSV_CONTENT_TYPE)
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"users.csv\"",
+        ))
+        .insert_header((header::CACHE_CONTROL, "private, no-store"))
+        .body(users_csv(&users, &columns)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use chrono::{TimeZone, Utc};
+    use entity::entities::{
+        legal_document_entity::legal_document_model::{self, DocumentKind},
+        two_fa_entity::two_fa_model,
+        user_entity::user_model,
+    };
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase, MockExecResult};
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use super::{export_users, import_users};
+
+    const CSV: &str = "firstName,lastName,email,phone,siren,terms,privacy\r\n\
+        Jean,Dupont,Jean.Dupont@Focus.test,0612345678,123456789,true,true\r\n\
+        J,Durand,durand@focus.test,0612345679,123456789,true,true\r\n\
+        Anne,Martin,jean.dupont@focus.test,0612345670,123456789,yes,1\r\n\
+        Paul,Bernard,taken@focus.test,+33612345671,123456789,true,false\r\n\
+        Marie,Petit,taken@focus.test,0612345672,123456789,x,x\r\n";
+
+    #[actix_web::test]
+    async fn test_import_users_dry_run() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user_model::Model {
+                    email: String::from("taken@focus.test"),
+                    phone: String::from("+33600000000"),
+                    ..Default::default()
+                }]])
+                .into_connection(),
+        );
+        let app = test::init_service(
+            App::new().app_data(db_data).service(
+                web::scope("/admin")
+                    .service(import_users)
+                    .service(export_users),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/admin/users/import?dryRun=true")
+            .set_payload(CSV)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body["dryRun"], true);
+        assert_eq!(resp_body["rows"], 5);
+        assert_eq!(resp_body["valid"], 1);
+        assert_eq!(resp_body["imported"], 0);
+
+        let errors = resp_body["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 4);
+        assert_eq!(errors[0]["line"], 3);
+        assert_eq!(errors[0]["errors"]["firstName"], "not_a_first_name");
+        assert_eq!(errors[1]["line"], 4);
+        assert_eq!(errors[1]["errors"]["email"], "already_exists");
+        assert_eq!(errors[2]["line"], 5);
+        assert_eq!(errors[2]["errors"]["privacy"], "must_accept");
+        assert_eq!(errors[3]["line"], 6);
+        assert_eq!(errors[3]["errors"]["email"], "already_exists");
+        assert_eq!(errors[3]["errors"]["phone"], "");
+    }
+
+    #[actix_web::test]
+    async fn test_import_users_commits_valid_rows() {
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([Vec::<user_model::Model>::new()])
+                .append_query_results([[legal_document_model::Model {
+                    kind: DocumentKind::Terms,
+                    version: String::from("2024-01-21"),
+                    ..Default::default()
+                }]])
+                .append_exec_results([
+                    MockExecResult {
+                        last_insert_id: 0,
+                        rows_affected: 1,
+                    },
+                    MockExecResult {
+                        last_insert_id: 0,
+                        rows_affected: 1,
+                    },
+                    MockExecResult {
+                        last_insert_id: 0,
+                        rows_affected: 1,
+                    },
+                ])
+                .into_connection(),
+        );
+        let app = test::init_service(
+            App::new().app_data(db_data.clone()).service(
+                web::scope("/admin")
+                    .service(import_users)
+                    .service(export_users),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/admin/users/import")
+            .set_payload(CSV.lines().take(2).collect::<Vec<&str>>().join("\n"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body["dryRun"], false);
+        assert_eq!(resp_body["imported"], 1);
+        assert_eq!(resp_body["errors"], Value::Array(Vec::new()));
+
+        drop(app);
+        let log = Arc::try_unwrap(db_data.into_inner())
+            .unwrap()
+            .into_transaction_log();
+        let inserts = format!("{:?}", log[2]);
+
+        assert!(inserts.contains("BEGIN"));
+        assert!(inserts.contains(r#"INSERT INTO \"users\""#));
+        assert!(inserts.contains(r#"String(Some("jean.dupont@focus.test"))"#));
+        assert!(inserts.contains(r#"String(Some("+33612345678"))"#));
+        assert!(inserts.contains(r#"INSERT INTO \"two_fa\""#));
+        assert!(inserts.contains(r#"INSERT INTO \"consents\""#));
+        assert!(inserts.contains("COMMIT"));
+    }
+
+    #[actix_web::test]
+    async fn test_import_users_missing_column() {
+        let db_data: Data<DatabaseConnection> =
+            Data::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+        let app = test::init_service(
+            App::new().app_data(db_data).service(
+                web::scope("/admin")
+                    .service(import_users)
+                    .service(export_users),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/admin/users/import")
+            .set_payload("firstName,lastName,email\nJean,Dupont,jean@focus.test\n")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let body = test::read_body(resp).await;
+        let resp_body: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(resp_body["code"], "csv-invalid");
+    }
+
+    #[actix_web::test]
+    async fn test_export_users_selected_columns() {
+        let id = Uuid::from_u128(1);
+        let db_data: Data<DatabaseConnection> = Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[(
+                    user_model::Model {
+                        id,
+                        first_name: String::from("Jean"),
+                        last_name: String::from("Dupont, fils"),
+                        email: String::from("jean.dupont@focus.test"),
+                        phone: String::from("+33612345678"),
+                        created_at: Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap(),
+                        ..Default::default()
+                    },
+                    Some(two_fa_model::Model {
+                        user_id: id,
+                        email_verified: true,
+                        ..Default::default()
+                    }),
+                )]])
+                .into_connection(),
+        );
+        let app = test::init_service(
+            App::new().app_data(db_data).service(
+                web::scope("/admin")
+                    .service(import_users)
+                    .service(export_users),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/admin/users/export?columns=lastName,phone,emailVerified,createdAt&sort=lastName")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "text/csv; charset=utf-8"
+        );
+
+        let body = test::read_body(resp).await;
+
+        assert_eq!(
+            body,
+            "lastName,phone,emailVerified,createdAt\r\n\
+            \"Dupont, fils\",+33612345678,true,2024-03-01T09:00:00Z\r\n"
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/admin/users/export?columns=lastName,password")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
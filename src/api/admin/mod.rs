@@ -0,0 +1,4 @@
+pub mod admin_export_api;
+pub mod admin_auth_events_api;
+pub mod admin_users_api;
+pub mod admin_users_csv_api;
\ No newline at end of file
@@ -0,0 +1,97 @@
+use crate::{
+    error::{api_error::ApiError, codes},
+    storage::blob_store::{is_valid_key, BlobStore},
+    utils::image_utils::{avatar_object_key, AVATAR_SIZES},
+};
+use actix_web::{
+    get,
+    http::header,
+    web::{Data, Path},
+    HttpResponse,
+};
+use tracing::error;
+
+#[get("/{user_id}/{avatar_id}/{size}.jpg")]
+pub async fn get_avatar(
+    store: Data<dyn BlobStore>,
+    path: Path<(String, String, u32)>,
+) -> Result<HttpResponse, ApiError> {
+    let (user_id, avatar_id, size) = path.into_inner();
+
+    let key = avatar_object_key(&format!("avatars/{}/{}", user_id, avatar_id), size);
+    if !AVATAR_SIZES.contains(&size) || !is_valid_key(&key) {
+        return Err(ApiError::NotFound(codes::NOT_FOUND));
+    }
+
+    match store.get(&key).await {
+        Ok(Some(blob)) => Ok(HttpResponse::Ok()
+            .content_type(blob.content_type)
+            // Every upload gets a fresh key, so a stored avatar never changes.
+            .insert_header((header::CACHE_CONTROL, "public, max-age=31536000, immutable"))
+            .body(blob.bytes)),
+        Ok(None) => Err(ApiError::NotFound(codes::NOT_FOUND)),
+        Err(err) => {
+            error!("Cannot read avatar {}: {}", key, err);
+            Err(ApiError::Internal(codes::INTERNAL))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use actix_web::{
+        http::header,
+        test,
+        web::{self, Data},
+        App,
+    };
+    use nanoid::nanoid;
+    use reqwest::StatusCode;
+
+    use crate::storage::{blob_store::BlobStore, local_blob_store::LocalBlobStore};
+
+    use super::get_avatar;
+
+    #[actix_web::test]
+    async fn test_get_avatar() {
+        let root = std::env::temp_dir().join(format!("avatar-{}", nanoid!()));
+        let store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(&root));
+        store
+            .put("avatars/1/abc/64.jpg", "image/jpeg", vec![0xFF, 0xD8])
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::from(store))
+                .service(web::scope("/avatars").service(get_avatar)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/avatars/1/abc/64.jpg")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CACHE_CONTROL).unwrap(),
+            "public, max-age=31536000, immutable"
+        );
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "image/jpeg"
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/avatars/1/abc/32.jpg")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1 @@
+pub mod avatar_api;
\ No newline at end of file
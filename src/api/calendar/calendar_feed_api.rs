@@ -0,0 +1,240 @@
+use crate::{
+    error::{api_error::ApiError, codes},
+    types::calendar::calendar_feed::CalendarFeedPayload,
+    utils::{
+        ics_utils::{create_ics, IcsEvent, IcsMethod},
+        jwt_utils::decode_token,
+    },
+};
+use actix_web::{
+    get,
+    http::header,
+    web::{Data, Path},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use entity::entities::rdv_entity::rdv_model;
+use sea_orm::DatabaseConnection;
+use service::query::{
+    calendar_token_queries::CalendarTokenQuery, rdv_queries::RdvQuery, user_queries::UserQuery,
+};
+use sha2::{Digest, Sha256};
+
+#[get("/{token}.ics")]
+pub async fn calendar_feed(
+    req: HttpRequest,
+    db: Data<DatabaseConnection>,
+    token: Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    // Feed urls are shared with calendar apps, a bad token is answered as a missing feed.
+    let payload = decode_token::<CalendarFeedPayload>(token.as_str())
+        .map_err(|_| ApiError::NotFound(codes::NOT_FOUND))?
+        .payload;
+
+    match CalendarTokenQuery::find_calendar_token_by_user_id(&db, payload.id).await? {
+        Some(calendar_token) if calendar_token.nonce == payload.nonce => (),
+        _ => return Err(ApiError::NotFound(codes::NOT_FOUND)),
+    };
+
+    let user = UserQuery::find_user_by_id(&db, payload.id).await?;
+    let rdvs =
+        RdvQuery::find_upcoming_rdv_by_pro_id(&db, payload.id, Utc::now().timestamp()).await?;
+
+    let etag = calendar_feed_etag(&rdvs);
+
+    let if_none_match = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok());
+
+    if if_none_match.is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag)) {
+        return Ok(HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .finish());
+    }
+
+    let organizer_name = format!("{} {}", user.first_name, user.last_name);
+    let events: Vec<IcsEvent> = rdvs
+        .into_iter()
+        .map(|rdv| IcsEvent {
+            uid: rdv.id,
+            sequence: rdv.seq,
+            start_at: rdv.start_at,
+            end_at: rdv.end_at,
+            summary: rdv.title,
+            description: None,
+            location: rdv.address,
+            organizer_name: organizer_name.to_owned(),
+            attendee_email: None,
+            cancelled: rdv.cancelled,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .insert_header((header::ETAG, etag))
+        .insert_header((header::CACHE_CONTROL, "private, max-age=300"))
+        .body(create_ics(&events, IcsMethod::Publish)))
+}
+
+/// The digest has to stay the same across builds and restarts, which the
+/// std hashers do not promise, or every deploy would refetch all the feeds.
+fn calendar_feed_etag(rdvs: &[rdv_model::Model]) -> String {
+    let mut hasher = Sha256::new();
+    for rdv in rdvs {
+        hasher.update(rdv.id.as_bytes());
+        hasher.update(rdv.seq.to_be_bytes());
+        hasher.update(rdv.start_at.to_be_bytes());
+        hasher.update(rdv.end_at.to_be_bytes());
+        // Lengths first, so that the text fields cannot run into each other.
+        hasher.update((rdv.title.len() as u64).to_be_bytes());
+        hasher.update(&rdv.title);
+        match &rdv.address {
+            Some(address) => {
+                hasher.update([1]);
+                hasher.update((address.len() as u64).to_be_bytes());
+                hasher.update(address);
+            }
+            None => hasher.update([0]),
+        }
+        hasher.update([rdv.cancelled as u8]);
+        hasher.update(rdv.updated_at.timestamp_micros().to_be_bytes());
+    }
+    format!("W/\"{}\"", hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        http::header,
+        test,
+        web::{self, Data},
+        App,
+    };
+    use chrono::Utc;
+    use entity::entities::{
+        calendar_token_entity::calendar_token_model, rdv_entity::rdv_model, user_entity::user_model,
+    };
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase};
+    use uuid::Uuid;
+
+    use crate::{
+        types::calendar::calendar_feed::CalendarFeedPayload,
+        utils::{jwt_utils::create_token, time_utils::MAX_AGE_10Y},
+    };
+
+    use super::{calendar_feed, calendar_feed_etag};
+
+    fn rdv() -> rdv_model::Model {
+        rdv_model::Model {
+            id: Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap(),
+            title: String::from("Consultation individuelle"),
+            start_at: Utc::now().timestamp() + 3_600,
+            end_at: Utc::now().timestamp() + 7_200,
+            address: Some(String::from("5 rue de la justice")),
+            seq: 0,
+            cancelled: false,
+            updated_at: Utc::now(),
+            pro_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+        }
+    }
+
+    fn mock_db_with_feed(nonce: &str, rdv: rdv_model::Model) -> DatabaseConnection {
+        MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[calendar_token_model::Model {
+                id: 1,
+                nonce: String::from(nonce),
+                created_at: Utc::now(),
+                user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            }]])
+            .append_query_results([[user_model::Model {
+                id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                first_name: String::from("Rob"),
+                last_name: String::from("Doe"),
+                email: String::from("test.pro.1@gmail.com"),
+                phone: String::from("0600000001"),
+                terms: true,
+                privacy: true,
+                ..Default::default()
+            }]])
+            .append_query_results([[rdv]])
+            .into_connection()
+    }
+
+    fn feed_token(nonce: &str) -> String {
+        create_token(
+            &CalendarFeedPayload {
+                id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                nonce: String::from(nonce),
+            },
+            Utc::now().timestamp() + MAX_AGE_10Y,
+        )
+    }
+
+    #[actix_web::test]
+    async fn test_calendar_feed_success() {
+        let db_data: Data<DatabaseConnection> = Data::new(mock_db_with_feed("nonce", rdv()));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .service(web::scope("/calendar").service(calendar_feed)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/calendar/{}.ics", feed_token("nonce")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(resp.headers().contains_key(header::ETAG));
+
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+
+        assert!(body.contains("METHOD:PUBLISH\r\n"));
+        assert!(body.contains("SUMMARY:Consultation individuelle\r\n"));
+    }
+
+    #[actix_web::test]
+    async fn test_calendar_feed_not_modified() {
+        let rdv = rdv();
+        let etag = calendar_feed_etag(std::slice::from_ref(&rdv));
+        let db_data: Data<DatabaseConnection> = Data::new(mock_db_with_feed("nonce", rdv));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .service(web::scope("/calendar").service(calendar_feed)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/calendar/{}.ics", feed_token("nonce")))
+            .insert_header((header::IF_NONE_MATCH, etag))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[actix_web::test]
+    async fn test_calendar_feed_revoked_token() {
+        let db_data: Data<DatabaseConnection> = Data::new(mock_db_with_feed("new_nonce", rdv()));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .service(web::scope("/calendar").service(calendar_feed)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/calendar/{}.ics", feed_token("old_nonce")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
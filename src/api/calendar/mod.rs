@@ -0,0 +1 @@
+pub mod calendar_feed_api;
\ No newline at end of file
@@ -0,0 +1,146 @@
+use crate::{
+    error::{api_error::ApiError, codes},
+    storage::blob_store::BlobStore,
+    types::export::data_export::DataExportPayload,
+    utils::{
+        data_export_utils::{export_file_name, EXPORT_CONTENT_TYPE},
+        jwt_utils::decode_token,
+    },
+};
+use actix_web::{
+    get,
+    http::header,
+    web::{Data, Path},
+    HttpResponse,
+};
+use tracing::error;
+
+#[get("/{token}")]
+pub async fn download_export(
+    store: Data<dyn BlobStore>,
+    path: Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let payload = decode_token::<DataExportPayload>(path.as_str())?.payload;
+
+    if !payload.key.starts_with(&format!("exports/{}/", payload.id)) {
+        return Err(ApiError::NotFound(codes::NOT_FOUND));
+    }
+
+    match store.get(&payload.key).await {
+        Ok(Some(blob)) => Ok(HttpResponse::Ok()
+            .content_type(EXPORT_CONTENT_TYPE)
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", export_file_name(payload.id)),
+            ))
+            .insert_header((header::CACHE_CONTROL, "private, no-store"))
+            .body(blob.bytes)),
+        Ok(None) => Err(ApiError::NotFound(codes::NOT_FOUND)),
+        Err(err) => {
+            error!("Cannot read export {}: {}", payload.key, err);
+            Err(ApiError::Internal(codes::INTERNAL))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use chrono::Utc;
+    use nanoid::nanoid;
+    use reqwest::StatusCode;
+    use uuid::Uuid;
+
+    use crate::{
+        storage::{blob_store::BlobStore, local_blob_store::LocalBlobStore},
+        utils::{jwt_utils::create_token, time_utils::MAX_AGE_7J},
+    };
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_download_export_success() {
+        let root = std::env::temp_dir().join(format!("export-download-{}", nanoid!()));
+        let store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(&root));
+        let store: Data<dyn BlobStore> = Data::from(store);
+        let id = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let key = format!("exports/{}/archive.zip", id);
+        store
+            .put(&key, EXPORT_CONTENT_TYPE, vec![1, 2, 3])
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(store)
+                .service(web::scope("/exports").service(download_export)),
+        )
+        .await;
+
+        let token = create_token(
+            &DataExportPayload { id, key },
+            Utc::now().timestamp() + MAX_AGE_7J,
+        );
+        let req = test::TestRequest::get()
+            .uri(&format!("/exports/{}", token))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(test::read_body(resp).await.to_vec(), vec![1, 2, 3]);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[actix_web::test]
+    async fn test_download_export_other_user_key() {
+        let store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(std::env::temp_dir()));
+        let store: Data<dyn BlobStore> = Data::from(store);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(store)
+                .service(web::scope("/exports").service(download_export)),
+        )
+        .await;
+
+        let token = create_token(
+            &DataExportPayload {
+                id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                key: String::from("avatars/00000000-0000-0000-0000-000000000002/a/64.jpg"),
+            },
+            Utc::now().timestamp() + MAX_AGE_7J,
+        );
+        let req = test::TestRequest::get()
+            .uri(&format!("/exports/{}", token))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_download_export_invalid_token() {
+        let store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(std::env::temp_dir()));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::from(store))
+                .service(web::scope("/exports").service(download_export)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/exports/not-a-token")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+}
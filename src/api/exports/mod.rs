@@ -0,0 +1 @@
+pub mod export_download_api;
\ No newline at end of file
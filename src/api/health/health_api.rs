@@ -0,0 +1,182 @@
+use actix_web::{get, web::Data, HttpResponse};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::DatabaseConnection;
+use serde_json::{json, Value};
+use tracing::error;
+
+use crate::{repository::postgres_repo::PostgresRepo, utils::account_deletion_utils::PurgeWorker};
+
+const UP: &str = "up";
+const DOWN: &str = "down";
+
+/// Liveness probe, answers as long as the server accepts requests.
+#[get("/healthz")]
+pub async fn get_healthz() -> HttpResponse {
+    HttpResponse::Ok().json(json!({ "status": UP }))
+}
+
+/// Readiness probe: database reachable, migrations applied and purge worker
+/// alive. Answers 503 with the same details when a check is down. Errors are
+/// only logged, the route is public.
+#[get("/readyz")]
+pub async fn get_readyz(db: Data<DatabaseConnection>, worker: Data<PurgeWorker>) -> HttpResponse {
+    let database = match PostgresRepo::ping(&db).await {
+        Ok(()) => json!({ "status": UP }),
+        Err(err) => {
+            error!("Readiness, database: {}", err);
+            json!({ "status": DOWN })
+        }
+    };
+
+    let migrations = match Migrator::get_pending_migrations(db.get_ref()).await {
+        Ok(pending) => {
+            let pending: Vec<&str> = pending.iter().map(|migration| migration.name()).collect();
+            json!({
+                "status": if pending.is_empty() { UP } else { DOWN },
+                "pending": pending,
+            })
+        }
+        Err(err) => {
+            error!("Readiness, migrations: {}", err);
+            json!({ "status": DOWN, "pending": Value::Null })
+        }
+    };
+
+    let last_run = worker.last_run();
+    let worker = json!({
+        "status": if worker.is_running() { UP } else { DOWN },
+        "lastRunAt": last_run.last_run_at,
+        "lastRunFailed": last_run.last_error.is_some(),
+    });
+
+    let ready = [&database, &migrations, &worker]
+        .iter()
+        .all(|check| check["status"] == UP);
+    let body = json!({
+        "status": if ready { "ready" } else { "unavailable" },
+        "checks": {
+            "database": database,
+            "migrations": migrations,
+            "worker": worker,
+        },
+    });
+
+    match ready {
+        true => HttpResponse::Ok().json(body),
+        false => HttpResponse::ServiceUnavailable().json(body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult, Value as DbValue};
+
+    use super::*;
+    use crate::{
+        storage::{blob_store::BlobStore, local_blob_store::LocalBlobStore},
+        utils::{account_deletion_utils::start_account_purge, task_manager_utils::TaskManager},
+    };
+
+    fn install_results() -> [MockExecResult; 3] {
+        // `get_pending_migrations` creates the migration table if needed at each step.
+        [0, 0, 0].map(|_| MockExecResult {
+            last_insert_id: 0,
+            rows_affected: 0,
+        })
+    }
+
+    fn purge_worker() -> Data<PurgeWorker> {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<BTreeMap<&str, DbValue>>::new()])
+            .into_connection();
+        let store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(std::env::temp_dir()));
+
+        let tasks = TaskManager::new();
+
+        Data::new(start_account_purge(
+            Data::new(db),
+            Data::from(store),
+            &tasks,
+        ))
+    }
+
+    async fn call_readyz(db: DatabaseConnection) -> (StatusCode, Value) {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(db))
+                .app_data(purge_worker())
+                .service(web::scope("").service(get_readyz)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/readyz").to_request();
+        let resp = test::call_service(&app, req).await;
+        let status = resp.status();
+
+        (status, test::read_body_json(resp).await)
+    }
+
+    #[actix_web::test]
+    async fn test_get_healthz() {
+        let app = test::init_service(App::new().service(get_healthz)).await;
+
+        let req = test::TestRequest::get().uri("/healthz").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_get_readyz_when_migrated() {
+        let applied: Vec<BTreeMap<&str, DbValue>> = Migrator::migrations()
+            .iter()
+            .map(|migration| {
+                BTreeMap::from([
+                    ("version", DbValue::from(migration.name())),
+                    ("applied_at", DbValue::from(1_711_000_000_i64)),
+                ])
+            })
+            .collect();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results(install_results())
+            .append_query_results([applied])
+            .into_connection();
+
+        let (status, body) = call_readyz(db).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ready");
+        assert_eq!(body["checks"]["database"]["status"], UP);
+        assert_eq!(body["checks"]["migrations"]["pending"], json!([]));
+        assert_eq!(body["checks"]["worker"]["status"], UP);
+    }
+
+    #[actix_web::test]
+    async fn test_get_readyz_with_pending_migrations() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results(install_results())
+            .append_query_results([Vec::<BTreeMap<&str, DbValue>>::new()])
+            .into_connection();
+
+        let (status, body) = call_readyz(db).await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "unavailable");
+        assert_eq!(body["checks"]["migrations"]["status"], DOWN);
+        assert_eq!(
+            body["checks"]["migrations"]["pending"]
+                .as_array()
+                .unwrap()
+                .len(),
+            Migrator::migrations().len()
+        );
+    }
+}
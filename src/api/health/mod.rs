@@ -0,0 +1 @@
+pub mod health_api;
\ No newline at end of file
@@ -0,0 +1,84 @@
+use crate::{
+    error::{api_error::ApiError, codes},
+    utils::metrics_utils::{encode_metrics, metrics_token_from_env, observe_db_pool},
+};
+use actix_web::{get, http::header, web::Data, HttpRequest, HttpResponse};
+use sea_orm::DatabaseConnection;
+use sha2::{Digest, Sha256};
+
+/// Prometheus scrape endpoint, answers 404 unless `METRICS_TOKEN` is set.
+#[get("/metrics")]
+pub async fn get_metrics(
+    req: HttpRequest,
+    db: Data<DatabaseConnection>,
+) -> Result<HttpResponse, ApiError> {
+    let expected_token = metrics_token_from_env().ok_or(ApiError::NotFound(codes::NOT_FOUND))?;
+
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(ApiError::Unauthorized(codes::TOKEN_MISSING))?;
+
+    // Digests have the same length, comparing them does not leak the token length.
+    if Sha256::digest(token.trim()) != Sha256::digest(expected_token) {
+        return Err(ApiError::Unauthorized(codes::TOKEN_INVALID));
+    }
+
+    observe_db_pool(&db);
+    let (content_type, body) = encode_metrics();
+
+    Ok(HttpResponse::Ok().content_type(content_type).body(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use actix_web::{
+        test,
+        web::{self, Data},
+        App,
+    };
+    use reqwest::StatusCode;
+    use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase};
+
+    use super::get_metrics;
+
+    #[actix_web::test]
+    async fn test_get_metrics_requires_token() {
+        env::set_var("METRICS_TOKEN", "scrape-token");
+
+        let db_data: Data<DatabaseConnection> =
+            Data::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .service(web::scope("").service(get_metrics)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/metrics").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::get()
+            .uri("/metrics")
+            .insert_header(("Authorization", "Bearer wrong-token"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::get()
+            .uri("/metrics")
+            .insert_header(("Authorization", "Bearer scrape-token"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1 @@
+pub mod metrics_api;
\ No newline at end of file
@@ -1,3 +1,8 @@
 pub mod routes;
 pub mod account;
-
+pub mod calendar;
+pub mod avatars;
+pub mod admin;
+pub mod exports;
+pub mod metrics;
+pub mod health;
\ No newline at end of file
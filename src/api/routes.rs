@@ -1,9 +1,33 @@
 use actix_web::web;
 
-use super::account::{
-    auth::{check_code_api::check_code, check_email_api::check_email, send_code_api::send_code},
-    delete::delete_user::delete_user,
-    register::{connection_test_pro_api::connection_test_pro, signin_api::sign_in_pro, signup_api::sign_up_pro},
+use super::{
+    account::{
+        audit::auth_events_api::get_my_auth_events,
+        auth::{
+            check_code_api::check_code, check_email_api::check_email, logout_api::logout,
+            not_me_api::{report_new_device, revoke_new_device}, send_code_api::send_code,
+        },
+        calendar::calendar_token_api::{create_calendar_token, revoke_calendar_token},
+        consent::consent_api::{accept_consents, get_consents},
+        delete::delete_user::delete_user,
+        export::data_export_api::export_my_data,
+        profile::avatar_upload_api::upload_avatar,
+        profile::profile_api::{
+            confirm_email_change, confirm_phone_change, get_profile, request_email_change,
+            request_phone_change, update_profile,
+        },
+        register::{connection_test_pro_api::connection_test_pro, signin_api::sign_in_pro, signup_api::sign_up_pro},
+    },
+    admin::{
+        admin_auth_events_api::get_auth_events, admin_export_api::export_user_data_as_admin,
+        admin_users_api::get_users,
+        admin_users_csv_api::{export_users, import_users},
+    },
+    avatars::avatar_api::get_avatar,
+    calendar::calendar_feed_api::calendar_feed,
+    exports::export_download_api::download_export,
+    health::health_api::{get_healthz, get_readyz},
+    metrics::metrics_api::get_metrics,
 };
 
 pub fn init_auth_pro_routes(cfg: &mut web::ServiceConfig) {
@@ -13,4 +37,52 @@ pub fn init_auth_pro_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(check_email);
     cfg.service(send_code);
     cfg.service(check_code);
+    cfg.service(report_new_device);
+    cfg.service(revoke_new_device);
+}
+
+pub fn init_account_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(create_calendar_token);
+    cfg.service(revoke_calendar_token);
+    cfg.service(get_profile);
+    cfg.service(update_profile);
+    cfg.service(request_email_change);
+    cfg.service(confirm_email_change);
+    cfg.service(request_phone_change);
+    cfg.service(confirm_phone_change);
+    cfg.service(upload_avatar);
+    cfg.service(export_my_data);
+    cfg.service(delete_user);
+    cfg.service(get_consents);
+    cfg.service(accept_consents);
+    cfg.service(get_my_auth_events);
+    cfg.service(logout);
+}
+
+pub fn init_calendar_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(calendar_feed);
+}
+
+pub fn init_avatar_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_avatar);
+}
+
+pub fn init_export_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(download_export);
+}
+
+pub fn init_admin_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(export_user_data_as_admin);
+    cfg.service(get_auth_events);
+    cfg.service(get_users);
+    cfg.service(import_users);
+    cfg.service(export_users);
+}
+
+pub fn init_metrics_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_metrics);
+}
+
+pub fn init_health_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_healthz).service(get_readyz);
 }
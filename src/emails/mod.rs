@@ -1,2 +1,5 @@
 pub mod two_factor_auth_email;
-pub mod types_emails;
\ No newline at end of file
+pub mod types_emails;
+pub mod rdv_emails;
+pub mod data_export_email;
+pub mod new_device_email;
\ No newline at end of file
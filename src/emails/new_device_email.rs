@@ -0,0 +1,66 @@
+use dotenv::dotenv;
+use reqwest::header;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+use tracing::{error, Instrument};
+use crate::utils::metrics_utils::{observe_delivery, BREVO_PROVIDER, EMAIL_CHANNEL};
+use crate::utils::telemetry_utils::brevo_span;
+
+const NEW_DEVICE_TEMPLATE_ID: i64 = 12;
+pub const NOT_ME_PATH: &str = "auth/pro/not_me";
+
+#[derive(Serialize, Deserialize)]
+pub struct NewDeviceEmailData {
+    pub email_to: String,
+    pub first_name: String,
+    pub device: String,
+    pub ip: String,
+    pub token: String,
+}
+
+pub async fn send_new_device_email(data: NewDeviceEmailData) -> Result<(), ()> {
+    if data.email_to.contains("test") {
+        return Ok(());
+    }
+    let client = Client::new();
+    dotenv().ok();
+    let api_root = env::var("API_ROOT").expect("Error loading env var");
+    let api_key = env::var("EMAIL_API_KEY_SENDINBLUE").expect("Error loading env var");
+
+    let body = json!({
+        "to": [{"email": data.email_to}],
+        "templateId": NEW_DEVICE_TEMPLATE_ID,
+        "params": {
+            "firstName": data.first_name,
+            "device": data.device,
+            "ip": data.ip,
+            "url": format!("{}/{}/{}", api_root, NOT_ME_PATH, data.token),
+        },
+    });
+
+    let res = client
+        .post("https://api.brevo.com/v3/smtp/email")
+        .header(header::ACCEPT, "application/json")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("api-key", api_key)
+        .json(&body)
+        .send()
+        .instrument(brevo_span("/v3/smtp/email"))
+        .await;
+
+    let sent = match res {
+        Ok(response) if response.status() == 201 => Ok(()),
+        Ok(response) => {
+            error!("New device email, details: {:?}", response.text().await);
+            Err(())
+        }
+        Err(e) => {
+            error!("New device email, details: {:?}", e);
+            Err(())
+        }
+    };
+    observe_delivery(EMAIL_CHANNEL, BREVO_PROVIDER, sent.is_ok());
+    sent
+}
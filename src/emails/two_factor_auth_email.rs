@@ -1,15 +1,21 @@
 use dotenv::dotenv;
 use std::env;
-use tracing::error;
+use tracing::{error, Instrument};
 use reqwest::header;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use crate::utils::metrics_utils::{observe_delivery, BREVO_PROVIDER, EMAIL_CHANNEL};
+use crate::utils::telemetry_utils::brevo_span;
+
+pub const AUTH_CHECK_PATH: &str = "check";
+pub const EMAIL_CHANGE_CHECK_PATH: &str = "check-email";
 
 #[derive(Serialize, Deserialize)]
 pub struct TwoFactorAuthEmailData {
     pub email_to: String,
     pub first_name: String,
+    pub path: String,
     pub token: String,
 }
 
@@ -28,7 +34,7 @@ pub async fn send_two_factor_auth_email(data: TwoFactorAuthEmailData) -> Result<
     let body = json!({
         "to": [{"email": data.email_to}],
         "templateId": 6,
-        "params": {"firstName": data.first_name, "url": format!("{}/check/?t={}", saas_root, data.token) },
+        "params": {"firstName": data.first_name, "url": format!("{}/{}/?t={}", saas_root, data.path, data.token) },
         "headers": {"X-Mailin-custom": "custom_header_1:custom_value_1|custom_header_2:custom_value_2|custom_header_3:custom_value_3", "charset": "iso-8859-1"}
     });
 
@@ -39,20 +45,23 @@ pub async fn send_two_factor_auth_email(data: TwoFactorAuthEmailData) -> Result<
         .header("api-key", api_key)
         .json(&body)
         .send()
+        .instrument(brevo_span("/v3/smtp/email"))
         .await;
 
-    match res {
+    let sent = match res {
         Ok(response) => {
             if response.status() == 201 {
                 Ok(())
             } else {
                 error!("Auth email, details: {:?}", response.text().await.unwrap());
-                return Err(());
+                Err(())
             }
         }
         Err(e) => {
             error!("Auth email, details: {:?}", e);
-            return Err(());
+            Err(())
         }
-    }
+    };
+    observe_delivery(EMAIL_CHANNEL, BREVO_PROVIDER, sent.is_ok());
+    sent
 }
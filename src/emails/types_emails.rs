@@ -1,4 +1,16 @@
+use entity::entities::user_entity::user_model::Language;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RdvEmailEvent {
+    pub rdv_id: Uuid,
+    pub sequence: i32,
+    pub start_at: i64,
+    pub end_at: i64,
+    pub pro_full_name: String,
+    pub address: Option<String>,
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct RdvConfirmWithFormEmailData {
@@ -6,6 +18,8 @@ pub struct RdvConfirmWithFormEmailData {
     pub first_name: String,
     pub date: String,
     pub token: String,
+    pub lg: Language,
+    pub rdv: RdvEmailEvent,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -15,4 +29,27 @@ pub struct RdvConfirmEmailData {
     pub date: String,
     pub times: String,
     pub token: String,
-}
\ No newline at end of file
+    pub lg: Language,
+    pub rdv: RdvEmailEvent,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RdvRescheduleEmailData {
+    pub email_to: String,
+    pub first_name: String,
+    pub date: String,
+    pub times: String,
+    pub token: String,
+    pub lg: Language,
+    pub rdv: RdvEmailEvent,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RdvCancelEmailData {
+    pub email_to: String,
+    pub first_name: String,
+    pub date: String,
+    pub times: String,
+    pub lg: Language,
+    pub rdv: RdvEmailEvent,
+}
@@ -0,0 +1,247 @@
+use std::fmt;
+
+use actix_web::{
+    http::{header, StatusCode},
+    HttpResponse, ResponseError,
+};
+use entity::entities::user_entity::user_model::Language;
+use sea_orm::DbErr;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tracing::error;
+
+use super::{codes, messages::validation_message};
+
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Every error the API answers with. The variant picks the status, the code
+/// comes from `codes` and `errors` carries the details a client can act on.
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(&'static str),
+    Unauthorized(&'static str),
+    Forbidden(&'static str, Option<Value>),
+    NotFound(&'static str),
+    Conflict(&'static str, Option<Value>),
+    PayloadTooLarge(&'static str),
+    Unprocessable(&'static str, Option<Value>),
+    /// Carries the number of seconds before the client may retry.
+    TooManyRequests(&'static str, i64),
+    Internal(&'static str),
+}
+
+/// RFC 7807 body. `type` stays `about:blank`, so `title` is the status phrase.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Value>,
+    /// Message of each code of `errors`, in the language of the request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Value>,
+}
+
+impl ApiError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(code)
+            | ApiError::Unauthorized(code)
+            | ApiError::Forbidden(code, _)
+            | ApiError::NotFound(code)
+            | ApiError::Conflict(code, _)
+            | ApiError::PayloadTooLarge(code)
+            | ApiError::Unprocessable(code, _)
+            | ApiError::TooManyRequests(code, _)
+            | ApiError::Internal(code) => code,
+        }
+    }
+
+    pub fn errors(&self) -> Option<Value> {
+        match self {
+            ApiError::Forbidden(_, errors)
+            | ApiError::Conflict(_, errors)
+            | ApiError::Unprocessable(_, errors) => errors.to_owned(),
+            ApiError::TooManyRequests(_, time_left) => Some(json!({ "timeLeft": time_left })),
+            _ => None,
+        }
+    }
+
+    pub fn problem(&self) -> ProblemDetails {
+        let status = self.status_code();
+        ProblemDetails {
+            problem_type: String::from("about:blank"),
+            title: String::from(status.canonical_reason().unwrap_or_default()),
+            status: status.as_u16(),
+            code: String::from(self.code()),
+            errors: self.errors(),
+            messages: None,
+        }
+    }
+
+    /// Messages of the fields of `errors` whose value is a code, the empty
+    /// ones of the form structs included.
+    pub fn messages(&self, lg: &Language) -> Option<Value> {
+        let messages: Map<String, Value> = self
+            .errors()?
+            .as_object()?
+            .iter()
+            .filter_map(|(field, code)| {
+                let code = code.as_str().filter(|code| !code.is_empty())?;
+                Some((
+                    field.to_owned(),
+                    Value::String(validation_message(lg, field, code)),
+                ))
+            })
+            .collect();
+
+        match messages.is_empty() {
+            true => None,
+            false => Some(Value::Object(messages)),
+        }
+    }
+
+    /// The response of `error_response` with the `messages` in `lg`.
+    pub fn localized_response(&self, lg: &Language) -> HttpResponse {
+        let mut problem = self.problem();
+        problem.messages = self.messages(lg);
+        self.problem_response(&problem)
+    }
+
+    fn problem_response(&self, problem: &ProblemDetails) -> HttpResponse {
+        let mut resp = HttpResponse::build(self.status_code());
+        resp.insert_header((header::CONTENT_TYPE, PROBLEM_CONTENT_TYPE));
+
+        if let ApiError::TooManyRequests(_, time_left) = self {
+            resp.insert_header((header::RETRY_AFTER, time_left.max(&0).to_string()));
+        }
+
+        resp.body(serde_json::to_string(problem).unwrap_or_default())
+    }
+
+    /// Wraps the details of a validation failure, which are any serializable type.
+    pub fn invalid<T: Serialize>(code: &'static str, errors: T) -> Self {
+        ApiError::Unprocessable(code, serde_json::to_value(errors).ok())
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_, _) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_, _) => StatusCode::CONFLICT,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::Unprocessable(_, _) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::TooManyRequests(_, _) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        self.problem_response(&self.problem())
+    }
+}
+
+/// Error handler of the `Json` and `Query` extractors, so a body or a query
+/// string that cannot be parsed is answered as a problem too.
+pub fn malformed_request() -> actix_web::Error {
+    ApiError::BadRequest(codes::MALFORMED_REQUEST).into()
+}
+
+impl From<DbErr> for ApiError {
+    fn from(err: DbErr) -> Self {
+        match err {
+            DbErr::RecordNotFound(_) => ApiError::NotFound(codes::NOT_FOUND),
+            err => {
+                error!("Database error: {}", err);
+                ApiError::Internal(codes::DATABASE)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn it_serializes_to_problem_json() {
+        let resp =
+            ApiError::invalid(codes::FORM_INVALID, json!({ "email": "invalid" })).error_response();
+
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_CONTENT_TYPE
+        );
+
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let problem: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(problem["type"], "about:blank");
+        assert_eq!(problem["title"], "Unprocessable Entity");
+        assert_eq!(problem["status"], 422);
+        assert_eq!(problem["code"], "form-invalid");
+        assert_eq!(problem["errors"]["email"], "invalid");
+    }
+
+    #[actix_web::test]
+    async fn it_localizes_the_form_errors() {
+        let error = ApiError::invalid(
+            codes::FORM_INVALID,
+            json!({ "firstName": "not_a_first_name", "lastName": "", "email": "already_exists" }),
+        );
+
+        let body = to_bytes(error.localized_response(&Language::En).into_body())
+            .await
+            .unwrap();
+        let problem: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(problem["errors"]["firstName"], "not_a_first_name");
+        assert_eq!(
+            problem["messages"],
+            json!({
+                "firstName": "This is not a first name",
+                "email": "This email is already in use",
+            })
+        );
+        assert_eq!(
+            ApiError::TooManyRequests(codes::ACCOUNT_LOCKED, 120).messages(&Language::En),
+            None
+        );
+    }
+
+    #[actix_web::test]
+    async fn it_tells_when_to_retry() {
+        let resp = ApiError::TooManyRequests(codes::ACCOUNT_LOCKED, 120).error_response();
+
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.headers().get(header::RETRY_AFTER).unwrap(), "120");
+    }
+
+    #[test]
+    fn it_hides_database_errors() {
+        let err: ApiError = DbErr::Custom(String::from("password=secret")).into();
+
+        assert_eq!(err.code(), codes::DATABASE);
+        assert!(err.errors().is_none());
+        assert_eq!(
+            ApiError::from(DbErr::RecordNotFound(String::from("user"))).code(),
+            codes::NOT_FOUND
+        );
+    }
+}
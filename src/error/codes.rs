@@ -0,0 +1,47 @@
+//! Stable `code` values of the problem responses, clients match on these
+//! rather than on the status or the title.
+
+pub const MALFORMED_REQUEST: &str = "malformed-request";
+pub const CURSOR_INVALID: &str = "cursor-invalid";
+pub const CSV_INVALID: &str = "csv-invalid";
+
+pub const SESSION_MISSING: &str = "session-missing";
+pub const SESSION_EXPIRED: &str = "session-expired";
+pub const TOKEN_MISSING: &str = "token-missing";
+pub const TOKEN_EXPIRED: &str = "token-expired";
+pub const TOKEN_INVALID: &str = "token-invalid";
+
+pub const FORBIDDEN: &str = "forbidden";
+pub const ACCOUNT_UNVERIFIED: &str = "account-unverified";
+pub const CONSENT_REQUIRED: &str = "consent-required";
+
+pub const NOT_FOUND: &str = "not-found";
+pub const USER_NOT_FOUND: &str = "user-not-found";
+
+pub const ACCOUNT_EXISTS: &str = "account-exists";
+pub const CONSENT_OUTDATED: &str = "consent-outdated";
+
+pub const AVATAR_TOO_LARGE: &str = "avatar-too-large";
+pub const IMPORT_TOO_LARGE: &str = "import-too-large";
+
+pub const FORM_INVALID: &str = "form-invalid";
+pub const ACCOUNT_UNKNOWN: &str = "account-unknown";
+pub const CODE_INVALID: &str = "code-invalid";
+pub const AVATAR_MISSING: &str = "avatar-missing";
+pub const AVATAR_INVALID_TYPE: &str = "avatar-invalid-type";
+pub const AVATAR_UNREADABLE: &str = "avatar-unreadable";
+
+pub const ACCOUNT_LOCKED: &str = "account-locked";
+
+pub const INTERNAL: &str = "internal";
+pub const DATABASE: &str = "database";
+pub const EMAIL_NOT_SENT: &str = "email-not-sent";
+pub const SMS_NOT_SENT: &str = "sms-not-sent";
+pub const AVATAR_NOT_STORED: &str = "avatar-not-stored";
+pub const CALENDAR_TOKEN_NOT_SAVED: &str = "calendar-token-not-saved";
+pub const CALENDAR_TOKEN_NOT_REVOKED: &str = "calendar-token-not-revoked";
+pub const CONSENT_NOT_RECORDED: &str = "consent-not-recorded";
+pub const EXPORT_NOT_BUILT: &str = "export-not-built";
+pub const PROFILE_NOT_UPDATED: &str = "profile-not-updated";
+pub const ACCOUNT_NOT_DELETED: &str = "account-not-deleted";
+pub const USERS_NOT_IMPORTED: &str = "users-not-imported";
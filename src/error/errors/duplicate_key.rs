@@ -0,0 +1,27 @@
+use sea_orm::DbErr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DuplicateKey {
+    Email,
+    Phone,
+    Other,
+}
+
+pub fn duplicate_key(err: &DbErr) -> Option<DuplicateKey> {
+    let error = match err {
+        DbErr::Query(err) => err.to_string(),
+        _ => return None,
+    };
+
+    if !error.contains("duplicate key") {
+        return None;
+    }
+
+    if error.contains("users_email_key") || error.contains("users_email_lower_key") {
+        Some(DuplicateKey::Email)
+    } else if error.contains("users_phone_key") {
+        Some(DuplicateKey::Phone)
+    } else {
+        Some(DuplicateKey::Other)
+    }
+}
@@ -1,2 +1,4 @@
 pub mod signup_data;
-pub mod signin_data;
\ No newline at end of file
+pub mod signin_data;
+pub mod duplicate_key;
+pub mod profile_data;
\ No newline at end of file
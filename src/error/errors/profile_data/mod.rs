@@ -0,0 +1,2 @@
+pub mod profile_data_check;
+pub mod profile_data_errors;
\ No newline at end of file
@@ -0,0 +1,64 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{
+    string::format_into_string_utils::format_validation_error, validate_utils::has_errors,
+};
+use lazy_static::lazy_static;
+use validator::Validate;
+
+use super::profile_data_errors::ProfileDataErrors;
+
+lazy_static! {
+    static ref ONLY_ALPHABETIC: Regex = Regex::new(r"^[a-zA-Z]+$").unwrap();
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ProfileDataCheck {
+    #[validate(
+        length(min = 2, code = "not_a_first_name"),
+        length(max = 40, code = "only_the_first"),
+        regex(path = "ONLY_ALPHABETIC", code = "not_a_first_name")
+    )]
+    #[serde(rename = "firstName")]
+    pub first_name: Option<String>,
+    #[validate(
+        length(min = 2, code = "not_a_last_name"),
+        length(max = 40, code = "only_the_first"),
+        regex(path = "ONLY_ALPHABETIC", code = "not_a_last_name")
+    )]
+    #[serde(rename = "lastName")]
+    pub last_name: Option<String>,
+}
+
+impl ProfileDataCheck {
+    pub fn validate(&self) -> Option<ProfileDataErrors> {
+        match validator::Validate::validate(self) {
+            Ok(_) => None,
+            Err(err) => {
+                let mut profile_data_errors = ProfileDataErrors::new();
+                let validation_errors_json = serde_json::json!(err);
+                for (key, value) in validation_errors_json.as_object().unwrap() {
+                    match key.as_str() {
+                        "firstName" => {
+                            profile_data_errors.first_name = format_validation_error(value);
+                        }
+                        "lastName" => {
+                            profile_data_errors.last_name = format_validation_error(value);
+                        }
+                        _ => (),
+                    }
+                }
+                let profile_data_vec = vec![
+                    &profile_data_errors.first_name,
+                    &profile_data_errors.last_name,
+                ];
+
+                match has_errors(profile_data_vec) {
+                    false => None,
+                    true => Some(profile_data_errors),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize, Hash)]
+pub struct ProfileDataErrors {
+    #[serde(rename = "firstName")]
+    pub first_name: String,
+    #[serde(rename = "lastName")]
+    pub last_name: String,
+    pub email: String,
+    pub phone: String,
+    pub language: String,
+}
+
+impl ProfileDataErrors {
+    pub fn new() -> Self {
+        ProfileDataErrors::default()
+    }
+}
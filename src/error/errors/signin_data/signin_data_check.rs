@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use crate::utils::{validate_utils::required, string::format_into_string_utils::format_validation_error};
+use crate::utils::{
+    email_utils::normalize_email, string::format_into_string_utils::format_validation_error,
+    validate_utils::required,
+};
 use validator::Validate;
 
 use super::signin_data_errors::SignInDataErrors;
@@ -13,7 +16,9 @@ pub struct SignInDataCheck {
 
 impl SignInDataCheck {
     pub fn new(email: String) -> Self {
-        Self { email }
+        Self {
+            email: normalize_email(&email),
+        }
     }
 
     pub fn validate(self) -> Result<SignInDataCheck, SignInDataErrors> {
@@ -4,6 +4,7 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use crate::utils::{
+    phone_utils::{normalize_phone, PhoneError},
     string::format_into_string_utils::format_validation_error,
     validate_utils::{has_errors, must_accept, required},
 };
@@ -14,7 +15,6 @@ use super::signup_data_errors::SignUpDataErrors;
 
 lazy_static! {
     static ref ONLY_ALPHABETIC: Regex = Regex::new(r"^[a-zA-Z]+$").unwrap();
-    static ref PHONE_REGEX: Regex = Regex::new(r"^(\+33|0)(6|7|9)(\d{2}){4}$").unwrap();
     static ref SIREN_REGEX: Regex = Regex::new(r"^\d{9}$").unwrap();
 }
 
@@ -36,8 +36,9 @@ pub struct SignUpDataCheck {
     pub lastName: String,
     #[validate(custom = "required", email(code = "invalid"))]
     pub email: String,
-    #[validate(custom = "required", regex(path = "PHONE_REGEX", code = "invalid"))]
+    #[validate(custom = "required")]
     pub phone: String,
+    pub country: String,
     #[validate(custom = "required", regex(path = "SIREN_REGEX", code = "not_a_siren"))]
     pub siren: String,
     #[validate(custom = "must_accept")]
@@ -47,53 +48,60 @@ pub struct SignUpDataCheck {
 }
 
 impl SignUpDataCheck {
+    pub fn normalized_phone(&self) -> Result<String, PhoneError> {
+        normalize_phone(&self.phone, &self.country)
+    }
+
     pub fn validate(&self) -> Option<SignUpDataErrors> {
-        match validator::Validate::validate(self) {
-            Ok(_) => None,
-            Err(err) => {
-                let mut signup_data_errors = SignUpDataErrors::new();
-                let validation_errors_json = serde_json::json!(err);
-                for (key, value) in validation_errors_json.as_object().unwrap() {
-                    match key.as_str() {
-                        "firstName" => {
-                            signup_data_errors.firstName = format_validation_error(&value);
-                        }
-                        "lastName" => {
-                            signup_data_errors.lastName = format_validation_error(&value);
-                        }
-                        "email" => {
-                            signup_data_errors.email = format_validation_error(&value);
-                        }
-                        "phone" => {
-                            signup_data_errors.phone = format_validation_error(&value);
-                        }
-                        "siren" => {
-                            signup_data_errors.siren = format_validation_error(&value);
-                        }
-                        "terms" => {
-                            signup_data_errors.terms = format_validation_error(&value);
-                        }
-                        "privacy" => {
-                            signup_data_errors.privacy = format_validation_error(&value);
-                        }
-                        _ => (),
-                    }
-                }
-                let signup_data_vec = vec![
-                    &signup_data_errors.firstName,
-                    &signup_data_errors.lastName,
-                    &signup_data_errors.email,
-                    &signup_data_errors.phone,
-                    &signup_data_errors.siren,
-                    &signup_data_errors.terms,
-                    &signup_data_errors.privacy,
-                ];
+        let mut signup_data_errors = SignUpDataErrors::new();
 
-                match has_errors(signup_data_vec) {
-                    false => None,
-                    true => Some(signup_data_errors),
+        if let Err(err) = validator::Validate::validate(self) {
+            let validation_errors_json = serde_json::json!(err);
+            for (key, value) in validation_errors_json.as_object().unwrap() {
+                match key.as_str() {
+                    "firstName" => {
+                        signup_data_errors.firstName = format_validation_error(value);
+                    }
+                    "lastName" => {
+                        signup_data_errors.lastName = format_validation_error(value);
+                    }
+                    "email" => {
+                        signup_data_errors.email = format_validation_error(value);
+                    }
+                    "phone" => {
+                        signup_data_errors.phone = format_validation_error(value);
+                    }
+                    "siren" => {
+                        signup_data_errors.siren = format_validation_error(value);
+                    }
+                    "terms" => {
+                        signup_data_errors.terms = format_validation_error(value);
+                    }
+                    "privacy" => {
+                        signup_data_errors.privacy = format_validation_error(value);
+                    }
+                    _ => (),
                 }
             }
         }
+
+        if signup_data_errors.phone.is_empty() && self.normalized_phone().is_err() {
+            signup_data_errors.phone = String::from("invalid");
+        }
+
+        let signup_data_vec = vec![
+            &signup_data_errors.firstName,
+            &signup_data_errors.lastName,
+            &signup_data_errors.email,
+            &signup_data_errors.phone,
+            &signup_data_errors.siren,
+            &signup_data_errors.terms,
+            &signup_data_errors.privacy,
+        ];
+
+        match has_errors(signup_data_vec) {
+            false => None,
+            true => Some(signup_data_errors),
+        }
     }
 }
@@ -1,14 +1,41 @@
-pub const REQUIRED: &'static str = "Ce champ est requis";
-pub const NOT_A_LASTNAME: &str = "Ce n'est pas un nom";
-pub const NOT_A_FIRSTNAME: &str = "Ce n'est pas un prénom";
-pub const INVALID: &str = "Ce n'est pas valide";
-
-pub const ONLY_THE_FIRST: &str = "Uniquement le premier";
-pub const MUST_ACCEPT: &str = "Vous devez accepter";
-pub const NOT_A_SIREN: &str = "Ce n'est pas un SIREN";
-pub const NOT_A_SPE: &str = "Sélectionnez-la votre";
-pub const SIREN_TAKEN: &str = "Ce SIREN est déjà pris";
-pub const EMAIL_TAKEN: &str = "Ce mail est déjà utilisé";
-pub const PHONE_TAKEN: &str = "Ce numéro est déjà utilisé";
-pub const EMAIL_NOT_FOUND: &str = "Mail introuvable";
-pub const ERROR_SERVER: &str = "Une erreur est survenue";
\ No newline at end of file
+use entity::entities::user_entity::user_model::Language;
+
+use crate::i18n::catalog::translate;
+
+pub const REQUIRED: &str = "error-required";
+pub const NOT_A_LASTNAME: &str = "error-not-a-last-name";
+pub const NOT_A_FIRSTNAME: &str = "error-not-a-first-name";
+pub const INVALID: &str = "error-invalid";
+
+pub const ONLY_THE_FIRST: &str = "error-only-the-first";
+pub const MUST_ACCEPT: &str = "error-must-accept";
+pub const NOT_A_SIREN: &str = "error-not-a-siren";
+pub const NOT_A_SPE: &str = "error-not-a-spe";
+pub const SIREN_TAKEN: &str = "error-siren-taken";
+pub const EMAIL_TAKEN: &str = "error-email-taken";
+pub const PHONE_TAKEN: &str = "error-phone-taken";
+pub const EMAIL_NOT_FOUND: &str = "error-email-not-found";
+pub const ERROR_SERVER: &str = "error-server";
+
+pub fn message(lg: &Language, key: &str) -> String {
+    translate(lg, key, None)
+}
+
+pub fn validation_message(lg: &Language, field: &str, code: &str) -> String {
+    let key = match (field, code) {
+        (_, "required") => REQUIRED,
+        (_, "not_a_last_name") => NOT_A_LASTNAME,
+        (_, "not_a_first_name") => NOT_A_FIRSTNAME,
+        (_, "only_the_first") => ONLY_THE_FIRST,
+        (_, "must_accept") => MUST_ACCEPT,
+        (_, "not_a_siren") => NOT_A_SIREN,
+        (_, "not_a_spe") => NOT_A_SPE,
+        ("siren", "already_exists") => SIREN_TAKEN,
+        ("email", "already_exists") => EMAIL_TAKEN,
+        ("phone", "already_exists") => PHONE_TAKEN,
+        ("email", "not_found") => EMAIL_NOT_FOUND,
+        (_, "invalid") => INVALID,
+        _ => ERROR_SERVER,
+    };
+    message(lg, key)
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use entity::entities::user_entity::user_model::Language;
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use lazy_static::lazy_static;
+use tracing::{error, warn};
+use unic_langid::LanguageIdentifier;
+
+use super::language::language_code;
+
+pub const LOCALES: [(&str, &str); 5] = [
+    ("fr", include_str!("locales/fr.ftl")),
+    ("en", include_str!("locales/en.ftl")),
+    ("es", include_str!("locales/es.ftl")),
+    ("de", include_str!("locales/de.ftl")),
+    ("it", include_str!("locales/it.ftl")),
+];
+
+const FALLBACK_LOCALE: &str = "fr";
+
+lazy_static! {
+    static ref BUNDLES: HashMap<&'static str, FluentBundle<FluentResource>> = LOCALES
+        .iter()
+        .map(|(code, source)| (*code, create_bundle(code, source)))
+        .collect();
+}
+
+fn create_bundle(code: &str, source: &str) -> FluentBundle<FluentResource> {
+    let lang_id: LanguageIdentifier = code.parse().expect("Invalid locale code");
+    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(res, errors)| {
+        error!("Cannot parse {} locale: {:?}", code, errors);
+        res
+    });
+    let mut bundle = FluentBundle::new_concurrent(vec![lang_id]);
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .expect("Duplicated message in locale");
+    bundle
+}
+
+pub fn translate(lg: &Language, key: &str, args: Option<&FluentArgs>) -> String {
+    match format_message(language_code(lg), key, args) {
+        Some(message) => message,
+        None => {
+            warn!("Missing {} message for {}", language_code(lg), key);
+            format_message(FALLBACK_LOCALE, key, args).unwrap_or_else(|| key.to_string())
+        }
+    }
+}
+
+fn format_message(code: &str, key: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let bundle = BUNDLES.get(code)?;
+    let pattern = bundle.get_message(key)?.value()?;
+    let mut errors = vec![];
+    let message = bundle.format_pattern(pattern, args, &mut errors);
+
+    if !errors.is_empty() {
+        error!("Cannot format {} message {}: {:?}", code, key, errors);
+    }
+
+    Some(message.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use regex::Regex;
+
+    use super::*;
+
+    fn message_keys(source: &str) -> HashSet<String> {
+        let message_id = Regex::new(r"(?m)^([a-zA-Z][a-zA-Z0-9_-]*) =").unwrap();
+        message_id
+            .captures_iter(source)
+            .map(|captures| captures[1].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn it_has_every_key_in_every_language() {
+        let (_, reference) = LOCALES[0];
+        let reference_keys = message_keys(reference);
+
+        for (code, source) in LOCALES.iter() {
+            let keys = message_keys(source);
+            let missing: Vec<&String> = reference_keys.difference(&keys).collect();
+            let extra: Vec<&String> = keys.difference(&reference_keys).collect();
+
+            assert!(missing.is_empty(), "{} is missing {:?}", code, missing);
+            assert!(extra.is_empty(), "{} has unknown {:?}", code, extra);
+        }
+    }
+
+    #[test]
+    fn it_translates_with_args() {
+        let mut args = FluentArgs::new();
+        args.set("firstName", "Rob");
+        args.set("code", "1234567");
+
+        assert_eq!(
+            translate(&Language::En, "sms-auth-code", Some(&args)),
+            "Hello Rob,\nYour Focus verification code is: 1234567."
+        );
+    }
+
+    #[test]
+    fn it_falls_back_to_the_key() {
+        assert_eq!(translate(&Language::De, "unknown-key", None), "unknown-key");
+    }
+}
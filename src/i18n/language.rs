@@ -0,0 +1,84 @@
+use actix_web::{http::header, HttpRequest};
+use entity::entities::user_entity::user_model::Language;
+
+pub fn language_code(lg: &Language) -> &'static str {
+    match lg {
+        Language::Fr => "fr",
+        Language::En => "en",
+        Language::Es => "es",
+        Language::De => "de",
+        Language::It => "it",
+    }
+}
+
+pub fn language_from_code(code: &str) -> Option<Language> {
+    let primary = code.split(['-', '_']).next()?.trim().to_lowercase();
+    match primary.as_str() {
+        "fr" => Some(Language::Fr),
+        "en" => Some(Language::En),
+        "es" => Some(Language::Es),
+        "de" => Some(Language::De),
+        "it" => Some(Language::It),
+        _ => None,
+    }
+}
+
+pub fn language_from_accept_language(accept_language: &str) -> Option<Language> {
+    let mut candidates: Vec<(f32, Language)> = accept_language
+        .split(',')
+        .filter_map(|part| {
+            let mut params = part.trim().split(';');
+            let lg = language_from_code(params.next()?)?;
+            let quality = params
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            Some((quality, lg))
+        })
+        .filter(|(quality, _)| *quality > 0.0)
+        .collect();
+
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    candidates.into_iter().next().map(|(_, lg)| lg)
+}
+
+pub fn language_from_request(req: &HttpRequest) -> Language {
+    req.headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(language_from_accept_language)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    #[test]
+    fn it_picks_the_preferred_supported_language() {
+        assert_eq!(
+            language_from_accept_language("ja, de-CH;q=0.8, en;q=0.9"),
+            Some(Language::En)
+        );
+        assert_eq!(language_from_accept_language("ja, zh;q=0.5"), None);
+        assert_eq!(
+            language_from_accept_language("it;q=0, es;q=0.1"),
+            Some(Language::Es)
+        );
+    }
+
+    #[test]
+    fn it_reads_the_request_language() {
+        let req = TestRequest::default()
+            .insert_header((header::ACCEPT_LANGUAGE, "de"))
+            .to_http_request();
+
+        assert_eq!(language_from_request(&req), Language::De);
+        assert_eq!(
+            language_from_request(&TestRequest::default().to_http_request()),
+            Language::Fr
+        );
+    }
+}
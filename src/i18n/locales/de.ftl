@@ -0,0 +1,72 @@
+## Errors
+
+error-required = Dieses Feld ist erforderlich
+error-not-a-last-name = Das ist kein Nachname
+error-not-a-first-name = Das ist kein Vorname
+error-invalid = Das ist nicht gültig
+error-only-the-first = Nur der erste
+error-must-accept = Sie müssen zustimmen
+error-not-a-siren = Das ist keine SIREN
+error-not-a-spe = Wählen Sie Ihr Fachgebiet
+error-siren-taken = Diese SIREN ist bereits vergeben
+error-email-taken = Diese E-Mail wird bereits verwendet
+error-phone-taken = Diese Nummer wird bereits verwendet
+error-email-not-found = E-Mail nicht gefunden
+error-server = Ein Fehler ist aufgetreten
+
+## SMS
+
+sms-auth-code =
+    Hallo { $firstName },
+    Ihr Focus-Bestätigungscode lautet: { $code }.
+sms-rdv-reminder =
+    Hallo
+    Termin { $date } um { $timeslot }
+    { $proFullName }
+    Infos und Absage: { $url }.
+
+## Emails
+
+email-rdv-confirm-subject = Ihr Termin ist bestätigt
+email-rdv-reschedule-subject = Ihr Termin wurde verschoben
+email-rdv-cancel-subject = Ihr Termin wurde abgesagt
+email-rdv-summary = Termin mit { $proFullName }
+
+## Pages
+
+page-not-me-title = Das waren nicht Sie?
+page-not-me-text = Melden Sie das neue Gerät ab, wenn Sie sich nicht angemeldet haben. Ihre E-Mail und Ihre Telefonnummer müssen bei der nächsten Anmeldung erneut bestätigt werden.
+page-not-me-confirm = Dieses Gerät abmelden
+page-not-me-done = Das Gerät ist abgemeldet. Bestätigen Sie Ihre E-Mail und Ihre Telefonnummer bei der nächsten Anmeldung.
+
+## Dates
+
+date-long = { $weekday }, { $day }. { $month } { $year }
+date-slash = { $weekday } { $day }.{ $month }.
+date-times = { $hours }:{ $minutes }
+date-weekday-1 = Montag
+date-weekday-2 = Dienstag
+date-weekday-3 = Mittwoch
+date-weekday-4 = Donnerstag
+date-weekday-5 = Freitag
+date-weekday-6 = Samstag
+date-weekday-7 = Sonntag
+date-weekday-short-1 = Mo
+date-weekday-short-2 = Di
+date-weekday-short-3 = Mi
+date-weekday-short-4 = Do
+date-weekday-short-5 = Fr
+date-weekday-short-6 = Sa
+date-weekday-short-7 = So
+date-month-1 = Januar
+date-month-2 = Februar
+date-month-3 = März
+date-month-4 = April
+date-month-5 = Mai
+date-month-6 = Juni
+date-month-7 = Juli
+date-month-8 = August
+date-month-9 = September
+date-month-10 = Oktober
+date-month-11 = November
+date-month-12 = Dezember
@@ -0,0 +1,72 @@
+## Errors
+
+error-required = This field is required
+error-not-a-last-name = This is not a last name
+error-not-a-first-name = This is not a first name
+error-invalid = This is not valid
+error-only-the-first = Only the first one
+error-must-accept = You must accept
+error-not-a-siren = This is not a SIREN
+error-not-a-spe = Select your speciality
+error-siren-taken = This SIREN is already taken
+error-email-taken = This email is already in use
+error-phone-taken = This number is already in use
+error-email-not-found = Email not found
+error-server = An error occurred
+
+## SMS
+
+sms-auth-code =
+    Hello { $firstName },
+    Your Focus verification code is: { $code }.
+sms-rdv-reminder =
+    Hello
+    Appointment { $date } at { $timeslot }
+    { $proFullName }
+    Details and cancellation: { $url }.
+
+## Emails
+
+email-rdv-confirm-subject = Your appointment is confirmed
+email-rdv-reschedule-subject = Your appointment has been rescheduled
+email-rdv-cancel-subject = Your appointment has been cancelled
+email-rdv-summary = Appointment with { $proFullName }
+
+## Pages
+
+page-not-me-title = Not you?
+page-not-me-text = Sign out the new device if you did not log in. Your email and phone will have to be verified again at your next login.
+page-not-me-confirm = Sign out this device
+page-not-me-done = The device is signed out. Verify your email and phone at your next login.
+
+## Dates
+
+date-long = { $weekday }, { $month } { $day }, { $year }
+date-slash = { $weekday } { $month }/{ $day }
+date-times = { $hours }:{ $minutes }
+date-weekday-1 = Monday
+date-weekday-2 = Tuesday
+date-weekday-3 = Wednesday
+date-weekday-4 = Thursday
+date-weekday-5 = Friday
+date-weekday-6 = Saturday
+date-weekday-7 = Sunday
+date-weekday-short-1 = Mon
+date-weekday-short-2 = Tue
+date-weekday-short-3 = Wed
+date-weekday-short-4 = Thu
+date-weekday-short-5 = Fri
+date-weekday-short-6 = Sat
+date-weekday-short-7 = Sun
+date-month-1 = January
+date-month-2 = February
+date-month-3 = March
+date-month-4 = April
+date-month-5 = May
+date-month-6 = June
+date-month-7 = July
+date-month-8 = August
+date-month-9 = September
+date-month-10 = October
+date-month-11 = November
+date-month-12 = December
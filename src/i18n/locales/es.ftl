@@ -0,0 +1,72 @@
+## Errors
+
+error-required = Este campo es obligatorio
+error-not-a-last-name = Esto no es un apellido
+error-not-a-first-name = Esto no es un nombre
+error-invalid = Esto no es válido
+error-only-the-first = Solo el primero
+error-must-accept = Debe aceptar
+error-not-a-siren = Esto no es un SIREN
+error-not-a-spe = Seleccione su especialidad
+error-siren-taken = Este SIREN ya está registrado
+error-email-taken = Este correo ya está en uso
+error-phone-taken = Este número ya está en uso
+error-email-not-found = Correo no encontrado
+error-server = Se ha producido un error
+
+## SMS
+
+sms-auth-code =
+    Hola { $firstName },
+    Su código de verificación Focus es: { $code }.
+sms-rdv-reminder =
+    Hola
+    Cita { $date } a las { $timeslot }
+    { $proFullName }
+    Información y cancelación: { $url }.
+
+## Emails
+
+email-rdv-confirm-subject = Su cita está confirmada
+email-rdv-reschedule-subject = Su cita ha sido reprogramada
+email-rdv-cancel-subject = Su cita ha sido cancelada
+email-rdv-summary = Cita con { $proFullName }
+
+## Pages
+
+page-not-me-title = ¿No ha sido usted?
+page-not-me-text = Desconecte el nuevo dispositivo si no ha iniciado sesión. Su email y su teléfono deberán verificarse de nuevo en su próximo inicio de sesión.
+page-not-me-confirm = Desconectar este dispositivo
+page-not-me-done = El dispositivo está desconectado. Verifique su email y su teléfono en su próximo inicio de sesión.
+
+## Dates
+
+date-long = { $weekday }, { $day } de { $month } de { $year }
+date-slash = { $weekday } { $day }/{ $month }
+date-times = { $hours }:{ $minutes }
+date-weekday-1 = lunes
+date-weekday-2 = martes
+date-weekday-3 = miércoles
+date-weekday-4 = jueves
+date-weekday-5 = viernes
+date-weekday-6 = sábado
+date-weekday-7 = domingo
+date-weekday-short-1 = lun
+date-weekday-short-2 = mar
+date-weekday-short-3 = mié
+date-weekday-short-4 = jue
+date-weekday-short-5 = vie
+date-weekday-short-6 = sáb
+date-weekday-short-7 = dom
+date-month-1 = enero
+date-month-2 = febrero
+date-month-3 = marzo
+date-month-4 = abril
+date-month-5 = mayo
+date-month-6 = junio
+date-month-7 = julio
+date-month-8 = agosto
+date-month-9 = septiembre
+date-month-10 = octubre
+date-month-11 = noviembre
+date-month-12 = diciembre
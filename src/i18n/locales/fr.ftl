@@ -0,0 +1,75 @@
+## Errors
+
+error-required = Ce champ est requis
+error-not-a-last-name = Ce n'est pas un nom
+error-not-a-first-name = Ce n'est pas un prénom
+error-invalid = Ce n'est pas valide
+error-only-the-first = Uniquement le premier
+error-must-accept = Vous devez accepter
+error-not-a-siren = Ce n'est pas un SIREN
+error-not-a-spe = Sélectionnez-la votre
+error-siren-taken = Ce SIREN est déjà pris
+error-email-taken = Ce mail est déjà utilisé
+error-phone-taken = Ce numéro est déjà utilisé
+error-email-not-found = Mail introuvable
+error-server = Une erreur est survenue
+
+## SMS
+
+sms-auth-code =
+    Bonjour { $firstName },
+    Votre code de vérification Focus est: { $code }.
+sms-rdv-reminder =
+    Bonjour
+    RDV { $date } à { $timeslot }
+    { $proFullName }
+    Infos et annulation: { $url }.
+
+## Emails
+
+email-rdv-confirm-subject = Votre rendez-vous est confirmé
+email-rdv-reschedule-subject = Votre rendez-vous a été déplacé
+email-rdv-cancel-subject = Votre rendez-vous a été annulé
+email-rdv-summary = Rendez-vous avec { $proFullName }
+
+## Pages
+
+page-not-me-title = Ce n'était pas vous ?
+page-not-me-text = Déconnectez le nouvel appareil si vous ne vous êtes pas connecté. Votre email et votre téléphone devront être vérifiés à nouveau lors de votre prochaine connexion.
+page-not-me-confirm = Déconnecter cet appareil
+page-not-me-done = L'appareil est déconnecté. Vérifiez votre email et votre téléphone lors de votre prochaine connexion.
+
+## Dates
+
+date-long = { $weekday } { $day ->
+        [1] 1er
+       *[other] { $day }
+    } { $month } { $year }
+date-slash = { $weekday } { $day }/{ $month }
+date-times = { $hours }h{ $minutes }
+date-weekday-1 = lundi
+date-weekday-2 = mardi
+date-weekday-3 = mercredi
+date-weekday-4 = jeudi
+date-weekday-5 = vendredi
+date-weekday-6 = samedi
+date-weekday-7 = dimanche
+date-weekday-short-1 = lun
+date-weekday-short-2 = mar
+date-weekday-short-3 = mer
+date-weekday-short-4 = jeu
+date-weekday-short-5 = ven
+date-weekday-short-6 = sam
+date-weekday-short-7 = dim
+date-month-1 = janvier
+date-month-2 = février
+date-month-3 = mars
+date-month-4 = avril
+date-month-5 = mai
+date-month-6 = juin
+date-month-7 = juillet
+date-month-8 = août
+date-month-9 = septembre
+date-month-10 = octobre
+date-month-11 = novembre
+date-month-12 = décembre
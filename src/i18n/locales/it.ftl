@@ -0,0 +1,72 @@
+## Errors
+
+error-required = Questo campo è obbligatorio
+error-not-a-last-name = Questo non è un cognome
+error-not-a-first-name = Questo non è un nome
+error-invalid = Questo non è valido
+error-only-the-first = Solo il primo
+error-must-accept = Devi accettare
+error-not-a-siren = Questo non è un SIREN
+error-not-a-spe = Seleziona la tua specialità
+error-siren-taken = Questo SIREN è già registrato
+error-email-taken = Questa email è già in uso
+error-phone-taken = Questo numero è già in uso
+error-email-not-found = Email non trovata
+error-server = Si è verificato un errore
+
+## SMS
+
+sms-auth-code =
+    Ciao { $firstName },
+    Il tuo codice di verifica Focus è: { $code }.
+sms-rdv-reminder =
+    Ciao
+    Appuntamento { $date } alle { $timeslot }
+    { $proFullName }
+    Info e cancellazione: { $url }.
+
+## Emails
+
+email-rdv-confirm-subject = Il tuo appuntamento è confermato
+email-rdv-reschedule-subject = Il tuo appuntamento è stato spostato
+email-rdv-cancel-subject = Il tuo appuntamento è stato annullato
+email-rdv-summary = Appuntamento con { $proFullName }
+
+## Pages
+
+page-not-me-title = Non era lei?
+page-not-me-text = Disconnetta il nuovo dispositivo se non ha effettuato l'accesso. La sua email e il suo telefono dovranno essere verificati di nuovo al prossimo accesso.
+page-not-me-confirm = Disconnetti questo dispositivo
+page-not-me-done = Il dispositivo è disconnesso. Verifichi la sua email e il suo telefono al prossimo accesso.
+
+## Dates
+
+date-long = { $weekday } { $day } { $month } { $year }
+date-slash = { $weekday } { $day }/{ $month }
+date-times = { $hours }:{ $minutes }
+date-weekday-1 = lunedì
+date-weekday-2 = martedì
+date-weekday-3 = mercoledì
+date-weekday-4 = giovedì
+date-weekday-5 = venerdì
+date-weekday-6 = sabato
+date-weekday-7 = domenica
+date-weekday-short-1 = lun
+date-weekday-short-2 = mar
+date-weekday-short-3 = mer
+date-weekday-short-4 = gio
+date-weekday-short-5 = ven
+date-weekday-short-6 = sab
+date-weekday-short-7 = dom
+date-month-1 = gennaio
+date-month-2 = febbraio
+date-month-3 = marzo
+date-month-4 = aprile
+date-month-5 = maggio
+date-month-6 = giugno
+date-month-7 = luglio
+date-month-8 = agosto
+date-month-9 = settembre
+date-month-10 = ottobre
+date-month-11 = novembre
+date-month-12 = dicembre
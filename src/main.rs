@@ -1,65 +1,157 @@
 pub mod api;
 pub mod emails;
 pub mod error;
+pub mod i18n;
 pub mod middlewares;
 pub mod repository;
 pub mod sms;
+pub mod storage;
 pub mod types;
 pub mod utils;
 
 use actix_cors::Cors;
 use actix_web::{
     http::header,
-    middleware::{DefaultHeaders, Logger},
+    middleware::{Condition, DefaultHeaders, Logger},
     web::{self, Data},
     App, HttpServer,
 };
-use api::routes::init_auth_pro_routes;
+use api::routes::{
+    init_account_routes, init_admin_routes, init_auth_pro_routes, init_avatar_routes,
+    init_calendar_routes, init_export_routes, init_health_routes, init_metrics_routes,
+};
 use dotenv::dotenv;
-use migration::{Migrator, MigratorTrait};
+use error::api_error::malformed_request;
+use migration::lock::{connect_for_migrations, up_with_lock};
 use repository::postgres_repo::PostgresRepo;
-use tracing::{error, event};
-use crate::middlewares::{check_auth_middleware::Auth, app_state::AppState};
+use service::query::read_replica::{read_replica, set_read_replica};
+use std::io;
+use storage::blob_store::{init_blob_store, BlobStore};
+use tracing::{error, event, info, warn};
+use utils::{
+    account_deletion_utils::start_account_purge,
+    consent_utils::consent_required_from_env,
+    log_utils::{init_logging, LogConfig},
+    login_policy_utils::LoginPolicy,
+    migration_utils::migrate_on_start_from_env,
+    task_manager_utils::{shutdown_timeout_from_env, TaskManager},
+    telemetry_utils::{init_tracer, shutdown_tracer, TelemetryConfig},
+};
+use crate::middlewares::{
+    check_admin_middleware::Admin, check_auth_middleware::Auth,
+    check_consent_middleware::RequireConsents, localize_errors_middleware::LocalizeErrors,
+    metrics_middleware::RecordMetrics, request_id_middleware::TraceRequest,
+};
 
 #[actix_web::main]
-async fn main() -> std::io::Result<()> {
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::DEBUG)
-        .with_test_writer()
-        .init();
+async fn main() -> io::Result<()> {
+    let tracer = TelemetryConfig::from_env()
+        .map(|config| init_tracer(&config).expect("Failed to init the OTLP exporter"));
+    init_logging(&LogConfig::from_env(), tracer);
     dotenv().ok();
     let host = std::env::var("HOST").expect("HOST must be set");
     let port = std::env::var("PORT").expect("PORT must be set");
     let addr = format!("{}:{}", host, port);
-    let connection = PostgresRepo::init().await;
-    Migrator::up(&connection.db, None)
-        .await
-        .unwrap_or_else(|_| {
-            error!("Failed to migrate");
-            std::process::exit(1);
-        });
+    let connection = PostgresRepo::init().await.map_err(|err| {
+        error!("{}", err);
+        io::Error::other(err)
+    })?;
+    if let Some(read_db) = connection.read_db {
+        set_read_replica(read_db).expect("Read replica set twice");
+    }
+    if migrate_on_start_from_env() {
+        migrate().await.map_err(|err| {
+            error!("Failed to migrate: {}", err);
+            io::Error::other(err)
+        })?;
+    }
 
     let db_data = Data::new(connection.db);
-    let app_state_data = Data::new(AppState::new());
+    let blob_store_data: Data<dyn BlobStore> = Data::from(init_blob_store());
+    let task_manager_data = Data::new(TaskManager::new());
+    let purge_worker = start_account_purge(
+        db_data.clone(),
+        blob_store_data.clone(),
+        &task_manager_data,
+    );
+    let purge_worker_data = Data::new(purge_worker);
+    let login_policy_data = Data::new(LoginPolicy::from_env());
+    let consent_required = consent_required_from_env();
+    let shutdown_timeout = shutdown_timeout_from_env();
+    let server_db_data = db_data.clone();
+    let server_task_manager_data = task_manager_data.clone();
     event!(tracing::Level::INFO, "Server running on {}", addr);
     HttpServer::new(move || {
         App::new()
-            .app_data(db_data.clone())
-            .app_data(app_state_data.clone())
+            .app_data(server_db_data.clone())
+            .app_data(blob_store_data.clone())
+            .app_data(server_task_manager_data.clone())
+            .app_data(purge_worker_data.clone())
+            .app_data(login_policy_data.clone())
+            .app_data(web::JsonConfig::default().error_handler(|_, _| malformed_request()))
+            .app_data(web::QueryConfig::default().error_handler(|_, _| malformed_request()))
+            .wrap(LocalizeErrors)
             .wrap(DefaultHeaders::new().add(("X-Powered-By", "Focus")))
             .wrap(
                 Cors::default()
                     .allowed_origin("http://localhost:3000")
-                    .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
+                    .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE"])
                     .allowed_headers(vec![header::AUTHORIZATION, header::ACCEPT, header::ORIGIN])
                     .allowed_header(header::CONTENT_TYPE)
                     .supports_credentials()
                     .max_age(3600),
             )
-            .wrap(Logger::default())
+            .wrap(RecordMetrics)
+            .wrap(TraceRequest)
+            // Outside `TraceRequest`, which sets the request id on the response.
+            .wrap(Logger::new(
+                r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T %{x-request-id}o"#,
+            ))
+            .configure(init_metrics_routes)
+            .configure(init_health_routes)
             .service(web::scope("/auth/pro").configure(init_auth_pro_routes))
+            .service(
+                web::scope("/account")
+                    .wrap(Condition::new(consent_required, RequireConsents))
+                    .wrap(Auth)
+                    .configure(init_account_routes),
+            )
+            .service(web::scope("/calendar").configure(init_calendar_routes))
+            .service(web::scope("/avatars").configure(init_avatar_routes))
+            .service(web::scope("/exports").configure(init_export_routes))
+            .service(
+                web::scope("/admin")
+                    .wrap(Admin)
+                    .wrap(Auth)
+                    .configure(init_admin_routes),
+            )
     })
+    // SIGTERM stops accepting connections and gives the in-flight requests
+    // the timeout to finish, then the workers are stopped.
+    .shutdown_timeout(shutdown_timeout.as_secs())
     .bind(addr)?
     .run()
-    .await
+    .await?;
+
+    info!("Server stopped, draining background jobs");
+    let aborted = task_manager_data.shutdown(shutdown_timeout).await;
+    if aborted > 0 {
+        warn!("{} background jobs aborted at shutdown", aborted);
+    }
+    PostgresRepo::close(&db_data).await;
+    if let Some(read_db) = read_replica() {
+        PostgresRepo::close(read_db).await;
+    }
+    shutdown_tracer();
+    Ok(())
+}
+
+/// Other replicas starting at the same time wait on the advisory lock.
+async fn migrate() -> Result<(), sea_orm::DbErr> {
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let db = connect_for_migrations(&database_url, "public").await?;
+    info!("Applying the pending migrations");
+    let migrated = up_with_lock(&db).await;
+    db.close().await?;
+    migrated
 }
@@ -0,0 +1,93 @@
+use std::future::{ready, Ready};
+
+use actix_web::{dev::Payload, FromRequest, HttpMessage, HttpRequest};
+use uuid::Uuid;
+
+use crate::error::{api_error::ApiError, codes};
+
+/// User behind the session cookie. `Auth` stores it in the extensions of
+/// each request it lets through, so it never leaks to another request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthenticatedUser {
+    pub id: Uuid,
+}
+
+impl AuthenticatedUser {
+    /// `None` outside the scopes wrapped in `Auth`.
+    pub fn of(req: &impl HttpMessage) -> Option<Self> {
+        req.extensions().get::<AuthenticatedUser>().copied()
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(AuthenticatedUser::of(req).ok_or(ApiError::Unauthorized(codes::SESSION_MISSING)))
+    }
+}
+
+/// Stands in for `Auth` in the handler tests.
+#[cfg(test)]
+pub mod test_utils {
+    use std::{
+        future::{ready, Ready},
+        rc::Rc,
+    };
+
+    use actix_web::{
+        dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+        Error, HttpMessage,
+    };
+    use futures_util::future::LocalBoxFuture;
+    use uuid::Uuid;
+
+    use super::AuthenticatedUser;
+
+    pub struct AuthenticateAs(pub Uuid);
+
+    impl<S, B> Transform<S, ServiceRequest> for AuthenticateAs
+    where
+        S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+        S::Future: 'static,
+        B: 'static,
+    {
+        type Response = ServiceResponse<B>;
+        type Error = Error;
+        type InitError = ();
+        type Transform = AuthenticateAsMiddleware<S>;
+        type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+        fn new_transform(&self, service: S) -> Self::Future {
+            ready(Ok(AuthenticateAsMiddleware {
+                service: Rc::new(service),
+                id: self.0,
+            }))
+        }
+    }
+
+    pub struct AuthenticateAsMiddleware<S> {
+        service: Rc<S>,
+        id: Uuid,
+    }
+
+    impl<S, B> Service<ServiceRequest> for AuthenticateAsMiddleware<S>
+    where
+        S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+        S::Future: 'static,
+        B: 'static,
+    {
+        type Response = ServiceResponse<B>;
+        type Error = Error;
+        type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+        forward_ready!(service);
+
+        fn call(&self, req: ServiceRequest) -> Self::Future {
+            req.extensions_mut()
+                .insert(AuthenticatedUser { id: self.id });
+            Box::pin(self.service.call(req))
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error,
+};
+use entity::entities::user_entity::user_model::Role;
+use futures_util::future::LocalBoxFuture;
+use sea_orm::DatabaseConnection;
+use service::query::user_queries::UserQuery;
+use tracing::warn;
+
+use crate::error::{api_error::ApiError, codes};
+
+use super::authenticated_user::AuthenticatedUser;
+
+/// Must be wrapped inside `Auth`, which resolves the user id it checks.
+pub struct Admin;
+
+impl<S, B> Transform<S, ServiceRequest> for Admin
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = AdminMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AdminMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct AdminMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for AdminMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let db = match req.app_data::<web::Data<DatabaseConnection>>() {
+                Some(db) => db.clone(),
+                None => return Err(ApiError::Internal(codes::DATABASE).into()),
+            };
+            let user_id = match AuthenticatedUser::of(&req) {
+                Some(user) => user.id,
+                None => return Err(ApiError::Forbidden(codes::FORBIDDEN, None).into()),
+            };
+
+            match UserQuery::find_user_by_id(&db, user_id).await {
+                Ok(user) if user.role == Role::Admin => service.call(req).await,
+                Ok(_) => {
+                    warn!("Admin access denied to user: {}", user_id);
+                    Err(ApiError::Forbidden(codes::FORBIDDEN, None).into())
+                }
+                Err(_) => Err(ApiError::Forbidden(codes::FORBIDDEN, None).into()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http, test, App, HttpResponse};
+    use entity::entities::user_entity::user_model;
+    use sea_orm::{DatabaseBackend, MockDatabase};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::middlewares::authenticated_user::test_utils::AuthenticateAs;
+
+    fn authenticated() -> AuthenticateAs {
+        AuthenticateAs(Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap())
+    }
+
+    fn user(role: Role) -> user_model::Model {
+        user_model::Model {
+            id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            role,
+            ..Default::default()
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_admin_middleware_success() {
+        let db_data = web::Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user(Role::Admin)]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(Admin)
+                .wrap(authenticated())
+                .service(web::resource("/").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_admin_middleware_forbidden() {
+        let db_data = web::Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[user(Role::Pro)]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(Admin)
+                .wrap(authenticated())
+                .service(web::resource("/").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let resp = test::try_call_service(&app, req).await;
+
+        assert_eq!(
+            resp.err().unwrap().as_response_error().status_code(),
+            http::StatusCode::FORBIDDEN
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_admin_middleware_unauthenticated() {
+        let db_data =
+            web::Data::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(Admin)
+                .service(web::resource("/").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let resp = test::try_call_service(&app, req).await;
+
+        assert_eq!(
+            resp.err().unwrap().as_response_error().status_code(),
+            http::StatusCode::FORBIDDEN
+        );
+    }
+}
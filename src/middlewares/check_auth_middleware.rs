@@ -1,23 +1,32 @@
-use std::future::{ready, Ready};
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
 
-use crate::utils::{
-    cookie_utils::{get_cookie_from_service_request, CookiePayload},
-    crypto_utils::{decrypt_payload, EncryptedPayload},
+use crate::{
+    error::{api_error::ApiError, codes},
+    utils::{
+        cookie_utils::{get_cookie_from_service_request, CookiePayload},
+        crypto_utils::{decrypt_payload, EncryptedPayload},
+    },
 };
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    error, web, Error,
+    web, Error, HttpMessage,
 };
 use chrono::Utc;
 use futures_util::future::LocalBoxFuture;
+use sea_orm::DatabaseConnection;
+use service::query::session_queries::SessionQuery;
+use uuid::Uuid;
 
-use super::app_state::AppState;
+use super::authenticated_user::AuthenticatedUser;
 
 pub struct Auth;
 
 impl<S, B> Transform<S, ServiceRequest> for Auth
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -28,17 +37,19 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(AuthMiddleware { service }))
+        ready(Ok(AuthMiddleware {
+            service: Rc::new(service),
+        }))
     }
 }
 
 pub struct AuthMiddleware<S> {
-    service: S,
+    service: Rc<S>,
 }
 
 impl<S, B> Service<ServiceRequest> for AuthMiddleware<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -49,57 +60,77 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let request = match chekout_valid_cookie(req) {
+        let (request, user, session_id) = match chekout_valid_cookie(req) {
             Ok(authenticated_req) => authenticated_req,
             Err(err) => {
                 return Box::pin(async { Err(err) });
             }
         };
 
-        let fut = self.service.call(request);
+        let service = Rc::clone(&self.service);
 
         Box::pin(async move {
-            let res = fut.await?;
-
-            println!("Hi from response");
-            Ok(res)
+            let db = match request.app_data::<web::Data<DatabaseConnection>>() {
+                Some(db) => db.clone(),
+                None => return Err(ApiError::Internal(codes::DATABASE).into()),
+            };
+
+            match SessionQuery::find_active_session(&db, session_id).await {
+                Ok(Some(_)) => {
+                    request.extensions_mut().insert(user);
+                    service.call(request).await
+                }
+                Ok(None) => Err(ApiError::Unauthorized(codes::SESSION_EXPIRED).into()),
+                Err(_) => Err(ApiError::Internal(codes::DATABASE).into()),
+            }
         })
     }
 }
 
-pub fn chekout_valid_cookie(req: ServiceRequest) -> Result<ServiceRequest, Error> {
+/// Returns the request along with the user and the id of the session backing
+/// the cookie, which still has to be checked against the `sessions` table.
+pub fn chekout_valid_cookie(
+    req: ServiceRequest,
+) -> Result<(ServiceRequest, AuthenticatedUser, Uuid), Error> {
     let cookie = match get_cookie_from_service_request(&req, "SESSIONID") {
         Some(t) => t,
         None => {
-            return Err(error::ErrorGatewayTimeout("Token expired"));
+            return Err(ApiError::Unauthorized(codes::SESSION_MISSING).into());
         }
     };
 
-    let token = serde_json::from_str::<EncryptedPayload>(&cookie.as_str()).unwrap();
+    let token = match serde_json::from_str::<EncryptedPayload>(cookie.as_str()) {
+        Ok(t) => t,
+        Err(_) => return Err(ApiError::Unauthorized(codes::SESSION_EXPIRED).into()),
+    };
 
     let cookie_payload: CookiePayload = match decrypt_payload(&token.order, &token.content) {
         Ok(p) => p,
         Err(_) => {
-            return Err(error::ErrorGatewayTimeout("Token expired"));
+            return Err(ApiError::Unauthorized(codes::SESSION_EXPIRED).into());
         }
     };
 
+    let session_id = match cookie_payload.session_id {
+        Some(id) => id,
+        None => return Err(ApiError::Unauthorized(codes::SESSION_EXPIRED).into()),
+    };
+
     if cookie_payload.exp_at < Utc::now().timestamp() {
-        return Err(error::ErrorGatewayTimeout("Token expired"));
-    } else {
-        req.app_data::<web::Data<AppState>>().map(|app_state| {
-            let mut id_state = app_state.id.lock().unwrap();
-            *id_state = Some(cookie_payload.id);
-        });
-        Ok(req)
+        return Err(ApiError::Unauthorized(codes::SESSION_EXPIRED).into());
     }
+
+    let user = AuthenticatedUser {
+        id: cookie_payload.id,
+    };
+    Ok((req, user, session_id))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Mutex;
-
     use actix_web::{http, test, App, HttpResponse};
+    use entity::entities::session_entity::session_model;
+    use sea_orm::{DatabaseBackend, MockDatabase};
     use uuid::Uuid;
 
     use crate::utils::{cookie_utils::create_cookie, time_utils::MAX_AGE_3M};
@@ -107,21 +138,30 @@ mod tests {
     use super::*;
     #[actix_web::test]
     async fn test_auth_middleware_success() {
-        let app_state = web::Data::new(AppState {
-            id: Mutex::new(None),
-        });
+        let db_data = web::Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[session_model::Model {
+                    id: Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap(),
+                    user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                    ..Default::default()
+                }]])
+                .into_connection(),
+        );
 
         let app = test::init_service(
             App::new()
-                .app_data(app_state.clone())
+                .app_data(db_data)
                 .wrap(Auth)
-                .service(web::resource("/").to(|| HttpResponse::Ok())),
+                .service(web::resource("/").to(|user: AuthenticatedUser| async move {
+                    HttpResponse::Ok().body(user.id.to_string())
+                })),
         )
         .await;
 
         let token = CookiePayload {
             id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             exp_at: Utc::now().timestamp() + MAX_AGE_3M,
+            session_id: Some(Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap()),
         };
 
         let cookie = create_cookie("SESSIONID", &token);
@@ -134,6 +174,45 @@ mod tests {
         let resp = test::call_service(&app, req).await;
 
         assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            test::read_body(resp).await,
+            "00000000-0000-0000-0000-000000000001"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_auth_middleware_revoked_session() {
+        let db_data = web::Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([Vec::<session_model::Model>::new()])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(Auth)
+                .service(web::resource("/").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let token = CookiePayload {
+            id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            exp_at: Utc::now().timestamp() + MAX_AGE_3M,
+            session_id: Some(Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap()),
+        };
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .cookie(create_cookie("SESSIONID", &token))
+            .to_request();
+
+        let resp = test::try_call_service(&app, req).await;
+
+        assert_eq!(
+            resp.err().unwrap().as_response_error().status_code(),
+            http::StatusCode::UNAUTHORIZED
+        );
     }
 
     #[actix_web::test]
@@ -141,6 +220,7 @@ mod tests {
         let token = CookiePayload {
             id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             exp_at: Utc::now().timestamp() + MAX_AGE_3M,
+            session_id: Some(Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap()),
         };
 
         let cookie = create_cookie("SESSIONID", &token);
@@ -159,10 +239,7 @@ mod tests {
         let result = chekout_valid_cookie(req);
 
         assert!(result.is_err());
-        assert_eq!(
-            result.unwrap_err().to_string(),
-            "Cookie not found".to_string()
-        );
+        assert_eq!(result.unwrap_err().to_string(), codes::SESSION_MISSING);
     }
 
     #[actix_web::test]
@@ -170,6 +247,7 @@ mod tests {
         let token = CookiePayload {
             id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
             exp_at: Utc::now().timestamp() - MAX_AGE_3M,
+            session_id: Some(Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap()),
         };
 
         let cookie = create_cookie("SESSIONID", &token);
@@ -179,6 +257,6 @@ mod tests {
         let result = chekout_valid_cookie(req);
 
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Token expired".to_string());
+        assert_eq!(result.unwrap_err().to_string(), codes::SESSION_EXPIRED);
     }
 }
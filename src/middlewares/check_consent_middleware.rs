@@ -0,0 +1,181 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error,
+};
+use futures_util::future::LocalBoxFuture;
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    error::{api_error::ApiError, codes},
+    utils::consent_utils::find_pending_consents,
+};
+
+use super::authenticated_user::AuthenticatedUser;
+
+/// Routes a user must still reach to accept the documents or exercise their
+/// data rights.
+const CONSENT_EXEMPT_PATHS: [&str; 4] =
+    ["/me/consents", "/me/export", "/delete_account", "/logout"];
+
+/// Must be wrapped inside `Auth`, which resolves the user id it checks.
+pub struct RequireConsents;
+
+impl<S, B> Transform<S, ServiceRequest> for RequireConsents
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequireConsentsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireConsentsMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequireConsentsMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireConsentsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            if CONSENT_EXEMPT_PATHS
+                .iter()
+                .any(|path| req.path().ends_with(path))
+            {
+                return service.call(req).await;
+            }
+
+            let db = match req.app_data::<web::Data<DatabaseConnection>>() {
+                Some(db) => db.clone(),
+                None => return Err(ApiError::Internal(codes::DATABASE).into()),
+            };
+            let user_id = match AuthenticatedUser::of(&req) {
+                Some(user) => user.id,
+                None => return Err(ApiError::Forbidden(codes::FORBIDDEN, None).into()),
+            };
+
+            match find_pending_consents(&db, user_id).await {
+                Ok(pending) if pending.is_empty() => service.call(req).await,
+                Ok(pending) => Err(ApiError::Forbidden(
+                    codes::CONSENT_REQUIRED,
+                    serde_json::to_value(pending).ok(),
+                )
+                .into()),
+                Err(_) => Err(ApiError::Internal(codes::DATABASE).into()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http, test, App, HttpResponse};
+    use entity::entities::{
+        consent_entity::consent_model,
+        legal_document_entity::legal_document_model::{self, DocumentKind},
+    };
+    use sea_orm::{DatabaseBackend, MockDatabase};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::middlewares::authenticated_user::test_utils::AuthenticateAs;
+
+    fn authenticated() -> AuthenticateAs {
+        AuthenticateAs(Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap())
+    }
+
+    fn terms() -> legal_document_model::Model {
+        legal_document_model::Model {
+            kind: DocumentKind::Terms,
+            version: String::from("2024-03-01"),
+            ..Default::default()
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_require_consents_success() {
+        let db_data = web::Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[terms()]])
+                .append_query_results([[consent_model::Model {
+                    kind: DocumentKind::Terms,
+                    version: String::from("2024-03-01"),
+                    ..Default::default()
+                }]])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(RequireConsents)
+                .wrap(authenticated())
+                .service(web::resource("/me").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/me").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_require_consents_pending() {
+        let db_data = web::Data::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[terms()]])
+                .append_query_results([Vec::<consent_model::Model>::new()])
+                .into_connection(),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(db_data)
+                .wrap(RequireConsents)
+                .wrap(authenticated())
+                .service(web::resource("/me").to(HttpResponse::Ok))
+                .service(web::resource("/me/consents").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/me").to_request();
+        let resp = test::try_call_service(&app, req).await;
+
+        assert_eq!(
+            resp.err().unwrap().as_response_error().status_code(),
+            http::StatusCode::FORBIDDEN
+        );
+
+        let req = test::TestRequest::get().uri("/me/consents").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+    }
+}
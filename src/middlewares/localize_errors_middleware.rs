@@ -0,0 +1,115 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+
+use crate::{error::api_error::ApiError, i18n::language::language_from_request};
+
+/// Adds the `messages` of the form errors returned by the handlers, in the
+/// language of the `Accept-Language` header.
+pub struct LocalizeErrors;
+
+impl<S, B> Transform<S, ServiceRequest> for LocalizeErrors
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = LocalizeErrorsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(LocalizeErrorsMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct LocalizeErrorsMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for LocalizeErrorsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let response = self.service.call(req);
+
+        Box::pin(async move {
+            let response = response.await?;
+            let localized = response
+                .response()
+                .error()
+                .and_then(|err| err.as_error::<ApiError>())
+                .filter(|err| err.errors().is_some())
+                .map(|err| err.localized_response(&language_from_request(response.request())));
+
+            match localized {
+                Some(localized) => Ok(response.into_response(localized.map_into_right_body())),
+                None => Ok(response.map_into_left_body()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        http::{self, header},
+        test, web, App, HttpResponse,
+    };
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::error::codes;
+
+    async fn invalid_form() -> Result<HttpResponse, ApiError> {
+        Err(ApiError::invalid(
+            codes::FORM_INVALID,
+            json!({ "firstName": "not_a_first_name" }),
+        ))
+    }
+
+    #[actix_web::test]
+    async fn test_localize_errors_from_accept_language() {
+        let app = test::init_service(
+            App::new()
+                .wrap(LocalizeErrors)
+                .route("/", web::post().to(invalid_form)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/")
+            .insert_header((header::ACCEPT_LANGUAGE, "en-GB,en;q=0.9"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body: Value = test::read_body_json(resp).await;
+
+        assert_eq!(body["code"], codes::FORM_INVALID);
+        assert_eq!(body["errors"]["firstName"], "not_a_first_name");
+        assert_eq!(body["messages"]["firstName"], "This is not a first name");
+    }
+}
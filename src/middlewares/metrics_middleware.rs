@@ -0,0 +1,110 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+    time::Instant,
+};
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+
+use crate::utils::metrics_utils::{observe_http_request, UNMATCHED_ROUTE};
+
+/// Counts requests and their latency by route pattern, so `/avatars/{user_id}/..`
+/// stays a single series whatever the ids.
+pub struct RecordMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for RecordMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RecordMetricsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RecordMetricsMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RecordMetricsMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RecordMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let started_at = Instant::now();
+        let method = req.method().to_string();
+        // Read before calling the service, errors of inner middlewares come without the request.
+        let route = req
+            .match_pattern()
+            .unwrap_or_else(|| String::from(UNMATCHED_ROUTE));
+        let response = self.service.call(req);
+
+        Box::pin(async move {
+            let response = response.await;
+            let status = match &response {
+                Ok(response) => response.status(),
+                Err(err) => err.as_response_error().status_code(),
+            };
+            observe_http_request(
+                &method,
+                &route,
+                status.as_u16(),
+                started_at.elapsed().as_secs_f64(),
+            );
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http, test, web, App, HttpResponse};
+
+    use crate::utils::metrics_utils::encode_metrics;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_record_metrics_by_route_pattern() {
+        let app = test::init_service(
+            App::new()
+                .wrap(RecordMetrics)
+                .service(web::resource("/metrics_test/{id}").to(HttpResponse::Created)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/metrics_test/42")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::CREATED);
+
+        let body = String::from_utf8(encode_metrics().1).unwrap();
+
+        assert!(body.contains(
+            r#"http_requests_total{method="GET",route="/metrics_test/{id}",status="201"} 1"#
+        ));
+    }
+}
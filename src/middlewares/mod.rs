@@ -1,3 +1,8 @@
 pub mod rate_limit_middleware;
 pub mod check_auth_middleware;
-pub mod app_state;
\ No newline at end of file
+pub mod authenticated_user;
+pub mod check_admin_middleware;
+pub mod check_consent_middleware;
+pub mod request_id_middleware;
+pub mod metrics_middleware;
+pub mod localize_errors_middleware;
\ No newline at end of file
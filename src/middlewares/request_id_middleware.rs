@@ -0,0 +1,222 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    http::header::{HeaderName, HeaderValue},
+    Error, HttpMessage,
+};
+use futures_util::future::LocalBoxFuture;
+use tracing::{info_span, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use uuid::Uuid;
+
+use crate::utils::telemetry_utils::parent_context;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+const REQUEST_ID_MAX_LEN: usize = 128;
+
+/// Id of the current request, available in the request extensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// Keeps the `X-Request-Id` sent by the client or generates one, runs the rest
+/// of the request inside a `request` span carrying it and echoes it back.
+/// The span continues the trace of the W3C `traceparent` header if any.
+/// Must be the outermost middleware so errors of the inner ones get the header.
+pub struct TraceRequest;
+
+impl<S, B> Transform<S, ServiceRequest> for TraceRequest
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = TraceRequestMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(TraceRequestMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct TraceRequestMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for TraceRequestMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| is_valid_request_id(value))
+            .map(String::from)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let header_value = HeaderValue::from_str(&request_id).unwrap();
+
+        req.extensions_mut()
+            .insert(RequestId(request_id.to_owned()));
+
+        let span = info_span!(
+            "request",
+            request_id = %request_id,
+            method = %req.method(),
+            path = %req.path(),
+            otel.kind = "server",
+        );
+        span.set_parent(parent_context(req.headers()));
+        let response = span.in_scope(|| self.service.call(req));
+
+        Box::pin(
+            async move {
+                match response.await {
+                    Ok(mut response) => {
+                        response
+                            .headers_mut()
+                            .insert(HeaderName::from_static(REQUEST_ID_HEADER), header_value);
+                        Ok(response)
+                    }
+                    Err(err) => {
+                        let mut error_response = err.error_response();
+                        error_response
+                            .headers_mut()
+                            .insert(HeaderName::from_static(REQUEST_ID_HEADER), header_value);
+                        Err(InternalError::from_response(err, error_response).into())
+                    }
+                }
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// Client ids are kept only when they are short and printable, anything else
+/// could be used to forge log lines.
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= REQUEST_ID_MAX_LEN
+        && value.bytes().all(|byte| byte.is_ascii_graphic())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http, test, web, App, HttpRequest, HttpResponse};
+
+    use crate::middlewares::check_auth_middleware::Auth;
+
+    use super::*;
+
+    async fn echo_request_id(req: HttpRequest) -> HttpResponse {
+        let request_id = req.extensions().get::<RequestId>().unwrap().0.to_owned();
+        HttpResponse::Ok().body(request_id)
+    }
+
+    #[actix_web::test]
+    async fn test_trace_request_generates_id() {
+        let app = test::init_service(
+            App::new()
+                .wrap(TraceRequest)
+                .service(web::resource("/").to(echo_request_id)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let header = resp.headers().get(REQUEST_ID_HEADER).unwrap().to_owned();
+        let body = test::read_body(resp).await;
+
+        assert!(Uuid::parse_str(header.to_str().unwrap()).is_ok());
+        assert_eq!(body, header.as_bytes());
+    }
+
+    #[actix_web::test]
+    async fn test_trace_request_keeps_client_id() {
+        let app = test::init_service(
+            App::new()
+                .wrap(TraceRequest)
+                .service(web::resource("/").to(echo_request_id)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header((REQUEST_ID_HEADER, "client-request-1"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(
+            resp.headers().get(REQUEST_ID_HEADER).unwrap(),
+            "client-request-1"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_trace_request_replaces_invalid_id() {
+        let app = test::init_service(
+            App::new()
+                .wrap(TraceRequest)
+                .service(web::resource("/").to(echo_request_id)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header((REQUEST_ID_HEADER, "a".repeat(REQUEST_ID_MAX_LEN + 1)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        let header = resp.headers().get(REQUEST_ID_HEADER).unwrap();
+
+        assert!(Uuid::parse_str(header.to_str().unwrap()).is_ok());
+    }
+
+    #[actix_web::test]
+    async fn test_trace_request_id_on_middleware_error() {
+        let app = test::init_service(
+            App::new()
+                .wrap(Auth)
+                .wrap(TraceRequest)
+                .service(web::resource("/").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header((REQUEST_ID_HEADER, "client-request-2"))
+            .to_request();
+        let resp = test::try_call_service(&app, req)
+            .await
+            .err()
+            .unwrap()
+            .error_response();
+
+        assert_eq!(resp.status(), http::StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            resp.headers().get(REQUEST_ID_HEADER).unwrap(),
+            "client-request-2"
+        );
+    }
+}
@@ -1,26 +1,33 @@
 use dotenv::dotenv;
+use entity::entities::user_entity::user_model::Language;
+use fluent_bundle::FluentArgs;
 use std::env;
-use tracing::error;
+use tracing::{error, Instrument};
 
 use reqwest::header;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use crate::{
+    i18n::catalog::translate,
+    utils::{
+        metrics_utils::{observe_delivery, BREVO_PROVIDER, SMS_CHANNEL},
+        phone_utils::is_test_phone,
+        telemetry_utils::brevo_span,
+    },
+};
+
 #[derive(Serialize, Deserialize)]
 pub struct AuthCodeSmsData {
     pub phone: String,
     pub first_name: String,
     pub code: String,
+    pub lg: Language,
 }
 
 pub async fn send_auth_code_sms(data: AuthCodeSmsData) -> Result<(), ()> {
-    if data.phone == "0600000001"
-        || data.phone == "0600000002"
-        || data.phone == "0600000003"
-        || data.phone == "0600000004"
-        || data.phone == "0600000005"
-    {
+    if is_test_phone(&data.phone) {
         return Ok(());
     }
 
@@ -32,13 +39,14 @@ pub async fn send_auth_code_sms(data: AuthCodeSmsData) -> Result<(), ()> {
     dotenv().ok();
     let api_key = env::var("SMS_API_KEY_SENDINBLUE").expect("Failed to get SMS_API_KEY_SENDINBLUE");
 
-    let (_, last) = data.phone.split_at(1);
-    let user_phone = String::from("+33") + &last;
+    let mut args = FluentArgs::new();
+    args.set("firstName", data.first_name);
+    args.set("code", data.code);
 
     let body = json!({
         "sender": "FOCUS",
-        "recipient": user_phone,
-        "content": format!("Bonjour {},\nVotre code de vérification Focus est: {}.", data.first_name, data.code) ,
+        "recipient": data.phone,
+        "content": translate(&data.lg, "sms-auth-code", Some(&args)),
     });
 
     let res = client
@@ -49,20 +57,23 @@ pub async fn send_auth_code_sms(data: AuthCodeSmsData) -> Result<(), ()> {
         .header("api-key", api_key)
         .json(&body)
         .send()
+        .instrument(brevo_span("/v3/transactionalSMS/sms"))
         .await;
 
-    match res {
+    let sent = match res {
         Ok(response) => {
             if response.status() == 201 {
-                return Ok(());
+                Ok(())
             } else {
                 error!("Failed to send sms: {:?}", response.text().await.unwrap());
-                return Err(());
+                Err(())
             }
         }
         Err(err) => {
             error!("Failed to send sms: {:?}", err);
-            return Err(());
+            Err(())
         }
-    }
+    };
+    observe_delivery(SMS_CHANNEL, BREVO_PROVIDER, sent.is_ok());
+    sent
 }
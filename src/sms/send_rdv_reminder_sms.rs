@@ -1,12 +1,23 @@
 use dotenv::dotenv;
+use entity::entities::user_entity::user_model::Language;
+use fluent_bundle::FluentArgs;
 use std::env;
-use tracing::error;
+use tracing::{error, Instrument};
 
 use reqwest::header;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use crate::{
+    i18n::catalog::translate,
+    utils::{
+        metrics_utils::{observe_delivery, BREVO_PROVIDER, SMS_CHANNEL},
+        phone_utils::is_test_phone,
+        telemetry_utils::brevo_span,
+    },
+};
+
 #[derive(Serialize, Deserialize)]
 pub struct RdvReminderSmsData {
     pub date: String,
@@ -14,15 +25,11 @@ pub struct RdvReminderSmsData {
     pub pro_full_name: String,
     pub user_phone: String,
     pub shorten_url: String,
+    pub lg: Language,
 }
 
 pub async fn send_rdv_reminder_sms(data: RdvReminderSmsData) -> () {
-    if data.user_phone == "0600000001"
-        || data.user_phone == "0600000002"
-        || data.user_phone == "0600000003"
-        || data.user_phone == "0600000004"
-        || data.user_phone == "0600000005"
-    {
+    if is_test_phone(&data.user_phone) {
         return ();
     }
 
@@ -34,13 +41,16 @@ pub async fn send_rdv_reminder_sms(data: RdvReminderSmsData) -> () {
     dotenv().ok();
     let api_key = env::var("SMS_API_KEY_SENDINBLUE").expect("Failed to get SMS_API_KEY_SENDINBLUE");
 
-    let (_, last) = data.user_phone.split_at(1);
-    let user_phone = String::from("+33") + &last;
+    let mut args = FluentArgs::new();
+    args.set("date", data.date);
+    args.set("timeslot", data.timeslot);
+    args.set("proFullName", data.pro_full_name);
+    args.set("url", data.shorten_url);
 
     let body = json!({
         "sender": "FOCUS",
-        "recipient": user_phone,
-        "content": format!("Bonjour\nRDV {} à {}\n{}\nInfos et annulation: {}.", data.date, data.timeslot, data.pro_full_name, data.shorten_url) ,
+        "recipient": data.user_phone,
+        "content": translate(&data.lg, "sms-rdv-reminder", Some(&args)),
     });
 
     let res = client
@@ -51,16 +61,22 @@ pub async fn send_rdv_reminder_sms(data: RdvReminderSmsData) -> () {
         .header("api-key", api_key)
         .json(&body)
         .send()
+        .instrument(brevo_span("/v3/transactionalSMS/sms"))
         .await;
 
-    match res {
+    let sent = match res {
         Ok(response) => {
             if response.status() != 201 {
                 error!("Failed to send sms: {:?}", response.text().await.unwrap());
+                false
+            } else {
+                true
             }
         }
         Err(err) => {
             error!("Failed to send sms: {:?}", err);
+            false
         }
-    }
+    };
+    observe_delivery(SMS_CHANNEL, BREVO_PROVIDER, sent);
 }
@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use dotenv::dotenv;
+use std::{env, fmt, sync::Arc};
+use tracing::error;
+
+use super::{local_blob_store::LocalBlobStore, s3_blob_store::S3BlobStore};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blob {
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+}
+
+#[derive(Debug)]
+pub struct BlobStoreError(pub String);
+
+impl fmt::Display for BlobStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(
+        &self,
+        key: &str,
+        content_type: &str,
+        bytes: Vec<u8>,
+    ) -> Result<(), BlobStoreError>;
+    async fn get(&self, key: &str) -> Result<Option<Blob>, BlobStoreError>;
+    async fn delete(&self, key: &str) -> Result<(), BlobStoreError>;
+}
+
+pub fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with('/')
+        && key
+            .split('/')
+            .all(|part| !part.is_empty() && part != "." && part != "..")
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+}
+
+pub fn init_blob_store() -> Arc<dyn BlobStore> {
+    dotenv().ok();
+    let backend = env::var("BLOB_STORE").unwrap_or_else(|_| String::from("local"));
+
+    match backend.as_str() {
+        "local" => {
+            let root = env::var("BLOB_STORE_PATH").unwrap_or_else(|_| String::from("./storage"));
+            Arc::new(LocalBlobStore::new(root))
+        }
+        "s3" => {
+            let var = |key: &str| {
+                env::var(key).unwrap_or_else(|_| {
+                    error!("Error loading env variable {}", key);
+                    std::process::exit(1);
+                })
+            };
+            Arc::new(S3BlobStore::new(
+                var("S3_ENDPOINT"),
+                var("S3_BUCKET"),
+                var("S3_REGION"),
+                var("S3_ACCESS_KEY"),
+                var("S3_SECRET_KEY"),
+            ))
+        }
+        other => {
+            error!("Unknown BLOB_STORE backend: {}", other);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_validates_keys() {
+        assert!(is_valid_key(
+            "avatars/00000000-0000-0000-0000-000000000001/V1StGXR8_Z/64.jpg"
+        ));
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("/etc/passwd"));
+        assert!(!is_valid_key("avatars/../secret"));
+        assert!(!is_valid_key("avatars//64.jpg"));
+        assert!(!is_valid_key("avatars/a b.jpg"));
+    }
+}
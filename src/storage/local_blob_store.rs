@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use std::{io::ErrorKind, path::PathBuf};
+use tokio::fs;
+
+use super::blob_store::{is_valid_key, Blob, BlobStore, BlobStoreError};
+
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalBlobStore { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, BlobStoreError> {
+        if !is_valid_key(key) {
+            return Err(BlobStoreError(format!("invalid key: {}", key)));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(
+        &self,
+        key: &str,
+        _content_type: &str,
+        bytes: Vec<u8>,
+    ) -> Result<(), BlobStoreError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|err| BlobStoreError(err.to_string()))?;
+        }
+        fs::write(&path, bytes)
+            .await
+            .map_err(|err| BlobStoreError(err.to_string()))
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Blob>, BlobStoreError> {
+        let path = self.path(key)?;
+        match fs::read(&path).await {
+            Ok(bytes) => Ok(Some(Blob {
+                bytes,
+                content_type: String::from(content_type_from_key(key)),
+            })),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(BlobStoreError(err.to_string())),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BlobStoreError> {
+        let path = self.path(key)?;
+        match fs::remove_file(&path).await {
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(BlobStoreError(err.to_string())),
+        }
+    }
+}
+
+fn content_type_from_key(key: &str) -> &'static str {
+    match key.rsplit('.').next() {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nanoid::nanoid;
+
+    #[actix_web::test]
+    async fn it_puts_gets_and_deletes_blobs() {
+        let root = std::env::temp_dir().join(format!("blob-store-{}", nanoid!()));
+        let store = LocalBlobStore::new(&root);
+
+        store
+            .put("avatars/1/64.jpg", "image/jpeg", vec![1, 2, 3])
+            .await
+            .unwrap();
+
+        let blob = store.get("avatars/1/64.jpg").await.unwrap().unwrap();
+        assert_eq!(blob.bytes, vec![1, 2, 3]);
+        assert_eq!(blob.content_type, "image/jpeg");
+
+        store.delete("avatars/1/64.jpg").await.unwrap();
+        assert_eq!(store.get("avatars/1/64.jpg").await.unwrap(), None);
+        assert!(store.delete("avatars/1/64.jpg").await.is_ok());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[actix_web::test]
+    async fn it_rejects_keys_outside_the_root() {
+        let store = LocalBlobStore::new(std::env::temp_dir());
+
+        assert!(store.get("../etc/passwd").await.is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod blob_store;
+pub mod local_blob_store;
+pub mod s3_blob_store;
\ No newline at end of file
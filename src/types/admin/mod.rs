@@ -0,0 +1,4 @@
+pub mod user_list_data;
+
+pub mod user_import_data;
+pub mod user_export_data;
\ No newline at end of file
@@ -0,0 +1,123 @@
+use chrono::SecondsFormat;
+use serde::Deserialize;
+
+use super::user_list_data::AdminUserResponse;
+use crate::{
+    error::{api_error::ApiError, codes},
+    utils::csv_utils::write_csv_record,
+};
+
+pub const USERS_CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+
+/// Columns of the export, named like the fields of the listing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserColumn {
+    Id,
+    FirstName,
+    LastName,
+    Email,
+    Phone,
+    Language,
+    Role,
+    TwoFa,
+    EmailVerified,
+    PhoneVerified,
+    CreatedAt,
+}
+
+impl UserColumn {
+    pub const ALL: [UserColumn; 11] = [
+        UserColumn::Id,
+        UserColumn::FirstName,
+        UserColumn::LastName,
+        UserColumn::Email,
+        UserColumn::Phone,
+        UserColumn::Language,
+        UserColumn::Role,
+        UserColumn::TwoFa,
+        UserColumn::EmailVerified,
+        UserColumn::PhoneVerified,
+        UserColumn::CreatedAt,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserColumn::Id => "id",
+            UserColumn::FirstName => "firstName",
+            UserColumn::LastName => "lastName",
+            UserColumn::Email => "email",
+            UserColumn::Phone => "phone",
+            UserColumn::Language => "language",
+            UserColumn::Role => "role",
+            UserColumn::TwoFa => "twoFa",
+            UserColumn::EmailVerified => "emailVerified",
+            UserColumn::PhoneVerified => "phoneVerified",
+            UserColumn::CreatedAt => "createdAt",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        UserColumn::ALL
+            .into_iter()
+            .find(|column| column.name() == name)
+    }
+
+    fn value(&self, user: &AdminUserResponse) -> String {
+        match self {
+            UserColumn::Id => user.id.to_string(),
+            UserColumn::FirstName => user.first_name.to_owned(),
+            UserColumn::LastName => user.last_name.to_owned(),
+            UserColumn::Email => user.email.to_owned(),
+            UserColumn::Phone => user.phone.to_owned(),
+            UserColumn::Language => user.language.to_owned(),
+            UserColumn::Role => user.role.to_owned(),
+            UserColumn::TwoFa => user.two_fa.to_string(),
+            UserColumn::EmailVerified => user.email_verified.to_string(),
+            UserColumn::PhoneVerified => user.phone_verified.to_string(),
+            UserColumn::CreatedAt => user.created_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+}
+
+/// Read next to `UsersRequest`, whose filters and sort the export applies.
+#[derive(Deserialize)]
+pub struct UsersExportRequest {
+    /// Comma separated column names, every column when unset.
+    pub columns: Option<String>,
+}
+
+impl UsersExportRequest {
+    pub fn columns(&self) -> Result<Vec<UserColumn>, ApiError> {
+        let Some(columns) = &self.columns else {
+            return Ok(UserColumn::ALL.to_vec());
+        };
+
+        let columns = columns
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                UserColumn::from_name(name).ok_or(ApiError::BadRequest(codes::MALFORMED_REQUEST))
+            })
+            .collect::<Result<Vec<UserColumn>, ApiError>>()?;
+
+        match columns.is_empty() {
+            true => Err(ApiError::BadRequest(codes::MALFORMED_REQUEST)),
+            false => Ok(columns),
+        }
+    }
+}
+
+/// Header row then one row per user.
+pub fn users_csv(users: &[AdminUserResponse], columns: &[UserColumn]) -> String {
+    let mut csv = String::new();
+    let header: Vec<&str> = columns.iter().map(UserColumn::name).collect();
+    write_csv_record(&mut csv, &header);
+
+    for user in users {
+        let row: Vec<String> = columns.iter().map(|column| column.value(user)).collect();
+        write_csv_record(&mut csv, &row);
+    }
+
+    csv
+}
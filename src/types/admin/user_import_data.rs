@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{
+        api_error::ApiError,
+        codes,
+        errors::signup_data::{
+            signup_data_check::SignUpDataCheck, signup_data_errors::SignUpDataErrors,
+        },
+    },
+    utils::{csv_utils::parse_csv, email_utils::normalize_email, phone_utils::DEFAULT_COUNTRY},
+};
+
+pub const IMPORT_MAX_ROWS: usize = 1000;
+
+/// Header of the import file, in any order. `country` may be left out, the
+/// phone numbers are then read as French ones.
+pub const IMPORT_COLUMNS: [&str; 7] = [
+    "firstName",
+    "lastName",
+    "email",
+    "phone",
+    "siren",
+    "terms",
+    "privacy",
+];
+const COUNTRY_COLUMN: &str = "country";
+
+#[derive(Deserialize)]
+pub struct ImportRequest {
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+}
+
+/// A record of the file, checked like the signup form.
+pub struct ImportRow {
+    pub line: usize,
+    pub check: SignUpDataCheck,
+}
+
+/// Rows of `csv` after its header. Broken quoting, a missing column or a
+/// record without one field per column reject the whole file.
+pub fn import_rows(csv: &str) -> Result<Vec<ImportRow>, ApiError> {
+    let records = parse_csv(csv).map_err(|_| ApiError::BadRequest(codes::CSV_INVALID))?;
+    let mut records = records.into_iter();
+    let header = match records.next() {
+        Some((_, header)) => header,
+        None => return Err(ApiError::BadRequest(codes::CSV_INVALID)),
+    };
+
+    let position = |name: &str| header.iter().position(|column| column.trim() == name);
+    let mut positions = [0; IMPORT_COLUMNS.len()];
+    for (i, name) in IMPORT_COLUMNS.iter().enumerate() {
+        positions[i] = position(name).ok_or(ApiError::BadRequest(codes::CSV_INVALID))?;
+    }
+    let [first_name, last_name, email, phone, siren, terms, privacy] = positions;
+    let country = position(COUNTRY_COLUMN);
+
+    if records.len() > IMPORT_MAX_ROWS {
+        return Err(ApiError::PayloadTooLarge(codes::IMPORT_TOO_LARGE));
+    }
+
+    records
+        .map(|(line, record)| {
+            if record.len() != header.len() {
+                return Err(ApiError::BadRequest(codes::CSV_INVALID));
+            }
+            let field = |i: usize| record[i].trim().to_owned();
+
+            Ok(ImportRow {
+                line,
+                check: SignUpDataCheck {
+                    firstName: field(first_name),
+                    lastName: field(last_name),
+                    email: normalize_email(&record[email]),
+                    phone: field(phone),
+                    country: country
+                        .map(field)
+                        .filter(|country| !country.is_empty())
+                        .unwrap_or_else(|| String::from(DEFAULT_COUNTRY)),
+                    siren: field(siren),
+                    terms: accepted(&record[terms]),
+                    privacy: accepted(&record[privacy]),
+                },
+            })
+        })
+        .collect()
+}
+
+fn accepted(value: &str) -> bool {
+    matches!(
+        value.trim().to_lowercase().as_str(),
+        "true" | "1" | "yes" | "x"
+    )
+}
+
+/// The codes are the ones of the signup form, `already_exists` included.
+#[derive(Serialize)]
+pub struct ImportRowError {
+    pub line: usize,
+    pub errors: SignUpDataErrors,
+}
+
+#[derive(Serialize)]
+pub struct ImportResponse {
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    pub rows: usize,
+    pub valid: usize,
+    /// Always 0 on a dry run.
+    pub imported: u64,
+    pub errors: Vec<ImportRowError>,
+}
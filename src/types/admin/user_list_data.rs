@@ -0,0 +1,249 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use entity::entities::{
+    two_fa_entity::two_fa_model,
+    user_entity::user_model::{self, Role},
+};
+use sea_orm::Order;
+use serde::{Deserialize, Serialize};
+use service::query::user_queries::{UserCursor, UserFilter, UserPage, UserSort};
+use uuid::Uuid;
+
+use crate::{
+    error::{api_error::ApiError, codes},
+    i18n::language::{language_code, language_from_code},
+};
+
+pub const USERS_DEFAULT_LIMIT: u64 = 50;
+pub const USERS_MAX_LIMIT: u64 = 200;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UserSortParam {
+    CreatedAt,
+    LastName,
+    Email,
+}
+
+impl From<UserSortParam> for UserSort {
+    fn from(sort: UserSortParam) -> Self {
+        match sort {
+            UserSortParam::CreatedAt => UserSort::CreatedAt,
+            UserSortParam::LastName => UserSort::LastName,
+            UserSortParam::Email => UserSort::Email,
+        }
+    }
+}
+
+impl From<UserSort> for UserSortParam {
+    fn from(sort: UserSort) -> Self {
+        match sort {
+            UserSort::CreatedAt => UserSortParam::CreatedAt,
+            UserSort::LastName => UserSortParam::LastName,
+            UserSort::Email => UserSortParam::Email,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RoleParam {
+    Pro,
+    Admin,
+}
+
+pub fn role_code(role: &Role) -> &'static str {
+    match role {
+        Role::Pro => "pro",
+        Role::Admin => "admin",
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct UsersRequest {
+    pub cursor: Option<String>,
+    pub limit: Option<u64>,
+    pub search: Option<String>,
+    pub language: Option<String>,
+    pub role: Option<RoleParam>,
+    #[serde(rename = "createdFrom")]
+    pub created_from: Option<DateTime<Utc>>,
+    #[serde(rename = "createdTo")]
+    pub created_to: Option<DateTime<Utc>>,
+    #[serde(rename = "twoFa")]
+    pub two_fa: Option<bool>,
+    #[serde(rename = "emailVerified")]
+    pub email_verified: Option<bool>,
+    #[serde(rename = "phoneVerified")]
+    pub phone_verified: Option<bool>,
+    pub sort: Option<UserSortParam>,
+    pub order: Option<SortOrder>,
+}
+
+impl UsersRequest {
+    pub fn limit(&self) -> u64 {
+        self.limit
+            .unwrap_or(USERS_DEFAULT_LIMIT)
+            .clamp(1, USERS_MAX_LIMIT)
+    }
+
+    pub fn sort(&self) -> UserSort {
+        self.sort.map(UserSort::from).unwrap_or_default()
+    }
+
+    /// Newest accounts first by default, names and emails alphabetically.
+    pub fn order(&self) -> Order {
+        match (self.order, self.sort()) {
+            (Some(SortOrder::Asc), _) => Order::Asc,
+            (Some(SortOrder::Desc), _) => Order::Desc,
+            (None, UserSort::CreatedAt) => Order::Desc,
+            (None, _) => Order::Asc,
+        }
+    }
+
+    pub fn filter(&self) -> Result<UserFilter, ApiError> {
+        let language = match &self.language {
+            Some(code) => Some(
+                language_from_code(code).ok_or(ApiError::BadRequest(codes::MALFORMED_REQUEST))?,
+            ),
+            None => None,
+        };
+
+        Ok(UserFilter {
+            search: self.search.to_owned(),
+            language,
+            role: self.role.map(|role| match role {
+                RoleParam::Pro => Role::Pro,
+                RoleParam::Admin => Role::Admin,
+            }),
+            created_from: self.created_from,
+            created_to: self.created_to,
+            two_fa: self.two_fa,
+            email_verified: self.email_verified,
+            phone_verified: self.phone_verified,
+        })
+    }
+
+    /// The cursor only continues a listing sorted the same way.
+    pub fn cursor(&self) -> Result<Option<UserCursor>, ApiError> {
+        let Some(token) = &self.cursor else {
+            return Ok(None);
+        };
+
+        match decode_cursor(token) {
+            Some(cursor) if cursor.sort() == self.sort() => Ok(Some(cursor)),
+            _ => Err(ApiError::BadRequest(codes::CURSOR_INVALID)),
+        }
+    }
+}
+
+/// Content of the opaque `cursor` of the listing, base64url encoded JSON.
+#[derive(Serialize, Deserialize)]
+struct CursorToken {
+    sort: UserSortParam,
+    value: String,
+    id: Uuid,
+}
+
+pub fn encode_cursor(cursor: &UserCursor) -> String {
+    let (value, id) = match cursor {
+        UserCursor::CreatedAt(created_at, id) => {
+            (created_at.to_rfc3339_opts(SecondsFormat::AutoSi, true), id)
+        }
+        UserCursor::LastName(value, id) | UserCursor::Email(value, id) => (value.to_owned(), id),
+    };
+    let token = CursorToken {
+        sort: cursor.sort().into(),
+        value,
+        id: *id,
+    };
+
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(&token).unwrap_or_default())
+}
+
+pub fn decode_cursor(cursor: &str) -> Option<UserCursor> {
+    let bytes = URL_SAFE_NO_PAD.decode(cursor.trim()).ok()?;
+    let token: CursorToken = serde_json::from_slice(&bytes).ok()?;
+
+    Some(match token.sort {
+        UserSortParam::CreatedAt => UserCursor::CreatedAt(
+            DateTime::parse_from_rfc3339(&token.value)
+                .ok()?
+                .with_timezone(&Utc),
+            token.id,
+        ),
+        UserSortParam::LastName => UserCursor::LastName(token.value, token.id),
+        UserSortParam::Email => UserCursor::Email(token.value, token.id),
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AdminUserResponse {
+    pub id: Uuid,
+    #[serde(rename = "firstName")]
+    pub first_name: String,
+    #[serde(rename = "lastName")]
+    pub last_name: String,
+    pub email: String,
+    pub phone: String,
+    pub language: String,
+    pub role: String,
+    pub avatar: Option<String>,
+    #[serde(rename = "twoFa")]
+    pub two_fa: bool,
+    #[serde(rename = "emailVerified")]
+    pub email_verified: bool,
+    #[serde(rename = "phoneVerified")]
+    pub phone_verified: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl AdminUserResponse {
+    /// A user without a 2FA row counts as unverified.
+    pub fn new(user: &user_model::Model, two_fa: Option<&two_fa_model::Model>) -> Self {
+        AdminUserResponse {
+            id: user.id,
+            first_name: user.first_name.to_owned(),
+            last_name: user.last_name.to_owned(),
+            email: user.email.to_owned(),
+            phone: user.phone.to_owned(),
+            language: String::from(language_code(&user.language)),
+            role: String::from(role_code(&user.role)),
+            avatar: user.avatar.to_owned(),
+            two_fa: user.two_fa,
+            email_verified: two_fa.is_some_and(|two_fa| two_fa.email_verified),
+            phone_verified: two_fa.is_some_and(|two_fa| two_fa.phone_verified),
+            created_at: user.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UsersResponse {
+    pub users: Vec<AdminUserResponse>,
+    pub total: u64,
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<String>,
+}
+
+impl UsersResponse {
+    pub fn new(page: &UserPage) -> Self {
+        UsersResponse {
+            users: page
+                .users
+                .iter()
+                .map(|(user, two_fa)| AdminUserResponse::new(user, two_fa.as_ref()))
+                .collect(),
+            total: page.total,
+            next_cursor: page.next.as_ref().map(encode_cursor),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use entity::entities::auth_event_entity::auth_event_model::{
+    self, AuthEventKind, AuthEventOutcome,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const AUTH_EVENTS_DEFAULT_LIMIT: u64 = 100;
+pub const AUTH_EVENTS_MAX_LIMIT: u64 = 1000;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct AuthEventsRequest {
+    #[serde(rename = "userId")]
+    pub user_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<u64>,
+}
+
+impl AuthEventsRequest {
+    pub fn limit(&self) -> u64 {
+        self.limit
+            .unwrap_or(AUTH_EVENTS_DEFAULT_LIMIT)
+            .min(AUTH_EVENTS_MAX_LIMIT)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AuthEventResponse {
+    pub id: i64,
+    pub kind: AuthEventKind,
+    pub outcome: AuthEventOutcome,
+    pub ip: Option<String>,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "userId")]
+    pub user_id: Option<Uuid>,
+}
+
+impl AuthEventResponse {
+    pub fn new(event: &auth_event_model::Model) -> Self {
+        AuthEventResponse {
+            id: event.id,
+            kind: event.kind,
+            outcome: event.outcome,
+            ip: event.ip.to_owned(),
+            user_agent: event.user_agent.to_owned(),
+            created_at: event.created_at,
+            user_id: event.user_id,
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod auth_event_data;
\ No newline at end of file
@@ -1,2 +1,3 @@
 pub mod check_email;
-pub mod check_code;
\ No newline at end of file
+pub mod check_code;
+pub mod new_device;
\ No newline at end of file
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Carried by the "this wasn't me" link of the new device email.
+#[derive(Serialize, Deserialize)]
+pub struct NewDevicePayload {
+    pub user_id: Uuid,
+    pub session_id: Uuid,
+    /// `report_nonce` of the new device.
+    pub nonce: String,
+}
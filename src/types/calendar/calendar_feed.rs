@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize)]
+pub struct CalendarFeedPayload {
+    pub id: Uuid,
+    pub nonce: String,
+}
@@ -0,0 +1 @@
+pub mod calendar_feed;
\ No newline at end of file
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use entity::entities::{
+    consent_entity::consent_model,
+    legal_document_entity::legal_document_model::{self, DocumentKind},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ConsentDocument {
+    pub kind: DocumentKind,
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConsentAcceptRequest {
+    pub documents: Vec<ConsentDocument>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct PendingConsent {
+    pub kind: DocumentKind,
+    pub version: String,
+    pub url: String,
+}
+
+impl PendingConsent {
+    pub fn new(document: &legal_document_model::Model) -> Self {
+        PendingConsent {
+            kind: document.kind,
+            version: document.version.to_owned(),
+            url: document.url.to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConsentResponse {
+    pub kind: DocumentKind,
+    pub version: String,
+    #[serde(rename = "acceptedAt")]
+    pub accepted_at: DateTime<Utc>,
+}
+
+impl ConsentResponse {
+    pub fn new(consent: &consent_model::Model) -> Self {
+        ConsentResponse {
+            kind: consent.kind,
+            version: consent.version.to_owned(),
+            accepted_at: consent.accepted_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConsentStatusResponse {
+    #[serde(rename = "mustReaccept")]
+    pub must_reaccept: bool,
+    pub pending: Vec<PendingConsent>,
+    pub consents: Vec<ConsentResponse>,
+}
@@ -0,0 +1 @@
+pub mod consent_data;
\ No newline at end of file
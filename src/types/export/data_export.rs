@@ -0,0 +1,242 @@
+use chrono::{DateTime, Utc};
+use entity::entities::{
+    auth_event_entity::auth_event_model::{self, AuthEventKind, AuthEventOutcome},
+    calendar_token_entity::calendar_token_model,
+    consent_entity::consent_model,
+    known_device_entity::known_device_model,
+    legal_document_entity::legal_document_model::DocumentKind,
+    rdv_entity::rdv_model,
+    session_entity::session_model,
+    two_fa_entity::two_fa_model,
+    user_entity::user_model,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::i18n::language::language_code;
+
+#[derive(Serialize, Deserialize)]
+pub struct DataExportPayload {
+    pub id: Uuid,
+    pub key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserExport {
+    pub id: Uuid,
+    #[serde(rename = "firstName")]
+    pub first_name: String,
+    #[serde(rename = "lastName")]
+    pub last_name: String,
+    pub email: String,
+    pub phone: String,
+    pub avatar: Option<String>,
+    #[serde(rename = "termsAccepted")]
+    pub terms_accepted: bool,
+    #[serde(rename = "privacyAccepted")]
+    pub privacy_accepted: bool,
+    #[serde(rename = "twoFactorAuth")]
+    pub two_factor_auth: bool,
+    pub language: String,
+    pub role: user_model::Role,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl UserExport {
+    pub fn new(user: &user_model::Model) -> Self {
+        UserExport {
+            id: user.id,
+            first_name: user.first_name.to_owned(),
+            last_name: user.last_name.to_owned(),
+            email: user.email.to_owned(),
+            phone: user.phone.to_owned(),
+            avatar: user.avatar.to_owned(),
+            terms_accepted: user.terms,
+            privacy_accepted: user.privacy,
+            two_factor_auth: user.two_fa,
+            language: String::from(language_code(&user.language)),
+            role: user.role.to_owned(),
+            created_at: user.created_at,
+            deleted_at: user.deleted_at,
+        }
+    }
+}
+
+/// The pending code is a secret and is never exported.
+#[derive(Serialize, Deserialize)]
+pub struct TwoFaExport {
+    #[serde(rename = "emailVerified")]
+    pub email_verified: bool,
+    #[serde(rename = "phoneVerified")]
+    pub phone_verified: bool,
+    #[serde(rename = "remainingTries")]
+    pub remaining_tries: i32,
+    #[serde(rename = "codesSent")]
+    pub codes_sent: i32,
+    #[serde(rename = "blockedUntil")]
+    pub blocked_until: Option<i64>,
+}
+
+impl TwoFaExport {
+    pub fn new(two_fa: &two_fa_model::Model) -> Self {
+        TwoFaExport {
+            email_verified: two_fa.email_verified,
+            phone_verified: two_fa.phone_verified,
+            remaining_tries: two_fa.tries_left,
+            codes_sent: two_fa.codes_sent,
+            blocked_until: two_fa.locked_until,
+        }
+    }
+}
+
+/// The nonce signs the calendar feed url, so only its creation date is exported.
+#[derive(Serialize, Deserialize)]
+pub struct CalendarTokenExport {
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl CalendarTokenExport {
+    pub fn new(calendar_token: &calendar_token_model::Model) -> Self {
+        CalendarTokenExport {
+            created_at: calendar_token.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionExport {
+    pub id: Uuid,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(rename = "revokedAt")]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl SessionExport {
+    pub fn new(session: &session_model::Model) -> Self {
+        SessionExport {
+            id: session.id,
+            created_at: session.created_at,
+            expires_at: session.expires_at,
+            revoked_at: session.revoked_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConsentExport {
+    pub kind: DocumentKind,
+    pub version: String,
+    #[serde(rename = "acceptedAt")]
+    pub accepted_at: DateTime<Utc>,
+    pub ip: Option<String>,
+}
+
+impl ConsentExport {
+    pub fn new(consent: &consent_model::Model) -> Self {
+        ConsentExport {
+            kind: consent.kind,
+            version: consent.version.to_owned(),
+            accepted_at: consent.accepted_at,
+            ip: consent.ip.to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AuthEventExport {
+    pub kind: AuthEventKind,
+    pub outcome: AuthEventOutcome,
+    pub ip: Option<String>,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuthEventExport {
+    pub fn new(event: &auth_event_model::Model) -> Self {
+        AuthEventExport {
+            kind: event.kind,
+            outcome: event.outcome,
+            ip: event.ip.to_owned(),
+            user_agent: event.user_agent.to_owned(),
+            created_at: event.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct KnownDeviceExport {
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    #[serde(rename = "ipPrefix")]
+    pub ip_prefix: Option<String>,
+    #[serde(rename = "firstSeenAt")]
+    pub first_seen_at: DateTime<Utc>,
+    #[serde(rename = "lastSeenAt")]
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl KnownDeviceExport {
+    pub fn new(device: &known_device_model::Model) -> Self {
+        KnownDeviceExport {
+            user_agent: device.user_agent.to_owned(),
+            ip_prefix: device.ip_prefix.to_owned(),
+            first_seen_at: device.first_seen_at,
+            last_seen_at: device.last_seen_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AppointmentExport {
+    pub id: Uuid,
+    pub title: String,
+    #[serde(rename = "startAt")]
+    pub start_at: i64,
+    #[serde(rename = "endAt")]
+    pub end_at: i64,
+    pub address: Option<String>,
+    pub cancelled: bool,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AppointmentExport {
+    pub fn new(rdv: &rdv_model::Model) -> Self {
+        AppointmentExport {
+            id: rdv.id,
+            title: rdv.title.to_owned(),
+            start_at: rdv.start_at,
+            end_at: rdv.end_at,
+            address: rdv.address.to_owned(),
+            cancelled: rdv.cancelled,
+            updated_at: rdv.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserDataExport {
+    #[serde(rename = "generatedAt")]
+    pub generated_at: DateTime<Utc>,
+    pub user: UserExport,
+    #[serde(rename = "twoFa")]
+    pub two_fa: Option<TwoFaExport>,
+    #[serde(rename = "calendarToken")]
+    pub calendar_token: Option<CalendarTokenExport>,
+    pub sessions: Vec<SessionExport>,
+    pub consents: Vec<ConsentExport>,
+    #[serde(rename = "authEvents")]
+    pub auth_events: Vec<AuthEventExport>,
+    #[serde(rename = "knownDevices")]
+    pub known_devices: Vec<KnownDeviceExport>,
+    pub appointments: Vec<AppointmentExport>,
+}
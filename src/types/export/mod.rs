@@ -0,0 +1 @@
+pub mod data_export;
\ No newline at end of file
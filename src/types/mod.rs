@@ -1,3 +1,9 @@
 pub mod register;
 pub mod auth;
-pub mod common;
\ No newline at end of file
+pub mod common;
+pub mod calendar;
+pub mod profile;
+pub mod export;
+pub mod consent;
+pub mod audit;
+pub mod admin;
\ No newline at end of file
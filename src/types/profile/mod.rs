@@ -0,0 +1 @@
+pub mod profile_data;
\ No newline at end of file
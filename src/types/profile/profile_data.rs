@@ -0,0 +1,87 @@
+use entity::entities::{two_fa_entity::two_fa_model, user_entity::user_model};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::i18n::language::language_code;
+
+/// The avatar is only set by `upload_avatar`, which controls its key.
+#[derive(Serialize, Deserialize)]
+pub struct ProfileUpdateRequest {
+    #[serde(rename = "firstName")]
+    pub first_name: Option<String>,
+    #[serde(rename = "lastName")]
+    pub last_name: Option<String>,
+    pub language: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EmailChangeRequest {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EmailChangeConfirmRequest {
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PhoneChangeRequest {
+    pub phone: String,
+    pub country: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PhoneChangeConfirmRequest {
+    pub token: String,
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EmailChangePayload {
+    pub id: Uuid,
+    pub email: String,
+}
+
+/// The code sent to the new phone only lives in this token, hashed, so it
+/// can neither log in nor replace a pending login code. `current_phone`
+/// makes the token single-use.
+#[derive(Serialize, Deserialize)]
+pub struct PhoneChangePayload {
+    pub id: Uuid,
+    pub phone: String,
+    pub current_phone: String,
+    pub code_hash: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProfileResponse {
+    pub id: Uuid,
+    #[serde(rename = "firstName")]
+    pub first_name: String,
+    #[serde(rename = "lastName")]
+    pub last_name: String,
+    pub email: String,
+    pub phone: String,
+    pub language: String,
+    pub avatar: Option<String>,
+    #[serde(rename = "emailVerified")]
+    pub email_verified: bool,
+    #[serde(rename = "phoneVerified")]
+    pub phone_verified: bool,
+}
+
+impl ProfileResponse {
+    pub fn new(user: &user_model::Model, two_fa: &two_fa_model::Model) -> Self {
+        ProfileResponse {
+            id: user.id,
+            first_name: user.first_name.to_owned(),
+            last_name: user.last_name.to_owned(),
+            email: user.email.to_owned(),
+            phone: user.phone.to_owned(),
+            language: String::from(language_code(&user.language)),
+            avatar: user.avatar.to_owned(),
+            email_verified: two_fa.email_verified,
+            phone_verified: two_fa.phone_verified,
+        }
+    }
+}
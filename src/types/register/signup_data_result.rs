@@ -6,6 +6,8 @@ pub struct SignUpDataResult {
     pub lastName: String,
     pub email: String,
     pub phone: String,
+    #[serde(default)]
+    pub country: Option<String>,
     pub address: Option<String>,
     pub postal: Option<String>,
     pub city: Option<String>,
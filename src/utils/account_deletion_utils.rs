@@ -0,0 +1,262 @@
+use std::{
+    env,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use actix_web::{rt, web::Data};
+use chrono::{DateTime, Utc};
+use dotenv::dotenv;
+use sea_orm::{DatabaseConnection, DbErr};
+use service::{
+    mutation::{data_export_mutations::DataExportMutation, user_mutations::UserMutation},
+    query::{data_export_queries::DataExportQuery, user_queries::UserQuery},
+};
+use tokio::task::AbortHandle;
+use tracing::{error, info};
+
+use crate::{
+    api::account::profile::avatar_upload_api::delete_avatar, storage::blob_store::BlobStore,
+    utils::task_manager_utils::TaskManager,
+};
+
+pub const DEFAULT_DELETION_GRACE_DAYS: i64 = 30;
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(3_600);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeletionPolicy {
+    pub grace_period: chrono::Duration,
+}
+
+impl DeletionPolicy {
+    pub fn from_env() -> Self {
+        dotenv().ok();
+        let days = env::var("ACCOUNT_DELETION_GRACE_DAYS")
+            .ok()
+            .and_then(|value| value.trim().parse::<i64>().ok())
+            .filter(|days| *days >= 0)
+            .unwrap_or(DEFAULT_DELETION_GRACE_DAYS);
+
+        DeletionPolicy {
+            grace_period: chrono::Duration::days(days),
+        }
+    }
+
+    pub fn purge_at(&self, deleted_at: DateTime<Utc>) -> DateTime<Utc> {
+        deleted_at + self.grace_period
+    }
+}
+
+/// Hard-deletes every account whose grace period is over. Related rows go
+/// with it through the FK cascades, the avatar and export files are removed
+/// by hand.
+pub async fn purge_deleted_accounts(
+    db: &DatabaseConnection,
+    store: &Data<dyn BlobStore>,
+    policy: &DeletionPolicy,
+) -> Result<usize, DbErr> {
+    let users = UserQuery::find_users_deleted_before(db, Utc::now() - policy.grace_period).await?;
+    let mut purged = 0;
+
+    for user in users {
+        let exports = DataExportQuery::find_data_exports_by_user_id(db, user.id).await?;
+        if let Err(err) = UserMutation::delete_user_by_id(db, user.id).await {
+            error!("Cannot purge user {}: {}", user.id, err);
+            continue;
+        }
+        if let Some(avatar_key) = &user.avatar {
+            delete_avatar(store, user.id, avatar_key).await;
+        }
+        for export in exports {
+            if let Err(err) = store.delete(&export.object_key).await {
+                error!("Cannot delete export {}: {}", export.object_key, err);
+            }
+        }
+        purged += 1;
+    }
+
+    Ok(purged)
+}
+
+/// Deletes the export archives whose download link has expired. A row is
+/// kept until its file is gone, so that a failed delete is retried.
+pub async fn purge_expired_exports(
+    db: &DatabaseConnection,
+    store: &Data<dyn BlobStore>,
+) -> Result<usize, DbErr> {
+    let exports = DataExportQuery::find_data_exports_expired_before(db, Utc::now()).await?;
+    let mut purged = 0;
+
+    for export in exports {
+        if let Err(err) = store.delete(&export.object_key).await {
+            error!("Cannot delete export {}: {}", export.object_key, err);
+            continue;
+        }
+        DataExportMutation::delete_data_export_by_id(db, export.id).await?;
+        purged += 1;
+    }
+
+    Ok(purged)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PurgeRun {
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+/// Handle of the purge loop, its state is reported by `/readyz`.
+pub struct PurgeWorker {
+    handle: AbortHandle,
+    last_run: Arc<Mutex<PurgeRun>>,
+}
+
+impl PurgeWorker {
+    /// The loop only returns at shutdown, before that a finished task means
+    /// it panicked.
+    pub fn is_running(&self) -> bool {
+        !self.handle.is_finished()
+    }
+
+    pub fn last_run(&self) -> PurgeRun {
+        match self.last_run.lock() {
+            Ok(last_run) => last_run.to_owned(),
+            Err(_) => PurgeRun::default(),
+        }
+    }
+}
+
+/// A purge started before the shutdown is completed, the next one is not.
+pub fn start_account_purge(
+    db: Data<DatabaseConnection>,
+    store: Data<dyn BlobStore>,
+    tasks: &TaskManager,
+) -> PurgeWorker {
+    let last_run = Arc::new(Mutex::new(PurgeRun::default()));
+    let worker_last_run = last_run.clone();
+    let mut shutdown = tasks.shutdown_signal();
+
+    let handle = tasks.spawn_job(async move {
+        let policy = DeletionPolicy::from_env();
+        let mut interval = rt::time::interval(PURGE_INTERVAL);
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.wait_for(|stopping| *stopping) => break,
+                _ = interval.tick() => {}
+            }
+            let result = purge_deleted_accounts(&db, &store, &policy).await;
+            match &result {
+                Ok(0) => {}
+                Ok(purged) => info!("Purged {} deleted accounts", purged),
+                Err(err) => error!("Cannot purge deleted accounts: {}", err),
+            }
+            let exports_result = purge_expired_exports(&db, &store).await;
+            match &exports_result {
+                Ok(0) => {}
+                Ok(purged) => info!("Purged {} expired exports", purged),
+                Err(err) => error!("Cannot purge expired exports: {}", err),
+            }
+            if let Ok(mut last_run) = worker_last_run.lock() {
+                *last_run = PurgeRun {
+                    last_run_at: Some(Utc::now()),
+                    last_error: result
+                        .err()
+                        .or(exports_result.err())
+                        .map(|err| err.to_string()),
+                };
+            }
+        }
+        info!("Account purge stopped");
+    });
+
+    PurgeWorker { handle, last_run }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::TimeZone;
+    use entity::entities::{data_export_entity::data_export_model, user_entity::user_model};
+    use nanoid::nanoid;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::storage::local_blob_store::LocalBlobStore;
+
+    #[test]
+    fn it_computes_the_purge_date() {
+        let policy = DeletionPolicy {
+            grace_period: chrono::Duration::days(DEFAULT_DELETION_GRACE_DAYS),
+        };
+        let deleted_at = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            policy.purge_at(deleted_at),
+            Utc.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[actix_web::test]
+    async fn it_purges_expired_accounts() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[user_model::Model {
+                id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                deleted_at: Some(Utc::now() - chrono::Duration::days(31)),
+                ..Default::default()
+            }]])
+            .append_query_results([Vec::<data_export_model::Model>::new()])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+        let store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(std::env::temp_dir()));
+        let policy = DeletionPolicy {
+            grace_period: chrono::Duration::days(DEFAULT_DELETION_GRACE_DAYS),
+        };
+
+        let purged = purge_deleted_accounts(&db, &Data::from(store), &policy)
+            .await
+            .unwrap();
+
+        assert_eq!(purged, 1);
+    }
+
+    #[actix_web::test]
+    async fn it_purges_expired_exports() {
+        let key = "exports/00000000-0000-0000-0000-000000000001/a.zip";
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[data_export_model::Model {
+                id: 1,
+                object_key: String::from(key),
+                expires_at: Utc::now() - chrono::Duration::days(1),
+                user_id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                ..Default::default()
+            }]])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+        let root = std::env::temp_dir().join(format!("account-deletion-{}", nanoid!()));
+        let store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(&root));
+        store
+            .put(key, "application/zip", vec![1, 2, 3])
+            .await
+            .unwrap();
+
+        let purged = purge_expired_exports(&db, &Data::from(store.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(purged, 1);
+        assert_eq!(store.get(key).await.unwrap(), None);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
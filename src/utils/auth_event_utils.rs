@@ -0,0 +1,77 @@
+use actix_web::HttpRequest;
+use entity::entities::auth_event_entity::auth_event_model::{
+    self, AuthEventKind, AuthEventOutcome,
+};
+use sea_orm::{ActiveModelBehavior, DbConn, Set};
+use service::mutation::auth_event_mutations::AuthEventMutation;
+use tracing::error;
+use uuid::Uuid;
+
+use super::request_utils::{client_ip, user_agent};
+
+pub fn new_auth_event(
+    req: &HttpRequest,
+    kind: AuthEventKind,
+    outcome: AuthEventOutcome,
+    user_id: Option<Uuid>,
+) -> auth_event_model::ActiveModel {
+    auth_event_model::ActiveModel {
+        kind: Set(kind),
+        outcome: Set(outcome),
+        ip: Set(client_ip(req)),
+        user_agent: Set(user_agent(req)),
+        user_id: Set(user_id),
+        ..auth_event_model::ActiveModel::new()
+    }
+}
+
+/// Auditing never fails the request it describes, a lost event is only logged.
+pub async fn record_auth_event(
+    db: &DbConn,
+    req: &HttpRequest,
+    kind: AuthEventKind,
+    outcome: AuthEventOutcome,
+    user_id: Option<Uuid>,
+) {
+    let event = new_auth_event(req, kind, outcome, user_id);
+
+    if let Err(err) = AuthEventMutation::create_auth_event(db, event).await {
+        error!("Cannot record {:?} auth event: {}", kind, err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::header, test};
+    use sea_orm::ActiveValue;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn it_builds_an_event_from_the_request() {
+        let req = test::TestRequest::default()
+            .insert_header(("X-Forwarded-For", "203.0.113.7"))
+            .insert_header((header::USER_AGENT, "Mozilla/5.0"))
+            .to_http_request();
+        let user_id = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+
+        let event = new_auth_event(
+            &req,
+            AuthEventKind::CodeFailed,
+            AuthEventOutcome::Failure,
+            Some(user_id),
+        );
+
+        assert_eq!(event.kind, ActiveValue::Set(AuthEventKind::CodeFailed));
+        assert_eq!(event.outcome, ActiveValue::Set(AuthEventOutcome::Failure));
+        assert_eq!(
+            event.ip,
+            ActiveValue::Set(Some(String::from("203.0.113.7")))
+        );
+        assert_eq!(
+            event.user_agent,
+            ActiveValue::Set(Some(String::from("Mozilla/5.0")))
+        );
+        assert_eq!(event.user_id, ActiveValue::Set(Some(user_id)));
+    }
+}
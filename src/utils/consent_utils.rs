@@ -0,0 +1,108 @@
+use entity::entities::{
+    consent_entity::consent_model, legal_document_entity::legal_document_model,
+};
+use sea_orm::{ActiveModelBehavior, DbConn, DbErr, Set};
+use service::query::{consent_queries::ConsentQuery, legal_document_queries::LegalDocumentQuery};
+use uuid::Uuid;
+
+use crate::types::consent::consent_data::PendingConsent;
+
+use super::login_policy_utils::env_flag;
+
+/// When set, protected routes answer 403 until the current documents are accepted.
+pub fn consent_required_from_env() -> bool {
+    env_flag("CONSENT_REQUIRED_ON_PROTECTED_ROUTES")
+}
+
+/// Current documents the user has not accepted in their current version.
+pub fn pending_consents(
+    documents: &[legal_document_model::Model],
+    consents: &[consent_model::Model],
+) -> Vec<PendingConsent> {
+    documents
+        .iter()
+        .filter(|document| {
+            !consents
+                .iter()
+                .any(|consent| consent.kind == document.kind && consent.version == document.version)
+        })
+        .map(PendingConsent::new)
+        .collect()
+}
+
+pub async fn find_pending_consents(
+    db: &DbConn,
+    user_id: Uuid,
+) -> Result<Vec<PendingConsent>, DbErr> {
+    let documents = LegalDocumentQuery::find_current_documents(db).await?;
+    let consents = ConsentQuery::find_consents_by_user_id(db, user_id).await?;
+
+    Ok(pending_consents(&documents, &consents))
+}
+
+pub fn new_consents(
+    user_id: Uuid,
+    documents: &[legal_document_model::Model],
+    ip: Option<String>,
+) -> Vec<consent_model::ActiveModel> {
+    documents
+        .iter()
+        .map(|document| consent_model::ActiveModel {
+            kind: Set(document.kind),
+            version: Set(document.version.to_owned()),
+            ip: Set(ip.to_owned()),
+            user_id: Set(user_id),
+            ..consent_model::ActiveModel::new()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use entity::entities::legal_document_entity::legal_document_model::DocumentKind;
+
+    use super::*;
+
+    fn document(kind: DocumentKind, version: &str) -> legal_document_model::Model {
+        legal_document_model::Model {
+            kind,
+            version: String::from(version),
+            url: format!("/legal/{}", version),
+            ..Default::default()
+        }
+    }
+
+    fn consent(kind: DocumentKind, version: &str) -> consent_model::Model {
+        consent_model::Model {
+            kind,
+            version: String::from(version),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_finds_documents_to_reaccept() {
+        let documents = vec![
+            document(DocumentKind::Terms, "2024-03-01"),
+            document(DocumentKind::Privacy, "2024-01-21"),
+        ];
+        let consents = vec![
+            consent(DocumentKind::Terms, "2024-01-21"),
+            consent(DocumentKind::Privacy, "2024-01-21"),
+        ];
+
+        let pending = pending_consents(&documents, &consents);
+
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].kind, DocumentKind::Terms);
+        assert_eq!(pending[0].version, "2024-03-01");
+    }
+
+    #[test]
+    fn it_has_nothing_pending_when_up_to_date() {
+        let documents = vec![document(DocumentKind::Terms, "2024-01-21")];
+        let consents = vec![consent(DocumentKind::Terms, "2024-01-21")];
+
+        assert!(pending_consents(&documents, &consents).is_empty());
+    }
+}
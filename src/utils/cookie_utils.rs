@@ -5,13 +5,20 @@ use actix_web::{
 use sea_orm::prelude::Uuid;
 use tracing::error;
 
-use crate::utils::crypto_utils::encrypt_payload;
+use crate::{
+    error::{api_error::ApiError, codes},
+    utils::crypto_utils::{decrypt_payload, encrypt_payload, EncryptedPayload},
+};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CookiePayload {
     pub exp_at: i64,
     pub id: Uuid,
+    /// Only set on the `SESSIONID` cookie, which is backed by a `sessions` row.
+    #[serde(default)]
+    pub session_id: Option<Uuid>,
 }
 
 pub fn create_cookie<'a>(name: &'a str, payload: &'a CookiePayload) -> Cookie<'a> {
@@ -66,6 +73,23 @@ pub fn get_cookie_from_http_request(req: &HttpRequest, name: &str) -> Option<Str
     };
 }
 
+/// Decrypts the payload of the named cookie and rejects it once expired.
+pub fn read_cookie_payload(req: &HttpRequest, name: &str) -> Result<CookiePayload, ApiError> {
+    let cookie =
+        get_cookie_from_http_request(req, name).ok_or(ApiError::Unauthorized(codes::TOKEN_MISSING))?;
+
+    let payload = serde_json::from_str::<EncryptedPayload>(cookie.as_str())
+        .ok()
+        .and_then(|token| decrypt_payload::<CookiePayload>(&token.order, &token.content).ok())
+        .ok_or(ApiError::Unauthorized(codes::TOKEN_INVALID))?;
+
+    if payload.exp_at < Utc::now().timestamp() {
+        return Err(ApiError::Unauthorized(codes::TOKEN_EXPIRED));
+    }
+
+    Ok(payload)
+}
+
 pub fn delete_cookie<'a>(name: &'a str) -> Cookie<'a> {
     return Cookie::build(name, "")
         .domain("localhost")
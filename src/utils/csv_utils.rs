@@ -0,0 +1,137 @@
+//! RFC 4180 CSV, enough for the admin user import and export.
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CsvError {
+    /// A quoted field is not closed before the end of the input.
+    UnclosedQuote,
+    /// Text follows the closing quote of a field on this line.
+    StrayQuote(usize),
+}
+
+/// Records of `input` with their line number, starting at 1. Blank lines are
+/// skipped and a leading byte order mark is ignored.
+pub fn parse_csv(input: &str) -> Result<Vec<(usize, Vec<String>)>, CsvError> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() => loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => match chars.peek() {
+                        None | Some(',') | Some('\r') | Some('\n') => break,
+                        Some(_) => return Err(CsvError::StrayQuote(line)),
+                    },
+                    Some(c) => {
+                        if c == '\n' {
+                            line += 1;
+                        }
+                        field.push(c);
+                    }
+                    None => return Err(CsvError::UnclosedQuote),
+                }
+            },
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                push_record(&mut records, record_line, std::mem::take(&mut record));
+                line += 1;
+                record_line = line;
+            }
+            c => field.push(c),
+        }
+    }
+    record.push(field);
+    push_record(&mut records, record_line, record);
+
+    Ok(records)
+}
+
+fn push_record(records: &mut Vec<(usize, Vec<String>)>, line: usize, record: Vec<String>) {
+    if record.iter().any(|field| !field.trim().is_empty()) {
+        records.push((line, record));
+    }
+}
+
+/// Appends `fields` as one CRLF terminated record. A value a spreadsheet
+/// would run as a formula gets a leading `'`, phone numbers excepted.
+pub fn write_csv_record<S: AsRef<str>>(out: &mut String, fields: &[S]) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let field = field.as_ref();
+        let field = match is_formula(field) {
+            true => format!("'{}", field),
+            false => field.to_owned(),
+        };
+
+        match field.contains([',', '"', '\r', '\n']) {
+            true => {
+                out.push('"');
+                out.push_str(&field.replace('"', "\"\""));
+                out.push('"');
+            }
+            false => out.push_str(&field),
+        }
+    }
+    out.push_str("\r\n");
+}
+
+fn is_formula(field: &str) -> bool {
+    let mut chars = field.chars();
+    match chars.next() {
+        Some('=') | Some('@') | Some('\t') | Some('\r') => true,
+        Some('+') | Some('-') => !chars.all(|c| c.is_ascii_digit()),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_quoted_fields() {
+        let records =
+            parse_csv("\u{feff}a,b\r\n\"x, y\",\"say \"\"hi\"\"\"\r\n\r\n\"multi\nline\",\n")
+                .unwrap();
+
+        assert_eq!(
+            records,
+            vec![
+                (1, vec![String::from("a"), String::from("b")]),
+                (2, vec![String::from("x, y"), String::from("say \"hi\"")]),
+                (4, vec![String::from("multi\nline"), String::new()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_rejects_broken_quotes() {
+        assert_eq!(parse_csv("a\n\"b"), Err(CsvError::UnclosedQuote));
+        assert_eq!(parse_csv("a\n\"b\"c"), Err(CsvError::StrayQuote(2)));
+    }
+
+    #[test]
+    fn it_writes_escaped_records() {
+        let mut out = String::new();
+        write_csv_record(&mut out, &["a,b", "say \"hi\"", "+33600000001"]);
+        write_csv_record(&mut out, &["=1+1", "-x", "plain"]);
+
+        assert_eq!(
+            out,
+            "\"a,b\",\"say \"\"hi\"\"\",+33600000001\r\n'=1+1,'-x,plain\r\n"
+        );
+    }
+}
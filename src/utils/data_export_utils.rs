@@ -0,0 +1,265 @@
+use std::{
+    fmt,
+    io::{Cursor, Write},
+};
+
+use chrono::Utc;
+use nanoid::nanoid;
+use sea_orm::{DbConn, DbErr};
+use service::query::{
+    auth_event_queries::AuthEventQuery, calendar_token_queries::CalendarTokenQuery,
+    consent_queries::ConsentQuery, known_device_queries::KnownDeviceQuery, rdv_queries::RdvQuery,
+    session_queries::SessionQuery, user_queries::UserQuery,
+};
+use uuid::Uuid;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    storage::blob_store::BlobStore,
+    types::export::data_export::{
+        AppointmentExport, AuthEventExport, CalendarTokenExport, ConsentExport, KnownDeviceExport,
+        SessionExport, TwoFaExport, UserDataExport, UserExport,
+    },
+    utils::image_utils::{avatar_object_key, is_avatar_of, AVATAR_SIZES},
+};
+
+pub const EXPORT_CONTENT_TYPE: &str = "application/zip";
+/// Above this many rows (sessions, consents, auth events, devices and
+/// appointments) the archive is built in the background and a download link
+/// is emailed instead.
+pub const EXPORT_SYNC_MAX_ROWS: usize = 1_000;
+
+const EXPORT_JSON_FILE: &str = "export.json";
+
+#[derive(Debug)]
+pub struct ExportError(pub String);
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl UserDataExport {
+    pub fn is_large(&self) -> bool {
+        let rows = self.sessions.len()
+            + self.consents.len()
+            + self.auth_events.len()
+            + self.known_devices.len()
+            + self.appointments.len();
+        rows > EXPORT_SYNC_MAX_ROWS
+    }
+}
+
+pub fn export_object_key(user_id: Uuid) -> String {
+    format!("exports/{}/{}.zip", user_id, nanoid!())
+}
+
+pub fn export_file_name(user_id: Uuid) -> String {
+    format!("export-{}.zip", user_id)
+}
+
+pub async fn collect_user_data(db: &DbConn, user_id: Uuid) -> Result<UserDataExport, DbErr> {
+    let user = UserQuery::find_user_by_id_with_deleted(db, user_id).await?;
+
+    let two_fa = match UserQuery::find_related_two_fa(db, &user).await {
+        Ok(t) => Some(TwoFaExport::new(&t)),
+        Err(DbErr::RecordNotFound(_)) => None,
+        Err(err) => return Err(err),
+    };
+
+    let calendar_token = CalendarTokenQuery::find_calendar_token_by_user_id(db, user_id)
+        .await?
+        .as_ref()
+        .map(CalendarTokenExport::new);
+
+    let sessions = SessionQuery::find_sessions_by_user_id(db, user_id)
+        .await?
+        .iter()
+        .map(SessionExport::new)
+        .collect();
+
+    let consents = ConsentQuery::find_consents_by_user_id(db, user_id)
+        .await?
+        .iter()
+        .map(ConsentExport::new)
+        .collect();
+
+    let auth_events = AuthEventQuery::find_auth_events_by_user_id(db, user_id)
+        .await?
+        .iter()
+        .map(AuthEventExport::new)
+        .collect();
+
+    let known_devices = KnownDeviceQuery::find_known_devices_by_user_id(db, user_id)
+        .await?
+        .iter()
+        .map(KnownDeviceExport::new)
+        .collect();
+
+    let appointments = RdvQuery::find_all_rdv_by_pro_id(db, user_id)
+        .await?
+        .iter()
+        .map(AppointmentExport::new)
+        .collect();
+
+    Ok(UserDataExport {
+        generated_at: Utc::now(),
+        user: UserExport::new(&user),
+        two_fa,
+        calendar_token,
+        sessions,
+        consents,
+        auth_events,
+        known_devices,
+        appointments,
+    })
+}
+
+pub async fn build_export_archive(
+    export: &UserDataExport,
+    store: &dyn BlobStore,
+) -> Result<Vec<u8>, ExportError> {
+    let mut files = vec![(
+        String::from(EXPORT_JSON_FILE),
+        serde_json::to_vec_pretty(export).map_err(|err| ExportError(err.to_string()))?,
+    )];
+
+    let avatar_key = export
+        .user
+        .avatar
+        .as_deref()
+        .filter(|avatar_key| is_avatar_of(avatar_key, export.user.id));
+    if let Some(avatar_key) = avatar_key {
+        for size in AVATAR_SIZES {
+            let blob = store
+                .get(&avatar_object_key(avatar_key, size))
+                .await
+                .map_err(|err| ExportError(err.to_string()))?;
+            if let Some(blob) = blob {
+                files.push((format!("avatar/{}.jpg", size), blob.bytes));
+            }
+        }
+    }
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, bytes) in files {
+        zip.start_file(name, options)
+            .and_then(|_| zip.write_all(&bytes).map_err(Into::into))
+            .map_err(|err| ExportError(err.to_string()))?;
+    }
+
+    zip.finish()
+        .map(|cursor| cursor.into_inner())
+        .map_err(|err| ExportError(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, sync::Arc};
+
+    use zip::ZipArchive;
+
+    use super::*;
+    use crate::storage::local_blob_store::LocalBlobStore;
+
+    const AVATAR_KEY: &str = "avatars/00000000-0000-0000-0000-000000000001/a";
+
+    fn export(avatar: Option<String>, appointments: usize) -> UserDataExport {
+        UserDataExport {
+            generated_at: Utc::now(),
+            user: UserExport::new(&entity::entities::user_entity::user_model::Model {
+                id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+                avatar,
+                first_name: String::from("Rob"),
+                ..Default::default()
+            }),
+            two_fa: None,
+            calendar_token: None,
+            sessions: Vec::new(),
+            consents: Vec::new(),
+            auth_events: Vec::new(),
+            known_devices: Vec::new(),
+            appointments: (0..appointments)
+                .map(|_| AppointmentExport::new(&Default::default()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn it_flags_large_exports() {
+        assert!(!export(None, EXPORT_SYNC_MAX_ROWS).is_large());
+        assert!(export(None, EXPORT_SYNC_MAX_ROWS + 1).is_large());
+
+        let mut export = export(None, EXPORT_SYNC_MAX_ROWS);
+        export
+            .auth_events
+            .push(AuthEventExport::new(&Default::default()));
+        assert!(export.is_large());
+    }
+
+    #[actix_web::test]
+    async fn it_builds_an_archive_with_the_avatar() {
+        let root = std::env::temp_dir().join(format!("data-export-{}", nanoid!()));
+        let store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(&root));
+        store
+            .put(
+                &avatar_object_key(AVATAR_KEY, 64),
+                "image/jpeg",
+                vec![1, 2, 3],
+            )
+            .await
+            .unwrap();
+
+        let bytes = build_export_archive(&export(Some(String::from(AVATAR_KEY)), 1), &*store)
+            .await
+            .unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut json = String::new();
+        archive
+            .by_name(EXPORT_JSON_FILE)
+            .unwrap()
+            .read_to_string(&mut json)
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["user"]["firstName"], "Rob");
+        assert_eq!(json["appointments"].as_array().unwrap().len(), 1);
+
+        let mut avatar = Vec::new();
+        archive
+            .by_name("avatar/64.jpg")
+            .unwrap()
+            .read_to_end(&mut avatar)
+            .unwrap();
+        assert_eq!(avatar, vec![1, 2, 3]);
+        assert!(archive.by_name("avatar/128.jpg").is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[actix_web::test]
+    async fn it_skips_the_avatar_of_another_user() {
+        let root = std::env::temp_dir().join(format!("data-export-{}", nanoid!()));
+        let store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(&root));
+        let other_key = "avatars/00000000-0000-0000-0000-000000000002/a";
+        store
+            .put(
+                &avatar_object_key(other_key, 64),
+                "image/jpeg",
+                vec![1, 2, 3],
+            )
+            .await
+            .unwrap();
+
+        let bytes = build_export_archive(&export(Some(String::from(other_key)), 0), &*store)
+            .await
+            .unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert!(archive.by_name("avatar/64.jpg").is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,225 @@
+use std::net::{IpAddr, SocketAddr};
+
+use actix_web::HttpRequest;
+use chrono::Utc;
+use entity::entities::{known_device_entity::known_device_model, user_entity::user_model};
+use nanoid::nanoid;
+use sea_orm::{ActiveModelBehavior, DbConn, DbErr, Set};
+use service::{
+    mutation::known_device_mutations::KnownDeviceMutation,
+    query::known_device_queries::KnownDeviceQuery,
+};
+use sha2::{Digest, Sha256};
+use tracing::{error, Instrument};
+use uuid::Uuid;
+
+use crate::{
+    emails::new_device_email::{send_new_device_email, NewDeviceEmailData},
+    types::auth::new_device::NewDevicePayload,
+};
+
+use super::{
+    jwt_utils::create_token,
+    request_utils::{client_ip, user_agent},
+    task_manager_utils::TaskManager,
+    time_utils::MAX_AGE_7J,
+};
+
+/// A device is the pair of a user agent and the network it connects from, so a
+/// phone moving between cell towers of the same operator range stays known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    pub fingerprint: String,
+    pub user_agent: Option<String>,
+    pub ip_prefix: Option<String>,
+}
+
+impl DeviceInfo {
+    pub fn new(user_agent: Option<String>, ip: Option<String>) -> Self {
+        let ip_prefix = ip.as_deref().and_then(ip_prefix);
+        DeviceInfo {
+            fingerprint: device_fingerprint(user_agent.as_deref(), ip_prefix.as_deref()),
+            user_agent,
+            ip_prefix,
+        }
+    }
+
+    pub fn from_request(req: &HttpRequest) -> Self {
+        DeviceInfo::new(user_agent(req), client_ip(req))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceLogin {
+    /// The first device ever recorded for the user, usually the signup one.
+    First,
+    Known,
+    New {
+        report_nonce: String,
+    },
+}
+
+/// `/24` for IPv4 and `/48` for IPv6, the address may carry a port.
+pub fn ip_prefix(ip: &str) -> Option<String> {
+    let ip = match ip.parse::<SocketAddr>() {
+        Ok(socket) => socket.ip(),
+        Err(_) => ip.parse::<IpAddr>().ok()?,
+    };
+
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, _] = v4.octets();
+            Some(format!("{}.{}.{}.0/24", a, b, c))
+        }
+        IpAddr::V6(v6) => {
+            let s = v6.segments();
+            Some(format!("{:x}:{:x}:{:x}::/48", s[0], s[1], s[2]))
+        }
+    }
+}
+
+pub fn device_fingerprint(user_agent: Option<&str>, ip_prefix: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(user_agent.unwrap_or_default());
+    hasher.update(b"\n");
+    hasher.update(ip_prefix.unwrap_or_default());
+    hex::encode(hasher.finalize())
+}
+
+pub async fn register_login_device(
+    db: &DbConn,
+    user_id: Uuid,
+    device: &DeviceInfo,
+) -> Result<DeviceLogin, DbErr> {
+    let devices = KnownDeviceQuery::find_known_devices_by_user_id(db, user_id).await?;
+
+    if let Some(known) = devices
+        .iter()
+        .find(|known| known.fingerprint == device.fingerprint)
+    {
+        KnownDeviceMutation::touch_known_device(db, known.id).await?;
+        return Ok(DeviceLogin::Known);
+    }
+
+    let report_nonce = nanoid!();
+    KnownDeviceMutation::create_known_device(
+        db,
+        known_device_model::ActiveModel {
+            fingerprint: Set(device.fingerprint.to_owned()),
+            user_agent: Set(device.user_agent.to_owned()),
+            ip_prefix: Set(device.ip_prefix.to_owned()),
+            report_nonce: Set(Some(report_nonce.to_owned())),
+            user_id: Set(user_id),
+            ..known_device_model::ActiveModel::new()
+        },
+    )
+    .await?;
+
+    if devices.is_empty() {
+        Ok(DeviceLogin::First)
+    } else {
+        Ok(DeviceLogin::New { report_nonce })
+    }
+}
+
+/// Sends the alert in the background, the login must not wait on the email provider.
+pub fn notify_new_device(
+    tasks: &TaskManager,
+    user: &user_model::Model,
+    device: &DeviceInfo,
+    ip: Option<String>,
+    session_id: Uuid,
+    report_nonce: String,
+) {
+    let payload = NewDevicePayload {
+        user_id: user.id,
+        session_id,
+        nonce: report_nonce,
+    };
+    let data = NewDeviceEmailData {
+        email_to: user.email.to_owned(),
+        first_name: user.first_name.to_owned(),
+        device: device.user_agent.to_owned().unwrap_or_default(),
+        ip: ip.unwrap_or_default(),
+        token: create_token(&payload, Utc::now().timestamp() + MAX_AGE_7J),
+    };
+    let user_id = user.id;
+
+    tasks.spawn_job(
+        async move {
+            if send_new_device_email(data).await.is_err() {
+                error!("Cannot alert user {} of a new device", user_id);
+            }
+        }
+        .in_current_span(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{DatabaseBackend, MockDatabase};
+
+    use super::*;
+
+    #[test]
+    fn it_keeps_the_network_prefix() {
+        assert_eq!(
+            ip_prefix("203.0.113.7"),
+            Some(String::from("203.0.113.0/24"))
+        );
+        assert_eq!(
+            ip_prefix("203.0.113.7:52044"),
+            Some(String::from("203.0.113.0/24"))
+        );
+        assert_eq!(
+            ip_prefix("2001:db8:85a3:8d3:1319:8a2e:370:7348"),
+            Some(String::from("2001:db8:85a3::/48"))
+        );
+        assert_eq!(ip_prefix("unknown"), None);
+    }
+
+    #[test]
+    fn it_fingerprints_the_same_network_alike() {
+        let ua = Some(String::from("Mozilla/5.0"));
+
+        let home = DeviceInfo::new(ua.to_owned(), Some(String::from("203.0.113.7")));
+        let same_network = DeviceInfo::new(ua.to_owned(), Some(String::from("203.0.113.42")));
+        let elsewhere = DeviceInfo::new(ua, Some(String::from("198.51.100.7")));
+
+        assert_eq!(home.fingerprint, same_network.fingerprint);
+        assert_ne!(home.fingerprint, elsewhere.fingerprint);
+    }
+
+    #[actix_web::test]
+    async fn it_flags_a_new_device() {
+        let user_id = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let known = DeviceInfo::new(
+            Some(String::from("Mozilla/5.0")),
+            Some(String::from("203.0.113.7")),
+        );
+        let device = DeviceInfo::new(
+            Some(String::from("curl/8.0")),
+            Some(String::from("198.51.100.7")),
+        );
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[known_device_model::Model {
+                id: 1,
+                fingerprint: known.fingerprint,
+                user_id,
+                ..Default::default()
+            }]])
+            .append_query_results([[known_device_model::Model {
+                id: 2,
+                fingerprint: device.fingerprint.to_owned(),
+                user_id,
+                ..Default::default()
+            }]])
+            .into_connection();
+
+        assert!(matches!(
+            register_login_device(&db, user_id, &device).await,
+            Ok(DeviceLogin::New { .. })
+        ));
+    }
+}
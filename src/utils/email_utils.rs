@@ -0,0 +1,18 @@
+/// Form stored in `users.email`, which has a unique index on `lower(email)`.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_trims_and_lowercases() {
+        assert_eq!(
+            normalize_email("  Jane.Doe@Gmail.COM "),
+            "jane.doe@gmail.com"
+        );
+        assert_eq!(normalize_email("a@b.co"), "a@b.co");
+    }
+}
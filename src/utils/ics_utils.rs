@@ -0,0 +1,180 @@
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+const ICS_PRODID: &str = "-//Focus//Focus Rdv//FR";
+const ICS_MAX_LINE_OCTETS: usize = 75;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IcsMethod {
+    Request,
+    Cancel,
+    Publish,
+}
+
+impl IcsMethod {
+    fn as_str(&self) -> &'static str {
+        match self {
+            IcsMethod::Request => "REQUEST",
+            IcsMethod::Cancel => "CANCEL",
+            IcsMethod::Publish => "PUBLISH",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IcsEvent {
+    pub uid: Uuid,
+    pub sequence: i32,
+    pub start_at: i64,
+    pub end_at: i64,
+    pub summary: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub organizer_name: String,
+    pub attendee_email: Option<String>,
+    pub cancelled: bool,
+}
+
+pub fn create_ics(events: &[IcsEvent], method: IcsMethod) -> String {
+    let mut lines: Vec<String> = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        format!("PRODID:{}", ICS_PRODID),
+        String::from("CALSCALE:GREGORIAN"),
+        format!("METHOD:{}", method.as_str()),
+    ];
+
+    let dtstamp = format_ics_datetime(Utc::now().timestamp());
+
+    for event in events {
+        lines.push(String::from("BEGIN:VEVENT"));
+        lines.push(format!("UID:{}@focus", event.uid));
+        lines.push(format!("SEQUENCE:{}", event.sequence));
+        lines.push(format!("DTSTAMP:{}", dtstamp));
+        lines.push(format!("DTSTART:{}", format_ics_datetime(event.start_at)));
+        lines.push(format!("DTEND:{}", format_ics_datetime(event.end_at)));
+        lines.push(format!("SUMMARY:{}", escape_ics_text(&event.summary)));
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape_ics_text(description)));
+        }
+        if let Some(location) = &event.location {
+            lines.push(format!("LOCATION:{}", escape_ics_text(location)));
+        }
+        lines.push(format!(
+            "ORGANIZER;CN={}:mailto:noreply@focus.fr",
+            escape_ics_param(&event.organizer_name)
+        ));
+        if let Some(attendee_email) = &event.attendee_email {
+            lines.push(format!("ATTENDEE;RSVP=FALSE:mailto:{}", attendee_email));
+        }
+        if event.cancelled || method == IcsMethod::Cancel {
+            lines.push(String::from("STATUS:CANCELLED"));
+        } else {
+            lines.push(String::from("STATUS:CONFIRMED"));
+        }
+        lines.push(String::from("END:VEVENT"));
+    }
+
+    lines.push(String::from("END:VCALENDAR"));
+
+    lines
+        .iter()
+        .map(|line| fold_ics_line(line))
+        .collect::<Vec<String>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+fn format_ics_datetime(timestamp: i64) -> String {
+    Utc.timestamp_opt(timestamp, 0)
+        .unwrap()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn escape_ics_param(text: &str) -> String {
+    if text.contains([':', ';', ',']) {
+        format!("\"{}\"", text.replace('"', ""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut octets = 0;
+
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if octets + len > ICS_MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += len;
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> IcsEvent {
+        IcsEvent {
+            uid: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            sequence: 2,
+            start_at: 1_703_113_200,
+            end_at: 1_703_116_800,
+            summary: String::from("Rdv avec Rob Doe"),
+            description: Some(String::from("Séance; suivi, bilan")),
+            location: Some(String::from("5 rue de la justice, Perpignan")),
+            organizer_name: String::from("Rob Doe"),
+            attendee_email: Some(String::from("test.user.1@gmail.com")),
+            cancelled: false,
+        }
+    }
+
+    #[test]
+    fn it_creates_a_request_ics() {
+        let ics = create_ics(&[event()], IcsMethod::Request);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("METHOD:REQUEST\r\n"));
+        assert!(ics.contains("UID:00000000-0000-0000-0000-000000000001@focus\r\n"));
+        assert!(ics.contains("SEQUENCE:2\r\n"));
+        assert!(ics.contains("DTSTART:20231220T230000Z\r\n"));
+        assert!(ics.contains("DTEND:20231221T000000Z\r\n"));
+        assert!(ics.contains("DESCRIPTION:Séance\\; suivi\\, bilan\r\n"));
+        assert!(ics.contains("STATUS:CONFIRMED\r\n"));
+    }
+
+    #[test]
+    fn it_creates_a_cancel_ics() {
+        let ics = create_ics(&[event()], IcsMethod::Cancel);
+
+        assert!(ics.contains("METHOD:CANCEL\r\n"));
+        assert!(ics.contains("STATUS:CANCELLED\r\n"));
+    }
+
+    #[test]
+    fn it_folds_long_lines() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(60));
+        let folded = fold_ics_line(&line);
+
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= ICS_MAX_LINE_OCTETS);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
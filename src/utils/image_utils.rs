@@ -0,0 +1,228 @@
+use std::io::Cursor;
+
+use image::{
+    codecs::jpeg::JpegEncoder, imageops::FilterType, io::Limits, io::Reader as ImageReader,
+    DynamicImage, ImageFormat,
+};
+use nanoid::nanoid;
+use uuid::Uuid;
+
+pub const AVATAR_SIZES: [u32; 3] = [64, 128, 256];
+pub const AVATAR_MAX_BYTES: usize = 5 * 1024 * 1024;
+pub const AVATAR_CONTENT_TYPES: [&str; 3] = ["image/jpeg", "image/png", "image/webp"];
+pub const AVATAR_OUTPUT_CONTENT_TYPE: &str = "image/jpeg";
+
+const AVATAR_MAX_DIMENSION: u32 = 8_000;
+const AVATAR_JPEG_QUALITY: u8 = 85;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ImageError {
+    InvalidType,
+    Unreadable,
+}
+
+pub struct Thumbnail {
+    pub size: u32,
+    pub bytes: Vec<u8>,
+}
+
+pub fn new_avatar_key(user_id: Uuid) -> String {
+    format!("avatars/{}/{}", user_id, nanoid!())
+}
+
+/// Whether `avatar_key` has the shape `new_avatar_key` gives to `user_id`.
+/// The column is checked before its files are read or deleted, so that a
+/// tampered value cannot reach the files of another user.
+pub fn is_avatar_of(avatar_key: &str, user_id: Uuid) -> bool {
+    avatar_key
+        .strip_prefix(&format!("avatars/{}/", user_id))
+        .is_some_and(|id| {
+            !id.is_empty()
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        })
+}
+
+pub fn avatar_object_key(avatar_key: &str, size: u32) -> String {
+    format!("{}/{}.jpg", avatar_key, size)
+}
+
+pub fn create_avatar_thumbnails(bytes: &[u8]) -> Result<Vec<Thumbnail>, ImageError> {
+    let format = image::guess_format(bytes).map_err(|_| ImageError::InvalidType)?;
+    if !matches!(
+        format,
+        ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP
+    ) {
+        return Err(ImageError::InvalidType);
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(AVATAR_MAX_DIMENSION);
+    limits.max_image_height = Some(AVATAR_MAX_DIMENSION);
+
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+    let image = reader.decode().map_err(|_| ImageError::Unreadable)?;
+
+    // Decoding then re-encoding drops every metadata segment, EXIF included,
+    // so the orientation has to be applied to the pixels first.
+    let image = apply_orientation(image, read_orientation(bytes));
+
+    AVATAR_SIZES
+        .iter()
+        .map(|size| {
+            let thumbnail = image.resize_to_fill(*size, *size, FilterType::Lanczos3);
+            let mut bytes = Vec::new();
+            JpegEncoder::new_with_quality(&mut bytes, AVATAR_JPEG_QUALITY)
+                .encode_image(&thumbnail.to_rgb8())
+                .map_err(|_| ImageError::Unreadable)?;
+            Ok(Thumbnail { size: *size, bytes })
+        })
+        .collect()
+}
+
+fn read_orientation(bytes: &[u8]) -> u32 {
+    exif::Reader::new()
+        .read_from_container(&mut Cursor::new(bytes))
+        .ok()
+        .and_then(|exif| {
+            exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+                .and_then(|field| field.value.get_uint(0))
+        })
+        .unwrap_or(1)
+}
+
+fn apply_orientation(image: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgb};
+
+    fn encode(format: ImageFormat, width: u32, height: u32) -> Vec<u8> {
+        let image = ImageBuffer::from_fn(width, height, |x, _| Rgb([(x % 255) as u8, 120, 40]));
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .unwrap();
+        bytes
+    }
+
+    fn with_exif_orientation(jpeg: Vec<u8>, orientation: u8) -> Vec<u8> {
+        let tiff: Vec<u8> = vec![
+            b'M',
+            b'M',
+            0,
+            42,
+            0,
+            0,
+            0,
+            8, // big-endian header, IFD0 at offset 8
+            0,
+            1, // one entry
+            0x01,
+            0x12,
+            0,
+            3,
+            0,
+            0,
+            0,
+            1,
+            0,
+            orientation,
+            0,
+            0, // Orientation, SHORT
+            0,
+            0,
+            0,
+            0, // no next IFD
+        ];
+        let mut app1 = b"Exif\0\0".to_vec();
+        app1.extend(tiff);
+        let len = (app1.len() + 2) as u16;
+
+        let mut bytes = jpeg[..2].to_vec();
+        bytes.extend([0xFF, 0xE1]);
+        bytes.extend(len.to_be_bytes());
+        bytes.extend(app1);
+        bytes.extend(&jpeg[2..]);
+        bytes
+    }
+
+    #[test]
+    fn it_creates_square_thumbnails() {
+        let thumbnails = create_avatar_thumbnails(&encode(ImageFormat::Png, 300, 200)).unwrap();
+
+        assert_eq!(thumbnails.len(), AVATAR_SIZES.len());
+        for thumbnail in thumbnails {
+            let image = image::load_from_memory(&thumbnail.bytes).unwrap();
+            assert_eq!(image.width(), thumbnail.size);
+            assert_eq!(image.height(), thumbnail.size);
+            assert_eq!(
+                image::guess_format(&thumbnail.bytes).unwrap(),
+                ImageFormat::Jpeg
+            );
+        }
+    }
+
+    #[test]
+    fn it_strips_exif() {
+        let jpeg = with_exif_orientation(encode(ImageFormat::Jpeg, 80, 40), 6);
+        assert_eq!(read_orientation(&jpeg), 6);
+
+        let thumbnails = create_avatar_thumbnails(&jpeg).unwrap();
+
+        for thumbnail in thumbnails {
+            assert_eq!(read_orientation(&thumbnail.bytes), 1);
+            assert!(!thumbnail.bytes.windows(4).any(|w| w == b"Exif"));
+        }
+    }
+
+    #[test]
+    fn it_applies_the_orientation() {
+        let image = DynamicImage::new_rgb8(80, 40);
+
+        let rotated = apply_orientation(image, 6);
+
+        assert_eq!((rotated.width(), rotated.height()), (40, 80));
+    }
+
+    #[test]
+    fn it_checks_the_owner_of_avatar_keys() {
+        let user_id = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let other_id = Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap();
+
+        assert!(is_avatar_of(&new_avatar_key(user_id), user_id));
+        assert!(!is_avatar_of(&new_avatar_key(other_id), user_id));
+        assert!(!is_avatar_of(&format!("avatars/{}/", user_id), user_id));
+        assert!(!is_avatar_of(
+            &format!("avatars/{}/../{}/a", user_id, other_id),
+            user_id
+        ));
+        assert!(!is_avatar_of("exports/a", user_id));
+    }
+
+    #[test]
+    fn it_rejects_other_types() {
+        assert_eq!(
+            create_avatar_thumbnails(b"GIF89a\x01\x00\x01\x00").err(),
+            Some(ImageError::InvalidType)
+        );
+        assert_eq!(
+            create_avatar_thumbnails(b"not an image").err(),
+            Some(ImageError::InvalidType)
+        );
+    }
+}
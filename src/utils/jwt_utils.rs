@@ -1,11 +1,13 @@
 use chrono::Utc;
 use dotenv::dotenv;
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{
+    decode, encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::env;
 use std::marker::PhantomData;
 
-use crate::error::resp_errors::RespErrors;
+use crate::error::{api_error::ApiError, codes};
 
 use super::crypto_utils::{decrypt_payload, encrypt_payload};
 
@@ -53,9 +55,7 @@ where
     }
 }
 
-pub fn decode_token<T: for<'a> DeserializeOwned>(
-    token: &str,
-) -> Result<ClaimsToken<T>, RespErrors<String>> {
+pub fn decode_token<T: for<'a> DeserializeOwned>(token: &str) -> Result<ClaimsToken<'_, T>, ApiError> {
     dotenv().ok();
     let secret_key = env::var("TOKEN_SECRET").expect("TOKEN_SECRET must be set");
     let decoding_key = DecodingKey::from_secret(secret_key.as_ref());
@@ -70,18 +70,17 @@ pub fn decode_token<T: for<'a> DeserializeOwned>(
             match decrypt_payload::<ClaimsToken<T>>(&iv, &cipher_text) {
                 Ok(payload) => {
                     if payload.exp < Utc::now().timestamp() {
-                        Err(RespErrors::new("Token", "Expired", None))
+                        Err(ApiError::Unauthorized(codes::TOKEN_EXPIRED))
                     } else {
                         Ok(payload)
                     }
                 }
-                Err(_) => {
-                    return Err(RespErrors::new("Token", "Expired", None));
-                }
+                Err(_) => Err(ApiError::Unauthorized(codes::TOKEN_INVALID)),
             }
         }
-        Err(_) => {
-            return Err(RespErrors::new("Token", "Expired", None));
-        }
+        Err(err) => match err.kind() {
+            ErrorKind::ExpiredSignature => Err(ApiError::Unauthorized(codes::TOKEN_EXPIRED)),
+            _ => Err(ApiError::Unauthorized(codes::TOKEN_INVALID)),
+        },
     }
 }
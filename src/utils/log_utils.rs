@@ -0,0 +1,140 @@
+use std::{env, io, str::FromStr};
+
+use dotenv::dotenv;
+use lazy_static::lazy_static;
+use opentelemetry_sdk::trace::Tracer;
+use regex::Regex;
+use tracing::Level;
+use tracing_subscriber::{
+    filter::LevelFilter, fmt::MakeWriter, layer::SubscriberExt, util::SubscriberInitExt, Layer,
+};
+
+lazy_static! {
+    static ref EMAIL_REGEX: Regex =
+        Regex::new(r"[A-Za-z0-9._%+-]+@([A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,})").unwrap();
+    // Phones are stored normalized (E.164), so a `+` followed by digits is enough.
+    static ref PHONE_REGEX: Regex = Regex::new(r"\+\d{6,13}(\d{2})\b").unwrap();
+    // `code=123456`, `"code":"123456"` and the `c: Some("123456")` of a debug printed two_fa.
+    static ref CODE_REGEX: Regex =
+        Regex::new(r#"(?i)(\b(?:code|c)"?\s*[:=]\s*(?:Some\()?"?)\d{4,8}"#).unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Pretty,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogConfig {
+    pub format: LogFormat,
+    pub level: Level,
+}
+
+impl LogConfig {
+    /// `LOG_FORMAT` is `json` or `pretty` (default), `LOG_LEVEL` one of
+    /// `trace`, `debug`, `info` (default), `warn` or `error`.
+    pub fn from_env() -> Self {
+        dotenv().ok();
+        let format = match env::var("LOG_FORMAT") {
+            Ok(value) if value.trim().eq_ignore_ascii_case("json") => LogFormat::Json,
+            _ => LogFormat::Pretty,
+        };
+        let level = env::var("LOG_LEVEL")
+            .ok()
+            .and_then(|value| Level::from_str(value.trim()).ok())
+            .unwrap_or(Level::INFO);
+
+        LogConfig { format, level }
+    }
+}
+
+/// Spans are also exported when a `tracer` is given, see `telemetry_utils`.
+pub fn init_logging(config: &LogConfig, tracer: Option<Tracer>) {
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        // Color codes would split the fields the redaction looks for.
+        .with_ansi(false)
+        .with_writer(RedactingStdout);
+    let fmt_layer = match config.format {
+        LogFormat::Json => fmt_layer.json().with_current_span(true).boxed(),
+        LogFormat::Pretty => fmt_layer.pretty().boxed(),
+    };
+    let otel_layer = tracer.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer));
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(otel_layer)
+        .with(LevelFilter::from_level(config.level))
+        .init();
+}
+
+/// Masks emails, phones and 2FA codes, the domain of an email and the last
+/// two digits of a phone are kept to help reading the logs.
+pub fn redact_pii(line: &str) -> String {
+    let line = EMAIL_REGEX.replace_all(line, "***@$1");
+    let line = PHONE_REGEX.replace_all(&line, "+***$1");
+    CODE_REGEX.replace_all(&line, "${1}***").into_owned()
+}
+
+/// Stdout writer of the subscriber. Events are formatted into a single buffer
+/// before being written, so each `write` sees whole fields.
+pub struct RedactingStdout;
+
+impl<'a> MakeWriter<'a> for RedactingStdout {
+    type Writer = RedactingWriter<io::Stdout>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(io::stdout())
+    }
+}
+
+pub struct RedactingWriter<W: io::Write>(W);
+
+impl<W: io::Write> io::Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match std::str::from_utf8(buf) {
+            Ok(line) => self.0.write_all(redact_pii(line).as_bytes())?,
+            Err(_) => self.0.write_all(buf)?,
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_pii_email() {
+        assert_eq!(
+            redact_pii("Cannot find user by email: test.pro@gmail.com"),
+            "Cannot find user by email: ***@gmail.com"
+        );
+    }
+
+    #[test]
+    fn test_redact_pii_phone() {
+        assert_eq!(
+            redact_pii(r#"{"phone":"+33612345678"}"#),
+            r#"{"phone":"+***78"}"#
+        );
+    }
+
+    #[test]
+    fn test_redact_pii_code() {
+        assert_eq!(redact_pii("code=123456"), "code=***");
+        assert_eq!(redact_pii(r#"{"code":"123456"}"#), r#"{"code":"***"}"#);
+        assert_eq!(redact_pii(r#"c: Some("123456"),"#), r#"c: Some("***"),"#);
+    }
+
+    #[test]
+    fn test_redact_pii_keeps_ids_and_dates() {
+        let line = r#"{"timestamp":"2024-03-26T10:00:00.000000Z","request_id":"00000000-0000-0000-0000-000000000001","code":"token-expired"}"#;
+
+        assert_eq!(redact_pii(line), line);
+    }
+}
@@ -0,0 +1,90 @@
+use dotenv::dotenv;
+use entity::entities::two_fa_entity::two_fa_model;
+use serde::{Deserialize, Serialize};
+use std::env;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoginPolicy {
+    pub require_verified_email: bool,
+    pub require_verified_phone: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UnverifiedContacts {
+    pub email: bool,
+    pub phone: bool,
+}
+
+impl LoginPolicy {
+    pub fn from_env() -> Self {
+        dotenv().ok();
+        LoginPolicy {
+            require_verified_email: env_flag("LOGIN_REQUIRE_VERIFIED_EMAIL"),
+            require_verified_phone: env_flag("LOGIN_REQUIRE_VERIFIED_PHONE"),
+        }
+    }
+
+    pub fn check(&self, two_fa: &two_fa_model::Model) -> Result<(), UnverifiedContacts> {
+        let unverified = UnverifiedContacts {
+            email: self.require_verified_email && !two_fa.email_verified,
+            phone: self.require_verified_phone && !two_fa.phone_verified,
+        };
+
+        if unverified.email || unverified.phone {
+            Err(unverified)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+pub fn env_flag(key: &str) -> bool {
+    match env::var(key) {
+        Ok(value) => value.trim().eq_ignore_ascii_case("true") || value.trim() == "1",
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_fa(email_verified: bool, phone_verified: bool) -> two_fa_model::Model {
+        two_fa_model::Model {
+            email_verified,
+            phone_verified,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_allows_everything_by_default() {
+        let policy = LoginPolicy::default();
+
+        assert_eq!(policy.check(&two_fa(false, false)), Ok(()));
+    }
+
+    #[test]
+    fn it_reports_unverified_contacts() {
+        let policy = LoginPolicy {
+            require_verified_email: true,
+            require_verified_phone: true,
+        };
+
+        assert_eq!(policy.check(&two_fa(true, true)), Ok(()));
+        assert_eq!(
+            policy.check(&two_fa(false, true)),
+            Err(UnverifiedContacts {
+                email: true,
+                phone: false,
+            })
+        );
+        assert_eq!(
+            policy.check(&two_fa(true, false)),
+            Err(UnverifiedContacts {
+                email: false,
+                phone: true,
+            })
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::env;
+
+use dotenv::dotenv;
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, Encoder, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, TextEncoder,
+};
+use sea_orm::DatabaseConnection;
+
+pub const EMAIL_CHANNEL: &str = "email";
+pub const SMS_CHANNEL: &str = "sms";
+pub const BREVO_PROVIDER: &str = "brevo";
+
+/// Route label of the requests no resource matched, keeps the label set bounded.
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+lazy_static! {
+    static ref HTTP_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "http_requests_total",
+        "HTTP requests by method, route and status.",
+        &["method", "route", "status"]
+    )
+    .unwrap();
+    static ref HTTP_REQUEST_DURATION: HistogramVec = register_histogram_vec!(
+        "http_request_duration_seconds",
+        "HTTP request latency by method, route and status.",
+        &["method", "route", "status"]
+    )
+    .unwrap();
+    static ref DB_POOL_CONNECTIONS: IntGaugeVec = register_int_gauge_vec!(
+        "db_pool_connections",
+        "Connections of the database pool by state.",
+        &["state"]
+    )
+    .unwrap();
+    static ref DB_POOL_MAX_CONNECTIONS: IntGauge = register_int_gauge!(
+        "db_pool_max_connections",
+        "Maximum size of the database pool."
+    )
+    .unwrap();
+    static ref DELIVERIES: IntCounterVec = register_int_counter_vec!(
+        "deliveries_total",
+        "Emails and SMS sent by channel, provider and outcome.",
+        &["channel", "provider", "outcome"]
+    )
+    .unwrap();
+    static ref TWO_FA_LOCKOUTS: IntCounter = register_int_counter!(
+        "two_fa_lockouts_total",
+        "Accounts locked after too many 2FA attempts."
+    )
+    .unwrap();
+    static ref RATE_LIMIT_REJECTIONS: IntCounterVec = register_int_counter_vec!(
+        "rate_limit_rejections_total",
+        "Requests answered 429 by route.",
+        &["route"]
+    )
+    .unwrap();
+}
+
+/// `/metrics` is only served when `METRICS_TOKEN` is set, scrapers send it
+/// as a bearer token.
+pub fn metrics_token_from_env() -> Option<String> {
+    dotenv().ok();
+    env::var("METRICS_TOKEN")
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+pub fn observe_http_request(method: &str, route: &str, status: u16, seconds: f64) {
+    let status = status.to_string();
+    HTTP_REQUESTS
+        .with_label_values(&[method, route, &status])
+        .inc();
+    HTTP_REQUEST_DURATION
+        .with_label_values(&[method, route, &status])
+        .observe(seconds);
+    if status == "429" {
+        RATE_LIMIT_REJECTIONS.with_label_values(&[route]).inc();
+    }
+}
+
+pub fn observe_delivery(channel: &str, provider: &str, sent: bool) {
+    let outcome = match sent {
+        true => "success",
+        false => "failure",
+    };
+    DELIVERIES
+        .with_label_values(&[channel, provider, outcome])
+        .inc();
+}
+
+pub fn observe_two_fa_lockout() {
+    TWO_FA_LOCKOUTS.inc();
+}
+
+/// Pool gauges are read at scrape time, only a Postgres pool has them.
+pub fn observe_db_pool(db: &DatabaseConnection) {
+    if let DatabaseConnection::SqlxPostgresPoolConnection(_) = db {
+        let pool = db.get_postgres_connection_pool();
+        let idle = pool.num_idle() as i64;
+        DB_POOL_CONNECTIONS.with_label_values(&["idle"]).set(idle);
+        DB_POOL_CONNECTIONS
+            .with_label_values(&["in_use"])
+            .set(pool.size() as i64 - idle);
+        DB_POOL_MAX_CONNECTIONS.set(pool.options().get_max_connections() as i64);
+    }
+}
+
+/// Every registered metric in the Prometheus text format.
+pub fn encode_metrics() -> (String, Vec<u8>) {
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    encoder.encode(&prometheus::gather(), &mut buffer).unwrap();
+    (encoder.format_type().to_string(), buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_metrics() {
+        observe_http_request("GET", "/account/me", 429, 0.01);
+        observe_delivery(SMS_CHANNEL, BREVO_PROVIDER, false);
+
+        let (content_type, body) = encode_metrics();
+        let body = String::from_utf8(body).unwrap();
+
+        assert!(content_type.starts_with("text/plain"));
+        assert!(
+            body.contains(r#"http_requests_total{method="GET",route="/account/me",status="429"}"#)
+        );
+        assert!(body.contains(r#"rate_limit_rejections_total{route="/account/me"}"#));
+        assert!(
+            body.contains(r#"deliveries_total{channel="sms",outcome="failure",provider="brevo"}"#)
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::env;
+
+use dotenv::dotenv;
+use migration::app_env::AppEnv;
+
+pub const MIGRATE_FLAG: &str = "--migrate";
+
+/// Whether the server applies the pending migrations before listening: with
+/// the `--migrate` flag, else from `MIGRATE_ON_START`, else only in development.
+/// Production deployments run the `migration` binary instead.
+pub fn migrate_on_start_from_env() -> bool {
+    dotenv().ok();
+    migrate_on_start(
+        env::args().skip(1),
+        env::var("MIGRATE_ON_START").ok(),
+        AppEnv::from_env(),
+    )
+}
+
+fn migrate_on_start(
+    mut args: impl Iterator<Item = String>,
+    setting: Option<String>,
+    app_env: AppEnv,
+) -> bool {
+    if args.any(|arg| arg == MIGRATE_FLAG) {
+        return true;
+    }
+
+    match setting.as_deref().map(str::trim) {
+        Some(value) if value.eq_ignore_ascii_case("true") || value == "1" => true,
+        Some(value) if value.eq_ignore_ascii_case("false") || value == "0" => false,
+        _ => app_env.is_development(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn it_defaults_to_off_in_production() {
+        assert!(!migrate_on_start(args(&[]), None, AppEnv::Production));
+        assert!(!migrate_on_start(
+            args(&[]),
+            Some("".to_string()),
+            AppEnv::Production
+        ));
+        assert!(migrate_on_start(args(&[]), None, AppEnv::Development));
+    }
+
+    #[test]
+    fn it_follows_the_setting() {
+        assert!(migrate_on_start(
+            args(&[]),
+            Some("true".to_string()),
+            AppEnv::Production
+        ));
+        assert!(!migrate_on_start(
+            args(&[]),
+            Some("0".to_string()),
+            AppEnv::Development
+        ));
+    }
+
+    #[test]
+    fn it_migrates_with_the_flag() {
+        assert!(migrate_on_start(
+            args(&["--migrate"]),
+            Some("false".to_string()),
+            AppEnv::Production
+        ));
+    }
+}
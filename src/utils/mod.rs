@@ -6,4 +6,20 @@ pub mod string;
 pub mod cookie_utils;
 pub mod validate_utils;
 pub mod task_manager_utils;
-pub mod number;
\ No newline at end of file
+pub mod number;
+pub mod ics_utils;
+pub mod phone_utils;
+pub mod login_policy_utils;
+pub mod image_utils;
+pub mod data_export_utils;
+pub mod account_deletion_utils;
+pub mod request_utils;
+pub mod consent_utils;
+pub mod auth_event_utils;
+pub mod device_utils;
+pub mod log_utils;
+pub mod metrics_utils;
+pub mod telemetry_utils;
+pub mod migration_utils;
+pub mod email_utils;
+pub mod csv_utils;
\ No newline at end of file
@@ -0,0 +1,113 @@
+use phonenumber::{country, Mode, PhoneNumber, Type};
+
+pub const DEFAULT_COUNTRY: &str = "FR";
+
+pub const TEST_PHONES: [&str; 5] = [
+    "+33600000001",
+    "+33600000002",
+    "+33600000003",
+    "+33600000004",
+    "+33600000005",
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PhoneError {
+    UnknownCountry,
+    Invalid,
+    /// A valid number that cannot receive the SMS codes, e.g. a landline.
+    NotMobile,
+}
+
+pub fn parse_phone(phone: &str, country: &str) -> Result<PhoneNumber, PhoneError> {
+    let country_id = country
+        .trim()
+        .to_uppercase()
+        .parse::<country::Id>()
+        .map_err(|_| PhoneError::UnknownCountry)?;
+    phonenumber::parse(Some(country_id), phone.trim()).map_err(|_| PhoneError::Invalid)
+}
+
+/// E.164 form of `phone`. French 09 numbers are VoIP lines, which receive
+/// SMS, so they are accepted like the mobiles.
+pub fn normalize_phone(phone: &str, country: &str) -> Result<String, PhoneError> {
+    let number = parse_phone(phone, country)?;
+
+    if !number.is_valid() {
+        return Err(PhoneError::Invalid);
+    }
+
+    match number.number_type(&phonenumber::metadata::DATABASE) {
+        Type::Mobile | Type::FixedLineOrMobile | Type::Voip => {
+            Ok(number.format().mode(Mode::E164).to_string())
+        }
+        _ => Err(PhoneError::NotMobile),
+    }
+}
+
+pub fn is_test_phone(phone: &str) -> bool {
+    match normalize_phone(phone, DEFAULT_COUNTRY) {
+        Ok(normalized) => TEST_PHONES.contains(&normalized.as_str()),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_normalizes_french_mobiles() {
+        assert_eq!(
+            normalize_phone("0612345678", "FR"),
+            Ok(String::from("+33612345678"))
+        );
+        assert_eq!(
+            normalize_phone("+33612345678", "FR"),
+            Ok(String::from("+33612345678"))
+        );
+        assert_eq!(
+            normalize_phone("06 12 34 56 78", "fr"),
+            Ok(String::from("+33612345678"))
+        );
+    }
+
+    #[test]
+    fn it_normalizes_international_mobiles() {
+        assert_eq!(
+            normalize_phone("0151 23456789", "DE"),
+            Ok(String::from("+4915123456789"))
+        );
+        assert_eq!(
+            normalize_phone("+34612345678", "FR"),
+            Ok(String::from("+34612345678"))
+        );
+    }
+
+    #[test]
+    fn it_normalizes_french_voip_numbers() {
+        assert_eq!(
+            normalize_phone("09 51 23 45 67", "FR"),
+            Ok(String::from("+33951234567"))
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_phones() {
+        assert_eq!(
+            normalize_phone("0102030405", "FR"),
+            Err(PhoneError::NotMobile)
+        );
+        assert_eq!(normalize_phone("0612", "FR"), Err(PhoneError::Invalid));
+        assert_eq!(
+            normalize_phone("0612345678", "ZZ"),
+            Err(PhoneError::UnknownCountry)
+        );
+    }
+
+    #[test]
+    fn it_detects_test_phones() {
+        assert!(is_test_phone("0600000001"));
+        assert!(is_test_phone("+33600000005"));
+        assert!(!is_test_phone("0600000006"));
+    }
+}
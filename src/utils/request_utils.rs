@@ -0,0 +1,32 @@
+use actix_web::{http::header, HttpRequest};
+
+/// Honors `Forwarded` and `X-Forwarded-For`, so the API must sit behind a
+/// proxy that overwrites them.
+pub fn client_ip(req: &HttpRequest) -> Option<String> {
+    req.connection_info().realip_remote_addr().map(String::from)
+}
+
+pub fn user_agent(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn it_reads_the_client_ip_and_user_agent() {
+        let req = test::TestRequest::default()
+            .insert_header(("X-Forwarded-For", "203.0.113.7"))
+            .insert_header((header::USER_AGENT, "Mozilla/5.0"))
+            .to_http_request();
+
+        assert_eq!(client_ip(&req), Some(String::from("203.0.113.7")));
+        assert_eq!(user_agent(&req), Some(String::from("Mozilla/5.0")));
+    }
+}
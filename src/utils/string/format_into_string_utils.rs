@@ -1,7 +1,11 @@
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{Datelike, TimeZone, Timelike, Utc, Weekday};
+use entity::entities::user_entity::user_model::Language;
+use fluent_bundle::FluentArgs;
 use serde_json::Value;
 use tracing::error;
 
+use crate::i18n::catalog::translate;
+
 pub fn format_custom_dberr(errors: String) -> String {
     let err = errors.split("Object").nth(1).unwrap().to_string();
     return err.replace("String(\"", "\"").replace("\")", "\"");
@@ -12,97 +16,56 @@ pub fn format_validation_error(error: &Value) -> String {
     return validation_error["code"].to_string().replace("\"", "");
 }
 
-pub fn format_timestamp_into_string_date(timestamp: i64) -> String {
+pub fn format_timestamp_into_string_date(timestamp: i64, lg: &Language) -> String {
     let dt = Utc.timestamp_opt(timestamp + 3_600, 0).unwrap();
 
-    let dt_string = dt.format("%A %e %B %Y").to_string();
-    let (wd_str, other_part) = dt_string.split_once(" ").unwrap();
-    let (d_str, last_part) = other_part.split_once(" ").unwrap();
-    let (m_str, y_str) = last_part.split_once(" ").unwrap();
-
-    let weekday_str = match wd_str {
-        "Monday" => "lundi",
-        "Tuesday" => "mardi",
-        "Wednesday" => "mercredi",
-        "Thursday" => "jeudi",
-        "Friday" => "vendredi",
-        "Saturday" => "samedi",
-        "Sunday" => "dimanche",
-        _ => "",
-    };
-
-    let day_str = match d_str {
-        "1" => "1er",
-        _ => d_str,
-    };
-
-    let month_str = match m_str {
-        "January" => "janvier",
-        "February" => "février",
-        "March" => "mars",
-        "April" => "avril",
-        "May" => "mai",
-        "June" => "juin",
-        "July" => "juillet",
-        "August" => "août",
-        "September" => "septembre",
-        "October" => "octobre",
-        "November" => "novembre",
-        "December" => "décembre",
-        _ => "",
-    };
-
-    return format!("{} {} {} {}", weekday_str, day_str, month_str, y_str);
+    let mut args = FluentArgs::new();
+    args.set(
+        "weekday",
+        translate(lg, &weekday_key(dt.weekday(), false), None),
+    );
+    args.set("day", dt.day());
+    args.set(
+        "month",
+        translate(lg, &format!("date-month-{}", dt.month()), None),
+    );
+    args.set("year", dt.year().to_string());
+
+    translate(lg, "date-long", Some(&args))
 }
 
-pub fn format_timestamp_into_string_times(timestamp: i32) -> String {
-    let dt = DateTime::<Utc>::from_utc(
-        chrono::NaiveDateTime::from_timestamp_opt(timestamp as i64, 0)
-            .unwrap_or_else(|| panic!("Failed to convert timestamp to NaiveDateTime")),
-        Utc,
-    );
+pub fn format_timestamp_into_string_times(timestamp: i32, lg: &Language) -> String {
+    let dt = Utc
+        .timestamp_opt(timestamp as i64, 0)
+        .single()
+        .unwrap_or_else(|| panic!("Failed to convert timestamp to DateTime"));
 
-    let dt_string = dt.format("%H:%M").to_string();
-    let (h_str, m_str) = dt_string.split_once(":").unwrap();
-
-    let hours_str = match h_str {
-        "00" => "0",
-        "01" => "1",
-        "02" => "2",
-        "03" => "3",
-        "04" => "4",
-        "05" => "5",
-        "06" => "6",
-        "07" => "7",
-        "08" => "8",
-        "09" => "9",
-        _ => h_str,
-    };
-
-    return format!("{}h{}", hours_str, m_str);
+    let mut args = FluentArgs::new();
+    args.set("hours", dt.hour().to_string());
+    args.set("minutes", format!("{:02}", dt.minute()));
+
+    translate(lg, "date-times", Some(&args))
 }
 
-pub fn format_timestamp_into_slash_date(timestamp: i64) -> String {
-    let dt = DateTime::<Utc>::from_utc(
-        chrono::NaiveDateTime::from_timestamp_opt(timestamp + 3_600, 0).unwrap(),
-        Utc,
+pub fn format_timestamp_into_slash_date(timestamp: i64, lg: &Language) -> String {
+    let dt = Utc.timestamp_opt(timestamp + 3_600, 0).unwrap();
+
+    let mut args = FluentArgs::new();
+    args.set(
+        "weekday",
+        translate(lg, &weekday_key(dt.weekday(), true), None),
     );
+    args.set("day", format!("{:02}", dt.day()));
+    args.set("month", format!("{:02}", dt.month()));
 
-    let dt_string = dt.format("%A %d/%m").to_string();
-    let (wd_str, other_part) = dt_string.split_once(" ").unwrap();
-
-    let weekday_str = match wd_str {
-        "Monday" => "lun",
-        "Tuesday" => "mar",
-        "Wednesday" => "mer",
-        "Thursday" => "jeu",
-        "Friday" => "ven",
-        "Saturday" => "sam",
-        "Sunday" => "dim",
-        _ => "",
-    };
-
-    return format!("{} {}", weekday_str, other_part);
+    translate(lg, "date-slash", Some(&args))
+}
+
+fn weekday_key(weekday: Weekday, short: bool) -> String {
+    match short {
+        true => format!("date-weekday-short-{}", weekday.number_from_monday()),
+        false => format!("date-weekday-{}", weekday.number_from_monday()),
+    }
 }
 
 #[cfg(test)]
@@ -113,7 +76,7 @@ mod tests {
     fn it_format_timestamp_into_string_date() {
         let expected: String = String::from("jeudi 21 décembre 2023");
         let payload_in_sec = 1_703_113_200;
-        let formatted = format_timestamp_into_string_date(payload_in_sec);
+        let formatted = format_timestamp_into_string_date(payload_in_sec, &Language::Fr);
         assert_eq!(expected, formatted);
     }
 
@@ -121,7 +84,7 @@ mod tests {
     fn it_format_timestamp_into_slash_date() {
         let expected: String = String::from("jeu 21/12");
         let payload_in_sec = 1_703_113_200;
-        let formatted = format_timestamp_into_slash_date(payload_in_sec);
+        let formatted = format_timestamp_into_slash_date(payload_in_sec, &Language::Fr);
         assert_eq!(&expected, &formatted);
     }
 
@@ -129,7 +92,36 @@ mod tests {
     fn it_format_timestamp_into_string_times() {
         let expected: String = String::from("9h00");
         let payload_in_sec = 32_400;
-        let formatted = format_timestamp_into_string_times(payload_in_sec);
+        let formatted = format_timestamp_into_string_times(payload_in_sec, &Language::Fr);
         assert_eq!(&expected, &formatted);
     }
+
+    #[test]
+    fn it_format_timestamp_into_localized_dates() {
+        let payload_in_sec = 1_703_113_200;
+
+        assert_eq!(
+            format_timestamp_into_string_date(payload_in_sec, &Language::En),
+            "Thursday, December 21, 2023"
+        );
+        assert_eq!(
+            format_timestamp_into_string_date(payload_in_sec, &Language::De),
+            "Donnerstag, 21. Dezember 2023"
+        );
+        assert_eq!(
+            format_timestamp_into_slash_date(payload_in_sec, &Language::En),
+            "Thu 12/21"
+        );
+        assert_eq!(
+            format_timestamp_into_string_times(32_400, &Language::En),
+            "9:00"
+        );
+    }
+
+    #[test]
+    fn it_format_first_day_of_month_in_french() {
+        let payload_in_sec = 1_704_063_600;
+        let formatted = format_timestamp_into_string_date(payload_in_sec, &Language::Fr);
+        assert_eq!(formatted, "lundi 1er janvier 2024");
+    }
 }
@@ -1,16 +1,51 @@
 use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
+use std::env;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use tokio::task::JoinHandle;
+use std::time::Duration;
 
+use dotenv::dotenv;
+use tokio::runtime::Handle;
+use tokio::sync::watch;
+use tokio::task::{AbortHandle, JoinHandle};
+use tokio::time::{self, Instant};
+
+pub const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+
+/// Time given at shutdown to the in-flight requests, then to the background
+/// jobs, from `SHUTDOWN_TIMEOUT_SECS`.
+pub fn shutdown_timeout_from_env() -> Duration {
+    dotenv().ok();
+    let secs = env::var("SHUTDOWN_TIMEOUT_SECS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS);
+
+    Duration::from_secs(secs)
+}
+
+/// Owns the work running outside of the requests: scheduled tasks added by
+/// id, one-off jobs (emails, exports) and the background worker loops.
 pub struct TaskManager {
     tasks: Arc<Mutex<HashMap<i64, JoinHandle<()>, RandomState>>>,
+    jobs: Arc<Mutex<HashMap<u64, JoinHandle<()>>>>,
+    next_job_id: AtomicU64,
+    runtime: Handle,
+    shutdown: watch::Sender<bool>,
 }
 
 impl TaskManager {
+    /// Jobs run on the runtime of the caller, create it from `main` so they
+    /// outlive the HTTP workers stopped at shutdown.
     pub fn new() -> Self {
         TaskManager {
             tasks: Arc::new(Mutex::new(HashMap::new())),
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            next_job_id: AtomicU64::new(0),
+            runtime: Handle::current(),
+            shutdown: watch::channel(false).0,
         }
     }
 
@@ -41,4 +76,201 @@ impl TaskManager {
             Err("Task not found".to_string())
         }
     }
+
+    /// Runs `job` in the background, `shutdown` waits for it.
+    pub fn spawn_job<F>(&self, job: F) -> AbortHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let job_id = self.next_job_id.fetch_add(1, Ordering::Relaxed);
+        let jobs = self.jobs.clone();
+        // Held until the job is registered, so it cannot unregister before.
+        let mut running = self.jobs.lock().unwrap();
+
+        let handle = self.runtime.spawn(async move {
+            job.await;
+            if let Ok(mut jobs) = jobs.lock() {
+                jobs.remove(&job_id);
+            }
+        });
+        let abort_handle = handle.abort_handle();
+        running.insert(job_id, handle);
+
+        abort_handle
+    }
+
+    /// Flips to `true` when the shutdown starts. Worker loops spawned with
+    /// `spawn_job` wait on it between two runs and return.
+    pub fn shutdown_signal(&self) -> watch::Receiver<bool> {
+        self.shutdown.subscribe()
+    }
+
+    /// Signals the workers, aborts the scheduled tasks and waits up to
+    /// `timeout` for the running jobs. Returns how many jobs were aborted.
+    pub async fn shutdown(&self, timeout: Duration) -> usize {
+        self.shutdown.send_replace(true);
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.drain().for_each(|(_, task)| task.abort());
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut aborted = 0;
+        loop {
+            // Jobs spawned meanwhile are picked by the next round.
+            let running: Vec<JoinHandle<()>> = match self.jobs.lock() {
+                Ok(mut jobs) => jobs.drain().map(|(_, job)| job).collect(),
+                Err(_) => return aborted,
+            };
+            if running.is_empty() {
+                return aborted;
+            }
+
+            for mut job in running {
+                if time::timeout_at(deadline, &mut job).await.is_err() {
+                    job.abort();
+                    aborted += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use actix_web::{web, App, HttpResponse, HttpServer};
+
+    use super::*;
+
+    type Done = web::Data<AtomicUsize>;
+
+    async fn start_job(tasks: web::Data<TaskManager>, done: Done) -> HttpResponse {
+        tasks.spawn_job(async move {
+            time::sleep(Duration::from_millis(100)).await;
+            done.fetch_add(1, Ordering::SeqCst);
+        });
+        HttpResponse::Accepted().finish()
+    }
+
+    #[actix_web::test]
+    async fn it_waits_for_running_jobs() {
+        let tasks = TaskManager::new();
+        let done = Arc::new(AtomicUsize::new(0));
+
+        for delay in [10, 50, 100] {
+            let done = done.clone();
+            tasks.spawn_job(async move {
+                time::sleep(Duration::from_millis(delay)).await;
+                done.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+
+        assert_eq!(tasks.shutdown(Duration::from_secs(5)).await, 0);
+        assert_eq!(done.load(Ordering::SeqCst), 3);
+    }
+
+    #[actix_web::test]
+    async fn it_waits_for_jobs_spawned_by_a_running_job() {
+        let tasks = Arc::new(TaskManager::new());
+        let done = Arc::new(AtomicUsize::new(0));
+
+        let spawner = tasks.clone();
+        let spawned_done = done.clone();
+        tasks.spawn_job(async move {
+            time::sleep(Duration::from_millis(10)).await;
+            spawner.spawn_job(async move {
+                time::sleep(Duration::from_millis(50)).await;
+                spawned_done.fetch_add(1, Ordering::SeqCst);
+            });
+        });
+
+        assert_eq!(tasks.shutdown(Duration::from_secs(5)).await, 0);
+        assert_eq!(done.load(Ordering::SeqCst), 1);
+    }
+
+    #[actix_web::test]
+    async fn it_keeps_jobs_of_a_stopped_server() {
+        let tasks = web::Data::new(TaskManager::new());
+        let done: Done = web::Data::new(AtomicUsize::new(0));
+
+        let server_tasks = tasks.clone();
+        let server_done = done.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(server_tasks.clone())
+                .app_data(server_done.clone())
+                .route("/jobs", web::post().to(start_job))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let addr = server.addrs()[0];
+        let server = server.run();
+        let server_handle = server.handle();
+        actix_web::rt::spawn(server);
+
+        let resp = reqwest::Client::new()
+            .post(format!("http://{}/jobs", addr))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 202);
+
+        // The worker thread and its runtime are gone before the job ends.
+        server_handle.stop(true).await;
+
+        assert_eq!(tasks.shutdown(Duration::from_secs(5)).await, 0);
+        assert_eq!(done.load(Ordering::SeqCst), 1);
+    }
+
+    #[actix_web::test]
+    async fn it_aborts_jobs_past_the_timeout() {
+        let tasks = TaskManager::new();
+        let job = tasks.spawn_job(time::sleep(Duration::from_secs(60)));
+
+        assert_eq!(tasks.shutdown(Duration::from_millis(20)).await, 1);
+        time::sleep(Duration::from_millis(10)).await;
+        assert!(job.is_finished());
+    }
+
+    #[actix_web::test]
+    async fn it_lets_workers_finish_their_run() {
+        let tasks = TaskManager::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let mut shutdown = tasks.shutdown_signal();
+        let worker_runs = runs.clone();
+        let worker = tasks.spawn_job(async move {
+            let mut interval = time::interval(Duration::from_millis(10));
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = shutdown.wait_for(|stopping| *stopping) => break,
+                    _ = interval.tick() => {}
+                }
+                time::sleep(Duration::from_millis(30)).await;
+                worker_runs.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        // Lands in the middle of the first run.
+        time::sleep(Duration::from_millis(15)).await;
+
+        assert_eq!(tasks.shutdown(Duration::from_secs(5)).await, 0);
+        assert!(worker.is_finished());
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[actix_web::test]
+    async fn it_aborts_scheduled_tasks() {
+        let tasks = TaskManager::new();
+        let task = actix_web::rt::spawn(time::sleep(Duration::from_secs(60)));
+        let abort_handle = task.abort_handle();
+        tasks.add_task(1, task).unwrap();
+
+        tasks.shutdown(Duration::from_secs(1)).await;
+        time::sleep(Duration::from_millis(10)).await;
+
+        assert!(abort_handle.is_finished());
+    }
 }
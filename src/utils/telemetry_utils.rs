@@ -0,0 +1,246 @@
+use std::env;
+
+use actix_web::http::header::HeaderMap;
+use dotenv::dotenv;
+use futures_util::future::BoxFuture;
+use opentelemetry::{
+    global,
+    propagation::{Extractor, TextMapPropagator},
+    trace::{Event, Status, TraceError, TracerProvider as _},
+    Context, KeyValue, Value,
+};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    export::trace::{ExportResult, SpanData, SpanExporter},
+    propagation::TraceContextPropagator,
+    runtime,
+    trace::{self, EvictedQueue, Tracer},
+    Resource,
+};
+use tracing::{info_span, Span};
+
+use super::log_utils::redact_pii;
+
+const DEFAULT_SERVICE_NAME: &str = "actix_postgres_api_model";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TelemetryConfig {
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+impl TelemetryConfig {
+    /// Traces are exported only when `OTEL_EXPORTER_OTLP_ENDPOINT` is set, e.g.
+    /// `http://localhost:4318` for a local collector. `OTEL_SERVICE_NAME`
+    /// defaults to the crate name.
+    pub fn from_env() -> Option<Self> {
+        dotenv().ok();
+        let endpoint = env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+            .ok()
+            .map(|endpoint| endpoint.trim().trim_end_matches('/').to_string())
+            .filter(|endpoint| !endpoint.is_empty())?;
+        let service_name = env::var("OTEL_SERVICE_NAME")
+            .ok()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| String::from(DEFAULT_SERVICE_NAME));
+
+        Some(TelemetryConfig {
+            endpoint,
+            service_name,
+        })
+    }
+}
+
+/// Installs the global OTLP/HTTP tracer, spans are batched and exported from
+/// a thread of their own so the actix runtime never waits on the collector.
+pub fn init_tracer(config: &TelemetryConfig) -> Result<Tracer, TraceError> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let exporter = opentelemetry_otlp::new_exporter()
+        .http()
+        .with_endpoint(&config.endpoint)
+        .build_span_exporter()?;
+    let provider = trace::TracerProvider::builder()
+        .with_batch_exporter(RedactingExporter(exporter), runtime::TokioCurrentThread)
+        .with_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                config.service_name.to_owned(),
+            )])),
+        )
+        .build();
+    let tracer = provider.tracer(DEFAULT_SERVICE_NAME);
+    global::set_tracer_provider(provider);
+
+    Ok(tracer)
+}
+
+/// Flushes the spans still in the batch, to call once the server stopped.
+pub fn shutdown_tracer() {
+    global::shutdown_tracer_provider();
+}
+
+/// Remote parent of a request, read from its W3C `traceparent` header.
+pub fn parent_context(headers: &HeaderMap) -> Context {
+    TraceContextPropagator::new().extract(&HeaderExtractor(headers))
+}
+
+/// Client span of an outbound call to the Brevo API.
+pub fn brevo_span(endpoint: &str) -> Span {
+    info_span!(
+        "brevo",
+        otel.name = %format!("POST {}", endpoint),
+        otel.kind = "client",
+        peer.service = "brevo",
+        http.method = "POST",
+        http.url = %format!("https://api.brevo.com{}", endpoint),
+    )
+}
+
+/// Masks the PII of the spans before they leave the process, the same way the
+/// stdout logs are, since event messages and fields are exported as they are.
+#[derive(Debug)]
+pub struct RedactingExporter<E: SpanExporter>(pub E);
+
+impl<E: SpanExporter> SpanExporter for RedactingExporter<E> {
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+        self.0.export(batch.into_iter().map(redact_span).collect())
+    }
+
+    fn shutdown(&mut self) {
+        self.0.shutdown()
+    }
+
+    fn force_flush(&mut self) -> BoxFuture<'static, ExportResult> {
+        self.0.force_flush()
+    }
+}
+
+fn redact_span(mut span: SpanData) -> SpanData {
+    span.attributes = redact_attributes(span.attributes);
+    if let Status::Error { description } = &span.status {
+        span.status = Status::error(redact_pii(description));
+    }
+    let events = std::mem::replace(&mut span.events, EvictedQueue::new(0));
+    let mut redacted = EvictedQueue::new(events.len() as u32);
+    redacted.extend(events.into_iter().map(|event| {
+        Event::new(
+            redact_pii(&event.name),
+            event.timestamp,
+            redact_attributes(event.attributes),
+            event.dropped_attributes_count,
+        )
+    }));
+    span.events = redacted;
+    span
+}
+
+fn redact_attributes(attributes: Vec<KeyValue>) -> Vec<KeyValue> {
+    attributes
+        .into_iter()
+        .map(|attribute| match &attribute.value {
+            Value::String(value) => KeyValue::new(attribute.key, redact_pii(value.as_str())),
+            _ => attribute,
+        })
+        .collect()
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl<'a> Extractor for HeaderExtractor<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::header::{HeaderName, HeaderValue};
+    use opentelemetry::trace::{SpanId, TraceContextExt, TraceId, TracerProvider as _};
+    use opentelemetry_sdk::{testing::trace::InMemorySpanExporter, trace::TracerProvider};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn test_request_span_continues_traceparent() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.to_owned())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            HeaderName::from_static("traceparent"),
+            HeaderValue::from_static(TRACEPARENT),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            let request = info_span!("request");
+            request.set_parent(parent_context(&headers));
+            request.in_scope(|| brevo_span("/v3/smtp/email").in_scope(|| {}));
+        });
+        provider.force_flush();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let brevo = spans.iter().find(|span| span.name == "POST /v3/smtp/email");
+        let request = spans.iter().find(|span| span.name == "request");
+        let (brevo, request) = (brevo.unwrap(), request.unwrap());
+
+        assert_eq!(
+            request.span_context.trace_id(),
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap()
+        );
+        assert_eq!(
+            request.parent_span_id,
+            SpanId::from_hex("00f067aa0ba902b7").unwrap()
+        );
+        assert_eq!(
+            brevo.span_context.trace_id(),
+            request.span_context.trace_id()
+        );
+        assert_eq!(brevo.parent_span_id, request.span_context.span_id());
+    }
+
+    #[test]
+    fn test_exported_events_are_redacted() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(RedactingExporter(exporter.to_owned()))
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            info_span!("request", phone = "+33612345678").in_scope(|| {
+                tracing::warn!("Cannot find user by email: {}", "test.pro@gmail.com");
+            });
+        });
+        provider.force_flush();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let request = spans.iter().find(|span| span.name == "request").unwrap();
+        let event = request.events.iter().next().unwrap();
+
+        assert_eq!(event.name, "Cannot find user by email: ***@gmail.com");
+        assert!(request
+            .attributes
+            .contains(&KeyValue::new("phone", "+***78")));
+    }
+
+    #[test]
+    fn test_parent_context_without_traceparent() {
+        let context = parent_context(&HeaderMap::new());
+
+        assert!(!context.span().span_context().is_valid());
+    }
+}
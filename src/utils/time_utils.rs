@@ -6,3 +6,4 @@ pub const MAX_AGE_5M_MILLI: i64 = 300_000;
 pub const MAX_AGE_1H_TEST: i64 = 3_600;
 pub const MAX_AGE_2J: i64 = 172_800;
 pub const MAX_AGE_7J: i64 = 604_800;
+pub const MAX_AGE_10Y: i64 = 315_360_000;
@@ -9,6 +9,7 @@ use service::mutation::two_fa_mutations::TwoFaMutation;
 use tracing::error;
 
 use crate::sms::send_auth_code_sms::{send_auth_code_sms, AuthCodeSmsData};
+use crate::utils::metrics_utils::observe_two_fa_lockout;
 use rand::Rng;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,6 +49,18 @@ pub trait TwoFactorsAuth {
     async fn send_code_to_pro(&self, user: UserModel, code: &String) -> SendingState;
     async fn reset_validation_system(&self, db: &Data<DatabaseConnection>);
     async fn reset_tries(&self, db: &Data<DatabaseConnection>) -> Result<(), DbErr>;
+    async fn verify_email(
+        &self,
+        db: &Data<DatabaseConnection>,
+    ) -> Result<two_fa_model::Model, DbErr>;
+    async fn verify_phone(
+        &self,
+        db: &Data<DatabaseConnection>,
+    ) -> Result<two_fa_model::Model, DbErr>;
+    async fn verify_new_phone(
+        &self,
+        db: &Data<DatabaseConnection>,
+    ) -> Result<two_fa_model::Model, DbErr>;
     async fn update_pro_by_remove_one_try(&self, db: &Data<DatabaseConnection>) -> i32;
     async fn update_pro_with_new_deadline(&self, db: &Data<DatabaseConnection>) -> i64;
     async fn update_two_fa_with_new_code(&self, code: &String, db: &Data<DatabaseConnection>);
@@ -59,19 +72,19 @@ pub trait TwoFactorsAuth {
 #[async_trait]
 impl TwoFactorsAuth for two_fa_model::Model {
     fn get_number_of_sending(&self) -> i32 {
-        return self.s;
+        self.codes_sent
     }
 
     fn get_tries(&self) -> i32 {
-        return self.t;
+        self.tries_left
     }
 
     fn get_exponent(&self) -> i32 {
-        return self.ex;
+        self.lock_exponent
     }
 
     fn get_deadline(&self) -> Option<i64> {
-        return self.up;
+        self.locked_until
     }
 
     fn generate_code(&self) -> String {
@@ -82,7 +95,7 @@ impl TwoFactorsAuth for two_fa_model::Model {
 
     fn check_code(&self, code: &String) -> RespCheckCode {
         RespCheckCode {
-            valid: self.c.as_ref().unwrap() == code,
+            valid: self.code.as_ref().unwrap() == code,
         }
     }
 
@@ -107,9 +120,10 @@ impl TwoFactorsAuth for two_fa_model::Model {
             return SendingState::AlreadySent;
         } else {
             let data = AuthCodeSmsData {
-                phone: user.ph.to_string(),
-                first_name: user.f.to_string(),
+                phone: user.phone.to_string(),
+                first_name: user.first_name.to_string(),
                 code: code.to_string(),
+                lg: user.language,
             };
             match send_auth_code_sms(data).await {
                 Ok(_) => return SendingState::Sent,
@@ -121,13 +135,13 @@ impl TwoFactorsAuth for two_fa_model::Model {
     async fn reset_validation_system(&self, db: &Data<DatabaseConnection>) {
         let two_fa = two_fa_model::ActiveModel {
             id: Set(self.id.to_owned()),
-            v_e: Set(false),
-            t: Set(3),
-            s: Set(0),
-            c: Set(None),
-            up: Set(None),
-            ex: Set(0),
-            v_ph: Set(false),
+            email_verified: Set(false),
+            tries_left: Set(3),
+            codes_sent: Set(0),
+            code: Set(None),
+            locked_until: Set(None),
+            lock_exponent: Set(0),
+            phone_verified: Set(false),
             user_id: Set(self.user_id.to_owned()),
         };
 
@@ -144,8 +158,8 @@ impl TwoFactorsAuth for two_fa_model::Model {
             return Ok(());
         } else {
             let mut two_fa: two_fa_model::ActiveModel = self.to_owned().into();
-            two_fa.t = Set(3);
-            two_fa.s = Set(0);
+            two_fa.tries_left = Set(3);
+            two_fa.codes_sent = Set(0);
 
             match TwoFaMutation::update_two_fa(db, two_fa).await {
                 Ok(_) => return Ok(()),
@@ -157,10 +171,69 @@ impl TwoFactorsAuth for two_fa_model::Model {
         }
     }
 
+    async fn verify_email(
+        &self,
+        db: &Data<DatabaseConnection>,
+    ) -> Result<two_fa_model::Model, DbErr> {
+        if self.email_verified {
+            return Ok(self.to_owned());
+        }
+
+        let mut two_fa: two_fa_model::ActiveModel = self.to_owned().into();
+        two_fa.email_verified = Set(true);
+
+        match TwoFaMutation::update_two_fa(db, two_fa).await {
+            Ok(t) => return Ok(t),
+            Err(err) => {
+                error!("Failed to verify email: {}", err);
+                return Err(err);
+            }
+        }
+    }
+
+    async fn verify_phone(
+        &self,
+        db: &Data<DatabaseConnection>,
+    ) -> Result<two_fa_model::Model, DbErr> {
+        if self.phone_verified {
+            return Ok(self.to_owned());
+        }
+
+        let mut two_fa: two_fa_model::ActiveModel = self.to_owned().into();
+        two_fa.phone_verified = Set(true);
+
+        match TwoFaMutation::update_two_fa(db, two_fa).await {
+            Ok(t) => return Ok(t),
+            Err(err) => {
+                error!("Failed to verify phone: {}", err);
+                return Err(err);
+            }
+        }
+    }
+
+    /// A confirmed phone change also gives back the tries and codes it used.
+    async fn verify_new_phone(
+        &self,
+        db: &Data<DatabaseConnection>,
+    ) -> Result<two_fa_model::Model, DbErr> {
+        let mut two_fa: two_fa_model::ActiveModel = self.to_owned().into();
+        two_fa.phone_verified = Set(true);
+        two_fa.tries_left = Set(3);
+        two_fa.codes_sent = Set(0);
+
+        match TwoFaMutation::update_two_fa(db, two_fa).await {
+            Ok(t) => Ok(t),
+            Err(err) => {
+                error!("Failed to verify new phone: {}", err);
+                Err(err)
+            }
+        }
+    }
+
     async fn update_pro_by_remove_one_try(&self, db: &Data<DatabaseConnection>) -> i32 {
         let tries = self.get_tries() - 1;
         let mut two_fa: two_fa_model::ActiveModel = self.clone().into();
-        two_fa.t = Set(tries);
+        two_fa.tries_left = Set(tries);
 
         match TwoFaMutation::update_two_fa(db, two_fa).await {
             Ok(_) => (),
@@ -174,7 +247,7 @@ impl TwoFactorsAuth for two_fa_model::Model {
 
     async fn update_two_fa_with_new_code(&self, code: &String, db: &Data<DatabaseConnection>) {
         let mut two_fa: two_fa_model::ActiveModel = self.clone().into();
-        two_fa.c = Set(Some(code.to_owned()));
+        two_fa.code = Set(Some(code.to_owned()));
 
         match TwoFaMutation::update_two_fa(db, two_fa).await {
             Ok(_) => (),
@@ -187,7 +260,7 @@ impl TwoFactorsAuth for two_fa_model::Model {
     async fn update_two_fa_with_new_num_of_sending(&self, db: &Data<DatabaseConnection>) -> i32 {
         let num_of_sending = self.get_number_of_sending() + 1;
         let mut two_fa: two_fa_model::ActiveModel = self.clone().into();
-        two_fa.s = Set(num_of_sending);
+        two_fa.codes_sent = Set(num_of_sending);
 
         match TwoFaMutation::update_two_fa(db, two_fa).await {
             Ok(_) => (),
@@ -201,6 +274,7 @@ impl TwoFactorsAuth for two_fa_model::Model {
 
     async fn block_account<'a>(&'a self, db: &'a Data<DatabaseConnection>) -> i64 {
         let deadline = self.update_pro_with_new_deadline(db).await;
+        observe_two_fa_lockout();
         let now = Utc::now().timestamp_millis() as i64;
         return deadline - now;
     }
@@ -209,8 +283,8 @@ impl TwoFactorsAuth for two_fa_model::Model {
         let new_exponent = self.get_exponent() + 1;
         let new_deadline = self.new_deadline(new_exponent);
         let mut two_fa: two_fa_model::ActiveModel = self.clone().into();
-        two_fa.ex = Set(new_exponent);
-        two_fa.up = Set(Some(new_deadline));
+        two_fa.lock_exponent = Set(new_exponent);
+        two_fa.locked_until = Set(Some(new_deadline));
 
         match TwoFaMutation::update_two_fa(db, two_fa).await {
             Ok(_) => (),